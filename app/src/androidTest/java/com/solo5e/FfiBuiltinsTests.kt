@@ -44,4 +44,20 @@ class FfiBuiltinsTests {
         val r = root.getJSONObject("result")
         assertTrue(r.getInt("rounds") > 0)
     }
+
+    @Test
+    fun campaign_steps_with_builtins() {
+        val first = JSONObject(Ffi.campaignStepJson("""{ "campaign_id": "goblin_warrens", "seed": 3 }"""))
+        assertTrue(first.getBoolean("ok"))
+        val state = first.getJSONObject("result").getJSONObject("state")
+        assertEquals(1, state.getInt("steps"))
+
+        val next = JSONObject()
+            .put("campaign_id", "goblin_warrens")
+            .put("seed", 3)
+            .put("state", state)
+        val second = JSONObject(Ffi.campaignStepJson(next.toString()))
+        assertTrue(second.getBoolean("ok"))
+        assertEquals(2, second.getJSONObject("result").getJSONObject("state").getInt("steps"))
+    }
 }
@@ -8,4 +8,6 @@ object Ffi {
     external fun simulateDuelJson(json: String): String
     external fun simulateDuelManyJson(json: String): String
     external fun simulateEncounterJson(json: String): String
+    external fun campaignStepJson(json: String): String
+    external fun campaignRunJson(json: String): String
 }
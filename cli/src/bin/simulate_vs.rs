@@ -1,8 +1,10 @@
+use anyhow::Context;
 use clap::Parser;
 use encoding_rs::Encoding;
-use engine::{Ability, AbilityScores, Actor, AdMode, Dice, Skill};
-use serde::Deserialize;
-use std::{collections::HashSet, fs, path::PathBuf};
+use engine::character::sample_fighter;
+use engine::content::{parse_target, parse_weapons, TargetData};
+use engine::{Ability, Actor, AdMode, Dice};
+use std::{fs, path::PathBuf};
 
 #[derive(Parser)]
 #[command(name = "simulate-vs")]
@@ -53,13 +55,6 @@ struct Args {
     file: Option<PathBuf>,
 }
 
-#[derive(Deserialize, Clone)]
-struct Target {
-    name: String,
-    ac: i32,
-    hp: i32,
-}
-
 #[derive(Copy, Clone)]
 struct WeaponPreset {
     name: &'static str,
@@ -144,45 +139,20 @@ fn read_text_auto(path: &std::path::Path) -> anyhow::Result<String> {
     }
 }
 
-fn read_target_auto(path: &std::path::Path) -> anyhow::Result<Target> {
+fn read_target_auto(path: &std::path::Path) -> anyhow::Result<TargetData> {
     let text = read_text_auto(path)?;
-    Ok(serde_json::from_str(&text)?)
+    parse_target(&text).with_context(|| format!("failed to load {}", path.display()))
 }
 
 fn load_weapons_file(path: &std::path::Path) -> anyhow::Result<Vec<engine::Weapon>> {
     let text = read_text_auto(path)?;
-    let v: Vec<engine::Weapon> = serde_json::from_str(&text)?;
-    Ok(v)
+    Ok(parse_weapons(&text)?)
 }
 
 fn find_weapon_in<'a>(name: &str, list: &'a [engine::Weapon]) -> Option<&'a engine::Weapon> {
     list.iter().find(|w| w.name.eq_ignore_ascii_case(name))
 }
 
-fn sample_fighter() -> Actor {
-    // same as main.rs sample
-    let abilities = AbilityScores {
-        str_: 16,
-        dex: 14,
-        con: 14,
-        int_: 10,
-        wis: 12,
-        cha: 8,
-    };
-    let mut save = HashSet::new();
-    save.insert(Ability::Str);
-    save.insert(Ability::Con);
-    let mut skills = HashSet::new();
-    skills.insert(Skill::Athletics);
-    skills.insert(Skill::Perception);
-    Actor {
-        abilities,
-        proficiency_bonus: 2,
-        save_proficiencies: save,
-        skill_proficiencies: skills,
-    }
-}
-
 fn pick_ability(choice: &str, finesse: bool, ranged: bool) -> Ability {
     match choice.to_lowercase().as_str() {
         "str" => Ability::Str,
@@ -205,7 +175,7 @@ fn main() -> anyhow::Result<()> {
         let text = read_text_auto(path)?;
         serde_json::from_str::<Actor>(&text)?
     } else {
-        sample_fighter()
+        sample_fighter().actor
     };
 
     // Target
@@ -213,7 +183,7 @@ fn main() -> anyhow::Result<()> {
 
     // Weapons file (optional) or default, else built-ins
     let loaded: Option<Vec<engine::Weapon>> = if let Some(ref p) = args.weapons {
-        load_weapons_file(p).ok()
+        Some(load_weapons_file(p)?)
     } else {
         let default = std::path::Path::new("content/weapons/basic.json");
         load_weapons_file(default).ok()
@@ -1,13 +1,29 @@
+use anyhow::Context;
 use clap::{Parser, Subcommand, ValueEnum};
 use encoding_rs::Encoding;
-use engine::conditions::{
-    maybe_apply_on_hit_condition, process_turn_boundary, vantage_from_conditions, ActiveCondition,
-    AttackStyle, ConditionKind, ConditionSpec, TurnBoundary, Vantage,
+use engine::api::{run_campaign, step_campaign, CampaignConfig};
+use engine::campaign::{CampaignState, CampaignStatus};
+use engine::character::{
+    create_character, sample_fighter, AbilityMethod, Background, Character, CharacterSpec, Class,
+    HpMethod, Species,
 };
-use engine::life::{apply_damage, heal, process_death_save_start_of_turn, Health, LifeState};
-use engine::{Ability, AbilityScores, Actor, AdMode, Cover, Dice, Skill};
-use serde::Deserialize;
-use std::{collections::HashSet, fs, path::PathBuf};
+use engine::checks::{contest, group_check, passive_score, Contestant};
+use engine::combat::{run_combat, AttackProfile, CombatOptions, CombatOutcome, Combatant, Side};
+use engine::conditions::{ActiveCondition, ConditionKind};
+use engine::content::{
+    builtin_effects, builtin_items, parse_content, parse_effects, parse_encounter, parse_items,
+    parse_target, parse_weapons, DownedPolicy, EncounterData, Focus, TargetData,
+};
+use engine::effects::{resolve_effects, ActiveEffect};
+use engine::explain::VantageBreakdown;
+use engine::features::{FightingStyle, Subclass, Tactics};
+use engine::items::{find_item, resolve_inventory, InventoryEntry, ItemDef, ItemStack};
+use engine::life::{Health, ShortRestPolicy};
+use engine::validate::{validate_content_dir, validate_file, ContentKind};
+use engine::{
+    Ability, AbilityScores, Actor, AdMode, AttackSource, Cover, CritRule, Dice, Material, Skill,
+};
+use std::{fs, path::PathBuf};
 
 #[derive(Copy, Clone, ValueEnum)]
 enum Adv {
@@ -58,219 +74,175 @@ impl CoverArg {
     }
 }
 
-#[derive(Deserialize, Clone)]
-struct TargetAttack {
-    name: String,
-    #[serde(rename = "to_hit")]
-    to_hit: i32,
-    dice: engine::DamageDice,
-    #[serde(default)]
-    damage_type: Option<engine::DamageType>,
-    #[serde(default)]
-    ranged: bool,
-    #[serde(default)]
-    apply_condition: Option<ConditionSpec>,
-}
-
-#[derive(Deserialize, Clone)]
-struct Target {
-    name: String,
-    ac: i32,
-    hp: i32,
-    #[serde(default)]
-    dex_mod: i32,
-    #[serde(default)]
-    abilities: Option<AbilityScores>,
-    #[serde(default)]
-    attacks: Vec<TargetAttack>,
-    #[serde(default)]
-    resistances: Vec<String>,
-    #[serde(default)]
-    vulnerabilities: Vec<String>,
-    #[serde(default)]
-    immunities: Vec<String>,
-    #[serde(default)]
-    conditions: Vec<ConditionKind>,
-    #[serde(default)]
-    cover: Cover,
+#[derive(Copy, Clone, ValueEnum)]
+enum KindArg {
+    Actor,
+    Target,
+    Encounter,
+    Weapons,
+    Items,
+    Effects,
 }
 
-impl Target {
-    fn ability_mod(&self, ability: Ability) -> i32 {
-        if let Some(ref scores) = self.abilities {
-            scores.mod_of(ability)
-        } else if ability == Ability::Dex {
-            self.dex_mod
-        } else {
-            0
+impl KindArg {
+    fn into_kind(self) -> ContentKind {
+        match self {
+            KindArg::Actor => ContentKind::Actor,
+            KindArg::Target => ContentKind::Target,
+            KindArg::Encounter => ContentKind::Encounter,
+            KindArg::Weapons => ContentKind::Weapons,
+            KindArg::Items => ContentKind::Items,
+            KindArg::Effects => ContentKind::Effects,
         }
     }
+}
 
-    fn dexterity_mod(&self) -> i32 {
-        if let Some(ref scores) = self.abilities {
-            scores.mod_of(Ability::Dex)
-        } else {
-            self.dex_mod
+#[derive(Copy, Clone, ValueEnum)]
+enum MethodArg {
+    StandardArray,
+    PointBuy,
+    Roll,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum RestArg {
+    Short,
+    Long,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum HpArg {
+    Average,
+    Roll,
+}
+
+impl HpArg {
+    fn into_method(self) -> HpMethod {
+        match self {
+            HpArg::Average => HpMethod::Average,
+            HpArg::Roll => HpMethod::Roll,
         }
     }
 }
 
-#[derive(Deserialize, Clone)]
-struct EncounterEnemy {
-    name: String,
-    ac: i32,
-    hp: i32,
-    #[serde(default)]
-    dex_mod: i32,
-    #[serde(default)]
-    abilities: Option<AbilityScores>,
-    #[serde(default)]
-    attacks: Vec<TargetAttack>,
-    #[serde(default)]
-    resistances: Vec<String>,
-    #[serde(default)]
-    vulnerabilities: Vec<String>,
-    #[serde(default)]
-    immunities: Vec<String>,
-    #[serde(default)]
-    conditions: Vec<ConditionKind>,
-    #[serde(default)]
-    cover: Option<Cover>,
+#[derive(Copy, Clone, ValueEnum)]
+enum FocusArg {
+    First,
+    Lowest,
+    Random,
 }
 
-#[derive(Deserialize)]
-struct Encounter {
-    #[serde(default)]
-    name: String,
-    #[serde(default = "default_focus")]
-    focus: String,
-    enemies: Vec<EncounterEnemy>,
+impl FocusArg {
+    fn into_focus(self) -> Focus {
+        match self {
+            FocusArg::First => Focus::First,
+            FocusArg::Lowest => Focus::Lowest,
+            FocusArg::Random => Focus::Random,
+        }
+    }
 }
 
-fn default_focus() -> String {
-    "first".to_string()
+#[derive(Copy, Clone, ValueEnum)]
+enum DownedArg {
+    Ignore,
+    Finish,
 }
 
-fn parse_condition_list(src: &Option<String>) -> Vec<ConditionKind> {
-    fn map_one(segment: &str) -> Option<ConditionKind> {
-        match segment.trim().to_lowercase().as_str() {
-            "poisoned" => Some(ConditionKind::Poisoned),
-            "prone" => Some(ConditionKind::Prone),
-            "restrained" => Some(ConditionKind::Restrained),
-            _ => None,
+impl DownedArg {
+    fn into_policy(self) -> DownedPolicy {
+        match self {
+            DownedArg::Ignore => DownedPolicy::Ignore,
+            DownedArg::Finish => DownedPolicy::Finish,
         }
     }
+}
 
-    match src {
-        None => vec![],
-        Some(text) => text.split(',').filter_map(map_one).collect(),
-    }
+#[derive(Copy, Clone, ValueEnum)]
+enum SideArg {
+    Party,
+    Enemies,
 }
 
-fn format_d20_sequence(raw: &[u8], kept: i32) -> String {
-    match raw {
-        [] => format!("d20=? (keep={})", kept),
-        [only] => format!("d20={} (keep={})", only, kept),
-        [first, second] => format!("d20={} vs d20={} (keep={})", first, second, kept),
-        _ => {
-            let joined = raw
-                .iter()
-                .map(|r| r.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
-            format!("d20s=[{}] (keep={})", joined, kept)
+#[derive(Copy, Clone, ValueEnum)]
+enum MaterialArg {
+    Silvered,
+    Adamantine,
+}
+
+impl MaterialArg {
+    fn into_material(self) -> Material {
+        match self {
+            MaterialArg::Silvered => Material::Silvered,
+            MaterialArg::Adamantine => Material::Adamantine,
         }
     }
 }
 
-fn format_modifier(modifier: i32) -> String {
-    if modifier >= 0 {
-        format!("+ {}", modifier)
-    } else {
-        format!("- {}", modifier.abs())
+#[derive(Copy, Clone, ValueEnum)]
+enum CritArg {
+    DoubleDice,
+    MaxPlusRoll,
+    DoubleTotal,
+}
+
+impl CritArg {
+    fn into_rule(self) -> CritRule {
+        match self {
+            CritArg::DoubleDice => CritRule::DoubleDice,
+            CritArg::MaxPlusRoll => CritRule::MaxPlusRoll,
+            CritArg::DoubleTotal => CritRule::DoubleTotal,
+        }
     }
 }
 
-fn log_attack_event(name: &str, atk: &engine::AttackResult) {
-    let rolls = format_d20_sequence(&atk.raw_rolls, atk.roll);
-    let outcome = if atk.is_crit {
-        "CRIT!"
-    } else if atk.hit {
-        "HIT"
-    } else if atk.nat1 {
-        "MISS (NAT1)"
-    } else {
-        "MISS"
-    };
-    let mark = if atk.hit { "✔" } else { "✖" };
-    println!(
-        "[ATTACK][{}] {} → {} to-hit={} vs AC={} {}",
-        name, rolls, outcome, atk.total, atk.ac, mark
-    );
+impl SideArg {
+    fn into_side(self) -> Side {
+        match self {
+            SideArg::Party => Side::Party,
+            SideArg::Enemies => Side::Enemies,
+        }
+    }
 }
 
-fn log_damage_event(
-    name: &str,
-    dice: engine::DamageDice,
-    modifier: i32,
-    crit: bool,
-    total: i32,
-    dtype: Option<engine::DamageType>,
-) {
-    let dice_expr = if crit {
-        format!("2×({})", dd_to_string(dice))
-    } else {
-        dd_to_string(dice)
-    };
-    let prefix = if crit { "crit: " } else { "" };
-    match dtype {
-        Some(dt) => println!(
-            "[DMG][{}] {}rolled {} {} = {} [{:?}]",
-            name,
-            prefix,
-            dice_expr,
-            format_modifier(modifier),
-            total,
-            dt
-        ),
-        None => println!(
-            "[DMG][{}] {}rolled {} {} = {}",
-            name,
-            prefix,
-            dice_expr,
-            format_modifier(modifier),
-            total
-        ),
+fn parse_condition_list(src: &Option<String>) -> anyhow::Result<Vec<ConditionKind>> {
+    match src {
+        None => Ok(vec![]),
+        Some(text) => text
+            .split(',')
+            .filter(|segment| !segment.trim().is_empty())
+            .map(|segment| segment.parse::<ConditionKind>().map_err(anyhow::Error::msg))
+            .collect(),
     }
 }
 
-fn log_defense(name: &str, base_ac: i32, cover: Cover) {
-    let bonus = cover.ac_bonus();
-    println!(
-        "[DEF][{}] AC {} + cover({:+}) = {}",
-        name,
-        base_ac,
-        bonus,
-        base_ac + bonus
-    );
+/// Print the combat log; with `explain`, each roll's explanation follows its line.
+fn print_combat_log(out: &CombatOutcome, explain: bool) {
+    for (n, line) in out.log.iter().enumerate() {
+        println!("{}", line);
+        if explain {
+            for roll in out.rolls.iter().filter(|r| r.log_index == n) {
+                println!("    why: {}", roll);
+            }
+        }
+    }
 }
 
-fn add_initial_conditions(
-    name: &str,
-    kinds: Vec<ConditionKind>,
-    bag: &mut Vec<ActiveCondition>,
-    mut log: impl FnMut(String),
-) {
-    for kind in kinds {
-        bag.push(ActiveCondition {
-            kind,
-            save_ends_each_turn: false,
-            end_phase: None,
-            end_save: None,
-            pending_one_turn: false,
-        });
-        log(format!("[COND][{}] starts with {:?}", name, kind));
+/// Comma-separated ids from the built-in effect list.
+fn parse_effect_list(src: &Option<String>, source: &str) -> anyhow::Result<Vec<ActiveEffect>> {
+    let Some(text) = src else {
+        return Ok(vec![]);
+    };
+    let mut catalog = Vec::new();
+    for json in builtin_effects().values() {
+        catalog.extend(parse_effects(json)?);
     }
+    let ids: Vec<String> = text
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .collect();
+    Ok(resolve_effects(&ids, &catalog, source)?)
 }
 
 #[derive(Subcommand)]
@@ -301,6 +273,28 @@ enum Cmd {
         /// Ability/skill modifier to add to the d20
         #[arg(long, default_value_t = 0)]
         modifier: i32,
+        /// Report the passive score (10 + modifier, ±5 for advantage) instead of rolling
+        #[arg(long, default_value_t = false)]
+        passive: bool,
+        /// Group check: one modifier per member, comma-separated; half must succeed
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        group: Vec<i32>,
+    },
+    /// Contested check: the initiator must beat the defender (ties go to the defender)
+    Contest {
+        /// RNG seed for determinism
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        /// Initiator's modifier and advantage mode
+        #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+        modifier: i32,
+        #[arg(long, value_enum, default_value_t = Adv::Normal)]
+        adv: Adv,
+        /// Defender's modifier and advantage mode
+        #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+        vs: i32,
+        #[arg(long = "vs-adv", value_enum, default_value_t = Adv::Normal)]
+        vs_adv: Adv,
     },
     /// Demo: run a few checks with a baked-in L1 Fighter
     ActorDemo {
@@ -313,6 +307,9 @@ enum Cmd {
         /// DC to test against
         #[arg(long, default_value_t = 13)]
         dc: i32,
+        /// Conditions on the actor (comma-separated); Poisoned and Restrained change the rolls
+        #[arg(long)]
+        cond: Option<String>,
     },
     /// Serialize the sample Fighter actor to JSON (stdout or file)
     ActorDump {
@@ -337,6 +334,9 @@ enum Cmd {
         /// DC to test against
         #[arg(long, default_value_t = 13)]
         dc: i32,
+        /// Conditions on the actor (comma-separated); Poisoned and Restrained change the rolls
+        #[arg(long)]
+        cond: Option<String>,
     },
     /// Demo a basic weapon attack + damage
     AttackDemo {
@@ -433,30 +433,66 @@ enum Cmd {
         #[arg(long, default_value_t = 16)]
         actor_ac: i32,
 
-        /// Actor HP (until we model hit dice/level, pass it in)
-        #[arg(long, default_value_t = 12)]
-        actor_hp: i32,
+        /// Actor HP (defaults to the character's max HP, or 12 for a bare actor)
+        #[arg(long)]
+        actor_hp: Option<i32>,
+
+        /// Temporary hit points the actor starts with (lost before HP)
+        #[arg(long, default_value_t = 0)]
+        actor_temp_hp: i32,
+
+        /// Add this many potions of healing (2d4+2, drunk as an action when low) to the inventory
+        #[arg(long, default_value_t = 0)]
+        potions: u32,
+
+        /// Extra item catalog JSON (on top of the built-in items) for inventory ids
+        #[arg(long)]
+        items: Option<PathBuf>,
+
+        /// After the duel ends, take a short rest (spend hit dice) or a long rest
+        #[arg(long, value_enum)]
+        rest: Option<RestArg>,
 
-        /// Auto-use a healing potion (2d4+2 averaged to 7 HP) the first time the actor drops to 0 HP.
-        #[arg(long = "auto-potion", default_value_t = false)]
-        auto_potion: bool,
+        /// Short rest: spend hit dice until HP reaches this percent of max
+        #[arg(long = "heal-to", default_value_t = 100)]
+        heal_to: u8,
 
-        /// After the duel ends, take a short rest (heal a flat 5 HP).
-        #[arg(long = "short-rest", default_value_t = false)]
-        short_rest: bool,
+        /// Short rest: keep at least this many hit dice unspent
+        #[arg(long = "keep-hit-dice", default_value_t = 0)]
+        keep_hit_dice: u8,
 
         /// Rounds safety cap (prevents infinite loops)
         #[arg(long, default_value_t = 20)]
         max_rounds: u32,
 
-        /// Starting conditions applied to the actor (comma-separated; valid: poisoned, prone, restrained)
+        /// Starting conditions applied to the actor (comma-separated; valid: poisoned, prone, restrained, grappled, stunned, paralyzed)
         #[arg(long = "actor-cond")]
         actor_cond: Option<String>,
 
-        /// Starting conditions applied to the enemy (comma-separated; valid: poisoned, prone, restrained)
+        /// Starting conditions applied to the enemy (comma-separated; valid: poisoned, prone, restrained, grappled, stunned, paralyzed)
         #[arg(long = "enemy-cond")]
         enemy_cond: Option<String>,
 
+        /// Built-in effects on the actor at the start (comma-separated; e.g. bless,shield_of_faith)
+        #[arg(long = "effect")]
+        effect: Option<String>,
+
+        /// Built-in effects on the enemy at the start (comma-separated; e.g. bane)
+        #[arg(long = "enemy-effect")]
+        enemy_effect: Option<String>,
+
+        /// Print each roll's breakdown and advantage sources under its log line
+        #[arg(long)]
+        explain: bool,
+
+        /// How critical hits roll damage: double-dice (default) | max-plus-roll | double-total
+        #[arg(long = "crit-rule", value_enum, default_value_t = CritArg::DoubleDice)]
+        crit_rule: CritArg,
+
+        /// Fight in sunlight (Sunlight Sensitivity)
+        #[arg(long)]
+        sunlight: bool,
+
         /// Weapon preset (or override with --dice)
         #[arg(long, default_value = "longsword")]
         weapon: String,
@@ -485,6 +521,14 @@ enum Cmd {
         #[arg(long)]
         dtype: Option<DType>,
 
+        /// The actor's weapon is magical (gets past resistance to nonmagical attacks)
+        #[arg(long = "magic-weapon")]
+        magic_weapon: bool,
+
+        /// The actor's weapon material: silvered | adamantine
+        #[arg(long, value_enum)]
+        material: Option<MaterialArg>,
+
         /// RNG seed
         #[arg(long, default_value_t = 777)]
         seed: u64,
@@ -501,45 +545,94 @@ enum Cmd {
         #[arg(long = "enemy-cover", value_enum)]
         enemy_cover: Option<CoverArg>,
 
-        /// Optional actor JSON (else sample fighter)
+        /// Side lying in wait: its Stealth vs the other side's passive Perception decides surprise
+        #[arg(long, value_enum)]
+        ambush: Option<SideArg>,
+
+        /// Roll the actor's initiative with advantage
+        #[arg(long = "init-adv", default_value_t = false)]
+        init_adv: bool,
+
+        /// Tactics JSON for class features (Second Wind threshold, smite policy, ...)
+        #[arg(long)]
+        tactics: Option<PathBuf>,
+
+        /// The actor's melee hits knock creatures out at 0 HP instead of killing them
+        #[arg(long)]
+        nonlethal: bool,
+
+        /// Optional actor or character JSON (else sample fighter)
         #[arg(long)]
         file: Option<PathBuf>,
     },
     /// Run a full multi-enemy encounter (initiative, turns, until one side drops)
     Encounter {
         /// Path to encounter JSON
-        #[arg(long)]
-        encounter: PathBuf,
+        /// Repeat to run several encounters back to back (HP and resources carry over)
+        #[arg(long, required = true)]
+        encounter: Vec<PathBuf>,
 
-        /// Actor AC/HP (until armor/level are modeled)
+        /// Actor AC (until armor is modeled) and HP (defaults as for duel)
         #[arg(long, default_value_t = 16)]
         actor_ac: i32,
-        #[arg(long, default_value_t = 12)]
-        actor_hp: i32,
+        #[arg(long)]
+        actor_hp: Option<i32>,
+        #[arg(long, default_value_t = 0)]
+        actor_temp_hp: i32,
 
-        /// Auto-use a healing potion (2d4+2 averaged to 7 HP) the first time the actor drops to 0 HP.
-        #[arg(long = "auto-potion", default_value_t = false)]
-        auto_potion: bool,
+        /// Potions of healing to add to the inventory, and an extra item catalog (same as duel)
+        #[arg(long, default_value_t = 0)]
+        potions: u32,
+        #[arg(long)]
+        items: Option<PathBuf>,
 
-        /// After the encounter ends, take a short rest (heal a flat 5 HP).
-        #[arg(long = "short-rest", default_value_t = false)]
-        short_rest: bool,
+        /// Rest after each encounter: short (spend hit dice) or long
+        #[arg(long, value_enum)]
+        rest: Option<RestArg>,
+        #[arg(long = "heal-to", default_value_t = 100)]
+        heal_to: u8,
+        #[arg(long = "keep-hit-dice", default_value_t = 0)]
+        keep_hit_dice: u8,
 
         /// Rounds safety cap
         #[arg(long, default_value_t = 50)]
         max_rounds: u32,
 
-        /// Starting conditions for the actor (comma-separated; valid: poisoned, prone, restrained)
+        /// Starting conditions for the actor (comma-separated; valid: poisoned, prone, restrained, grappled, stunned, paralyzed)
         #[arg(long = "actor-cond")]
         actor_cond: Option<String>,
 
-        /// Starting conditions applied to each enemy (comma-separated; valid: poisoned, prone, restrained)
+        /// Starting conditions applied to each enemy (comma-separated; valid: poisoned, prone, restrained, grappled, stunned, paralyzed)
         #[arg(long = "enemy-cond")]
         enemy_cond: Option<String>,
 
-        /// Focus strategy for actor: first | lowest | random
-        #[arg(long, default_value = "first")]
-        focus: String,
+        /// Built-in effects on the actor at the start (comma-separated; they last until a rest)
+        #[arg(long = "effect")]
+        effect: Option<String>,
+
+        /// Built-in effects on each enemy at the start (comma-separated)
+        #[arg(long = "enemy-effect")]
+        enemy_effect: Option<String>,
+
+        /// Print each roll's breakdown and advantage sources under its log line
+        #[arg(long)]
+        explain: bool,
+
+        /// How critical hits roll damage: double-dice (default) | max-plus-roll | double-total
+        #[arg(long = "crit-rule", value_enum, default_value_t = CritArg::DoubleDice)]
+        crit_rule: CritArg,
+
+        /// Fight in sunlight (Sunlight Sensitivity)
+        #[arg(long)]
+        sunlight: bool,
+
+        /// Focus strategy for actor: first | lowest | random (else from encounter file)
+        #[arg(long, value_enum)]
+        focus: Option<FocusArg>,
+
+        /// Whether enemies attack downed party members: ignore | finish (else from encounter file)
+        #[arg(long, value_enum)]
+        downed: Option<DownedArg>,
 
         /// Weapon + flags (same as duel)
         #[arg(long, default_value = "longsword")]
@@ -556,6 +649,10 @@ enum Cmd {
         weapons: Option<PathBuf>,
         #[arg(long)]
         dtype: Option<DType>,
+        #[arg(long = "magic-weapon")]
+        magic_weapon: bool,
+        #[arg(long, value_enum)]
+        material: Option<MaterialArg>,
 
         /// Seed & advantage (actor only)
         #[arg(long, default_value_t = 4242)]
@@ -571,10 +668,44 @@ enum Cmd {
         #[arg(long = "enemy-cover", value_enum)]
         enemy_cover: Option<CoverArg>,
 
-        /// Optional actor JSON
+        /// Ambush side (overrides encounter file) and initiative advantage (same as duel)
+        #[arg(long, value_enum)]
+        ambush: Option<SideArg>,
+        #[arg(long = "init-adv", default_value_t = false)]
+        init_adv: bool,
+
+        /// Tactics JSON for class features (same as duel)
+        #[arg(long)]
+        tactics: Option<PathBuf>,
+        #[arg(long)]
+        nonlethal: bool,
+
+        /// Optional actor or character JSON
         #[arg(long)]
         file: Option<PathBuf>,
     },
+    /// Validate content JSON against the schemas and semantic rules
+    Validate {
+        /// Content root to scan (characters/, targets/, encounters/, weapons/, items/, effects/, campaigns/)
+        #[arg(long, default_value = "content")]
+        dir: PathBuf,
+        /// Validate a single file instead of a whole content root
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Content kind for --file (else inferred from its folder)
+        #[arg(long, value_enum)]
+        kind: Option<KindArg>,
+    },
+    /// Create or level up a character, producing actor-compatible JSON
+    Character {
+        #[command(subcommand)]
+        cmd: CharacterCmd,
+    },
+    /// Play through a campaign of encounters, rests and checks
+    Campaign {
+        #[command(subcommand)]
+        cmd: CampaignCmd,
+    },
     /// FFI version string
     FfiVersion,
     /// FFI roll function (matches JNI implementation)
@@ -591,6 +722,132 @@ enum Cmd {
     },
 }
 
+#[derive(Subcommand)]
+enum CampaignCmd {
+    /// Run a campaign to the end (or for --steps nodes), carrying the character's state along
+    Run {
+        /// Campaign JSON (encounter paths in it are relative to this file)
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Built-in campaign id, when no --file is given
+        #[arg(long, default_value = "goblin_warrens")]
+        id: String,
+        /// Character JSON (else the sample fighter)
+        #[arg(long)]
+        character: Option<PathBuf>,
+        /// Resume from a state file written by --save (overrides --character)
+        #[arg(long)]
+        state: Option<PathBuf>,
+        /// Write the state after the last step, to resume later with --state
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Stop after this many nodes
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        steps: Option<u32>,
+        #[arg(long, default_value = "longsword")]
+        weapon: String,
+        #[arg(long)]
+        weapons: Option<PathBuf>,
+        #[arg(long)]
+        items: Option<PathBuf>,
+        #[arg(long)]
+        actor_ac: Option<i32>,
+        #[arg(long)]
+        tactics: Option<PathBuf>,
+        /// Short rests: spend hit dice until HP reaches this percent of max, keeping some back
+        #[arg(long = "heal-to", default_value_t = 100)]
+        heal_to: u8,
+        #[arg(long = "keep-hit-dice", default_value_t = 0)]
+        keep_hit_dice: u8,
+        #[arg(long = "crit-rule", value_enum, default_value_t = CritArg::DoubleDice)]
+        crit_rule: CritArg,
+        #[arg(long, default_value_t = 2024)]
+        seed: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum CharacterCmd {
+    /// Build a new character from class, species, background and ability scores
+    New {
+        /// Character name
+        #[arg(long, default_value = "")]
+        name: String,
+        /// Class (barbarian, bard, cleric, druid, fighter, monk, paladin, ranger, rogue, sorcerer, warlock, wizard)
+        #[arg(long)]
+        class: Class,
+        /// Species (dwarf, elf, halfling, human, dragonborn, gnome, half-elf, half-orc, tiefling)
+        #[arg(long, default_value = "human")]
+        species: Species,
+        /// Background (acolyte or custom)
+        #[arg(long, default_value = "acolyte")]
+        background: Background,
+        /// Two skills for a custom background, comma-separated
+        #[arg(long = "background-skills", value_delimiter = ',')]
+        background_skills: Vec<Skill>,
+        /// How to generate ability scores
+        #[arg(long, value_enum, default_value_t = MethodArg::StandardArray)]
+        method: MethodArg,
+        /// Point-buy base scores as str,dex,con,int,wis,cha (e.g. 15,14,13,10,10,8)
+        #[arg(long, value_delimiter = ',')]
+        scores: Vec<i32>,
+        /// Abilities to fill first with the highest scores, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        priority: Vec<Ability>,
+        /// Class skill picks, comma-separated (default: first eligible)
+        #[arg(long, value_delimiter = ',')]
+        skills: Vec<Skill>,
+        /// Half-elf skill picks, comma-separated
+        #[arg(long = "species-skills", value_delimiter = ',')]
+        species_skills: Vec<Skill>,
+        /// Rogue/Bard Expertise picks among proficient skills, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        expertise: Vec<Skill>,
+        /// Half-elf +1 ability picks, comma-separated
+        #[arg(long = "species-bonus", value_delimiter = ',')]
+        species_bonus: Vec<Ability>,
+        /// Fighting style (fighter, paladin, ranger); defaults to defense when available
+        #[arg(long = "fighting-style")]
+        fighting_style: Option<FightingStyle>,
+        /// Subclass, in effect from its level (champion: fighter 3rd)
+        #[arg(long)]
+        subclass: Option<Subclass>,
+        /// Starting inventory, comma-separated item ids with optional counts (potion_of_healing:2,antitoxin)
+        #[arg(long, value_delimiter = ',', value_parser = parse_inventory_entry)]
+        inventory: Vec<InventoryEntry>,
+        /// Starting level (1-20)
+        #[arg(long, default_value_t = 1)]
+        level: u8,
+        /// HP per level above 1st: average or roll
+        #[arg(long, value_enum, default_value_t = HpArg::Average)]
+        hp: HpArg,
+        /// RNG seed for rolled scores and hit dice
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        /// Optional output path; if omitted, prints to stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Raise a character loaded from JSON by one or more levels
+    LevelUp {
+        /// Path to character JSON (as written by `character new`)
+        #[arg(long)]
+        file: PathBuf,
+        /// Number of levels to gain
+        #[arg(long, default_value_t = 1)]
+        levels: u8,
+        /// HP per level: average or roll
+        #[arg(long, value_enum, default_value_t = HpArg::Average)]
+        hp: HpArg,
+        /// RNG seed for rolled hit dice
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        /// Optional output path; if omitted, prints to stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
 #[derive(Parser)]
 #[command(name = "solo5e-cli")]
 #[command(about = "Solo5e CLI harness")]
@@ -599,6 +856,185 @@ struct Cli {
     cmd: Cmd,
 }
 
+fn run_campaign_cmd(cmd: CampaignCmd) -> anyhow::Result<()> {
+    let CampaignCmd::Run {
+        file,
+        id,
+        character,
+        state,
+        save,
+        steps,
+        weapon,
+        weapons,
+        items,
+        actor_ac,
+        tactics,
+        heal_to,
+        keep_hit_dice,
+        crit_rule,
+        seed,
+    } = cmd;
+    let state = match state {
+        Some(p) => Some(
+            parse_content::<CampaignState>("campaign state", &read_text_auto(&p)?)
+                .with_context(|| format!("failed to parse campaign state {}", p.display()))?,
+        ),
+        None => None,
+    };
+    let path = |p: Option<PathBuf>| p.map(|p| p.display().to_string());
+    let mut cfg = CampaignConfig {
+        campaign_id: file.is_none().then_some(id),
+        campaign_path: path(file),
+        character_path: path(character),
+        weapon,
+        weapons_path: path(weapons),
+        items_path: path(items),
+        actor_ac,
+        seed,
+        tactics: load_tactics(tactics.as_deref())?,
+        rest_policy: ShortRestPolicy {
+            heal_to_pct: heal_to,
+            reserve: keep_hit_dice,
+        },
+        crit_rule: crit_rule.into_rule(),
+        state,
+    };
+
+    let state = match steps {
+        None => {
+            let out = run_campaign(cfg)?;
+            for line in &out.log {
+                println!("{}", line);
+            }
+            out.state
+        }
+        Some(n) => {
+            for _ in 0..n {
+                if cfg.state.as_ref().is_some_and(|s| s.is_over()) {
+                    break;
+                }
+                let out = step_campaign(cfg.clone())?;
+                for line in &out.log {
+                    println!("{}", line);
+                }
+                cfg.state = Some(out.state);
+            }
+            cfg.state.expect("at least one step or a resumed state")
+        }
+    };
+
+    println!("---");
+    let hp = format!("{}/{} HP", state.health.hp, state.health.effective_max_hp());
+    match (state.status, &state.node) {
+        (CampaignStatus::Victory, _) => println!("Result: victory ({})", hp),
+        (CampaignStatus::Defeat, _) => println!("Result: defeat ({})", hp),
+        (CampaignStatus::InProgress, node) => println!(
+            "Result: paused before node '{}' ({})",
+            node.as_deref().unwrap_or("?"),
+            hp
+        ),
+    }
+    if let Some(path) = save {
+        fs::write(path, serde_json::to_string_pretty(&state)?.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn run_character(cmd: CharacterCmd) -> anyhow::Result<()> {
+    let (character, out) = match cmd {
+        CharacterCmd::New {
+            name,
+            class,
+            species,
+            background,
+            background_skills,
+            method,
+            scores,
+            priority,
+            skills,
+            species_skills,
+            species_bonus,
+            expertise,
+            fighting_style,
+            subclass,
+            inventory,
+            level,
+            hp,
+            seed,
+            out,
+        } => {
+            let abilities = match method {
+                MethodArg::StandardArray => AbilityMethod::StandardArray,
+                MethodArg::Roll => AbilityMethod::Roll,
+                MethodArg::PointBuy => {
+                    let [str_, dex, con, int_, wis, cha] = scores[..] else {
+                        anyhow::bail!(
+                            "--scores needs 6 values (str,dex,con,int,wis,cha) for point buy"
+                        );
+                    };
+                    AbilityMethod::PointBuy {
+                        scores: AbilityScores {
+                            str_,
+                            dex,
+                            con,
+                            int_,
+                            wis,
+                            cha,
+                        },
+                    }
+                }
+            };
+            let spec = CharacterSpec {
+                name,
+                class,
+                species,
+                background,
+                abilities,
+                priority,
+                skills,
+                background_skills,
+                species_skills,
+                species_bonus,
+                expertise,
+                fighting_style,
+                subclass,
+                level,
+                hp_method: hp.into_method(),
+                inventory,
+            };
+            let mut dice = Dice::from_seed(seed);
+            (create_character(&spec, &mut dice)?, out)
+        }
+        CharacterCmd::LevelUp {
+            file,
+            levels,
+            hp,
+            seed,
+            out,
+        } => {
+            let text = read_text_auto(&file)?;
+            let mut character: Character = parse_content("character", &text)
+                .with_context(|| format!("failed to parse character {}", file.display()))?;
+            let mut dice = Dice::from_seed(seed);
+            for _ in 0..levels {
+                let gained = character.level_up(hp.into_method(), &mut dice)?;
+                eprintln!(
+                    "Level {}: +{} HP (max {}), proficiency +{}",
+                    character.level, gained, character.max_hp, character.actor.proficiency_bonus
+                );
+            }
+            (character, out)
+        }
+    };
+    let s = serde_json::to_string_pretty(&character)?;
+    if let Some(path) = out {
+        fs::write(path, s.as_bytes())?; // UTF-8, no BOM
+    } else {
+        println!("{}", s);
+    }
+    Ok(())
+}
+
 fn to_mode(a: Adv) -> AdMode {
     match a {
         Adv::Normal => AdMode::Normal,
@@ -607,30 +1043,6 @@ fn to_mode(a: Adv) -> AdMode {
     }
 }
 
-fn sample_fighter() -> Actor {
-    // L1 Fighter: PB +2, STR/CON saves; Athletics & Perception proficient
-    let abilities = AbilityScores {
-        str_: 16,
-        dex: 14,
-        con: 14,
-        int_: 10,
-        wis: 12,
-        cha: 8,
-    };
-    let mut save = HashSet::new();
-    save.insert(Ability::Str);
-    save.insert(Ability::Con);
-    let mut skills = HashSet::new();
-    skills.insert(Skill::Athletics);
-    skills.insert(Skill::Perception);
-    Actor {
-        abilities,
-        proficiency_bonus: 2,
-        save_proficiencies: save,
-        skill_proficiencies: skills,
-    }
-}
-
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     match cli.cmd {
@@ -646,9 +1058,43 @@ fn main() -> anyhow::Result<()> {
             adv,
             dc,
             modifier,
+            passive,
+            group,
         } => {
             let mode = to_mode(adv);
             let mut dice = Dice::from_seed(seed);
+            if passive {
+                let score = passive_score(modifier, mode);
+                println!(
+                    "passive={} dc={} => {}",
+                    score,
+                    dc,
+                    if score >= dc { "SUCCESS" } else { "FAIL" }
+                );
+                return Ok(());
+            }
+            if !group.is_empty() {
+                let members: Vec<Contestant> =
+                    group.iter().map(|&m| Contestant::new(m, mode)).collect();
+                let res = group_check(&mut dice, dc, &members);
+                for (m, r) in group.iter().zip(&res.results) {
+                    println!(
+                        "roll={} mod={} total={} => {}",
+                        r.roll,
+                        m,
+                        r.total,
+                        if r.passed { "SUCCESS" } else { "FAIL" }
+                    );
+                }
+                println!(
+                    "group: {}/{} succeeded vs dc={} => {}",
+                    res.successes,
+                    group.len(),
+                    dc,
+                    if res.passed { "SUCCESS" } else { "FAIL" }
+                );
+                return Ok(());
+            }
             let res = engine::check(&mut dice, engine::CheckInput { dc, modifier, mode });
             println!(
                 "roll={} mod={} total={} dc={} => {}",
@@ -659,13 +1105,44 @@ fn main() -> anyhow::Result<()> {
                 if res.passed { "SUCCESS" } else { "FAIL" }
             );
         }
-        Cmd::ActorDemo { seed, adv, dc } => {
+        Cmd::Contest {
+            seed,
+            modifier,
+            adv,
+            vs,
+            vs_adv,
+        } => {
+            let mut dice = Dice::from_seed(seed);
+            let res = contest(
+                &mut dice,
+                Contestant::new(modifier, to_mode(adv)),
+                Contestant::new(vs, to_mode(vs_adv)),
+            );
+            println!(
+                "initiator roll={} total={} vs defender roll={} total={} => {}",
+                res.initiator_roll,
+                res.initiator_total,
+                res.defender_roll,
+                res.defender_total,
+                if res.initiator_wins {
+                    "INITIATOR WINS"
+                } else {
+                    "DEFENDER HOLDS"
+                }
+            );
+        }
+        Cmd::ActorDemo {
+            seed,
+            adv,
+            dc,
+            cond,
+        } => {
             let mode = to_mode(adv);
-            let actor = sample_fighter();
-            demo_checks(actor, seed, mode, dc);
+            let actor = sample_fighter().actor;
+            demo_checks(actor, seed, mode, dc, &conditions_from(&cond)?);
         }
         Cmd::ActorDump { pretty, out } => {
-            let actor = sample_fighter();
+            let actor = sample_fighter().actor;
             let s = if pretty {
                 serde_json::to_string_pretty(&actor)?
             } else {
@@ -682,11 +1159,12 @@ fn main() -> anyhow::Result<()> {
             seed,
             adv,
             dc,
+            cond,
         } => {
             let text = read_text_auto(&file)?;
             let actor: Actor = serde_json::from_str(&text)?;
             let mode = to_mode(adv);
-            demo_checks(actor, seed, mode, dc);
+            demo_checks(actor, seed, mode, dc, &conditions_from(&cond)?);
         }
         Cmd::AttackDemo {
             ac,
@@ -705,7 +1183,7 @@ fn main() -> anyhow::Result<()> {
                 let text = read_text_auto(&path)?;
                 serde_json::from_str::<Actor>(&text)?
             } else {
-                sample_fighter()
+                sample_fighter().actor
             };
 
             let resolved = resolve_weapon(&weapon, weapons.as_deref())?;
@@ -779,27 +1257,11 @@ fn main() -> anyhow::Result<()> {
                 let text = read_text_auto(&path)?;
                 serde_json::from_str::<Actor>(&text)?
             } else {
-                sample_fighter()
+                sample_fighter().actor
             };
 
             // Load target
             let mut tgt = read_target_auto(&target)?;
-            let resist: HashSet<_> = tgt
-                .resistances
-                .iter()
-                .filter_map(|s| parse_dtype_str(s))
-                .collect();
-            let vuln: HashSet<_> = tgt
-                .vulnerabilities
-                .iter()
-                .filter_map(|s| parse_dtype_str(s))
-                .collect();
-            let immune: HashSet<_> = tgt
-                .immunities
-                .iter()
-                .filter_map(|s| parse_dtype_str(s))
-                .collect();
-
             let resolved = resolve_weapon(&weapon, weapons.as_deref())?;
             let dtype = resolve_damage_type(dtype, &resolved);
             let chosen_ability = pick_ability(ability, &resolved);
@@ -839,7 +1301,13 @@ fn main() -> anyhow::Result<()> {
                 let is_crit = atk.nat20;
                 if atk.hit {
                     let raw = engine::damage(&mut dice_rng, dmg_spec, damage_mod, is_crit);
-                    let dmg = engine::adjust_damage_by_type(raw, dtype, &resist, &vuln, &immune);
+                    let dmg = engine::adjust_damage_by_type(
+                        raw,
+                        dtype,
+                        &tgt.resistances,
+                        &tgt.vulnerabilities,
+                        &tgt.immunities,
+                    );
                     tgt.hp = (tgt.hp - dmg).max(0);
                     println!(
                         "round {}: HIT{} (roll={} total={}) dmg={} [{:?}] -> {} HP left",
@@ -870,11 +1338,20 @@ fn main() -> anyhow::Result<()> {
             target,
             actor_ac,
             actor_hp,
-            auto_potion,
-            short_rest,
+            actor_temp_hp,
+            potions,
+            items,
+            rest,
+            heal_to,
+            keep_hit_dice,
             max_rounds,
             actor_cond,
             enemy_cond,
+            effect,
+            enemy_effect,
+            explain,
+            crit_rule,
+            sunlight,
             weapon,
             dice,
             ability,
@@ -882,338 +1359,134 @@ fn main() -> anyhow::Result<()> {
             two_handed,
             weapons,
             dtype,
+            magic_weapon,
+            material,
             seed,
             adv,
             actor_cover: actor_cover_opt,
             enemy_cover: enemy_cover_opt,
+            ambush,
+            init_adv,
+            tactics,
+            nonlethal,
             file,
         } => {
-            let actor = if let Some(path) = file {
-                let text = read_text_auto(&path)?;
-                serde_json::from_str::<Actor>(&text)?
-            } else {
-                sample_fighter()
-            };
-
             let tgt = read_target_auto(&target)?;
-            let actor_cover = actor_cover_opt
-                .map(|c| c.into_cover())
-                .unwrap_or(Cover::None);
-            let enemy_cover = enemy_cover_opt.map(|c| c.into_cover()).unwrap_or(tgt.cover);
-            let resist: HashSet<_> = tgt
-                .resistances
-                .iter()
-                .filter_map(|s| parse_dtype_str(s))
-                .collect();
-            let vuln: HashSet<_> = tgt
-                .vulnerabilities
-                .iter()
-                .filter_map(|s| parse_dtype_str(s))
-                .collect();
-            let immune: HashSet<_> = tgt
-                .immunities
-                .iter()
-                .filter_map(|s| parse_dtype_str(s))
-                .collect();
-
+            if tgt.attacks.is_empty() {
+                anyhow::bail!("Target has no attacks");
+            }
             let resolved = resolve_weapon(&weapon, weapons.as_deref())?;
-            let actor_dtype = resolve_damage_type(dtype, &resolved);
-            let chosen_ability = pick_ability(ability, &resolved);
-            let proficient = !no_prof;
-
-            let actor_dd = if let Some(ref s) = dice {
-                parse_damage_dice(s)?
-            } else if two_handed {
-                resolved.versatile.unwrap_or(resolved.dice)
-            } else {
-                resolved.dice
+            let attack = AttackArgs {
+                dice,
+                ability,
+                no_prof,
+                two_handed,
+                dtype,
+                magic_weapon,
+                material,
             };
-
-            let actor_atk_bonus = actor.attack_bonus(chosen_ability, proficient);
-            let actor_dmg_mod = actor.damage_mod(chosen_ability);
-            let actor_mode = to_mode(adv);
-
-            let tgt_attack = tgt
-                .attacks
-                .first()
-                .cloned()
-                .ok_or_else(|| anyhow::anyhow!("Target has no attacks"))?;
-            let tgt_dtype = tgt_attack
-                .damage_type
-                .unwrap_or(engine::DamageType::Slashing);
-
-            let mut actor_conditions: Vec<ActiveCondition> = Vec::new();
-            let mut enemy_conditions: Vec<ActiveCondition> = Vec::new();
-
-            add_initial_conditions(
-                "Actor",
-                parse_condition_list(&actor_cond),
-                &mut actor_conditions,
-                |msg| println!("{}", msg),
-            );
-
-            let mut enemy_initial = tgt.conditions.clone();
-            let mut extra_enemy = parse_condition_list(&enemy_cond);
-            enemy_initial.append(&mut extra_enemy);
-            add_initial_conditions(&tgt.name, enemy_initial, &mut enemy_conditions, |msg| {
-                println!("{}", msg)
-            });
-
-            let mut rng = Dice::from_seed(seed);
-            let actor_init = rng.d20(AdMode::Normal) as i32 + actor.ability_mod(Ability::Dex);
-            let tgt_init = rng.d20(AdMode::Normal) as i32 + tgt.dexterity_mod();
-            let mut actor_turn = actor_init >= tgt_init;
-
-            let mut actor_health = Health::new(actor_hp);
-            let mut auto_potion_left = auto_potion;
-            let mut cur_tgt_hp = tgt.hp;
+            let catalog = load_item_catalog(items.as_deref())?;
+            let mut actor =
+                party_combatant(file, &resolved, &attack, actor_ac, actor_hp, &catalog)?;
+            actor.attack_vantage = to_mode(adv).into();
+            actor.cover = actor_cover_opt.map(|c| c.into_cover()).unwrap_or_default();
+            actor.initiative_advantage |= init_adv;
+            actor.health.temp_hp = actor_temp_hp.max(0);
+            add_potions(&mut actor, potions, &catalog);
+            for kind in parse_condition_list(&actor_cond)? {
+                actor.conditions.push(ActiveCondition::indefinite(kind));
+            }
+            actor.effects = parse_effect_list(&effect, "setup")?;
+            let mut enemy = Combatant::from_target(&tgt);
+            if let Some(cover) = enemy_cover_opt {
+                enemy.cover = cover.into_cover();
+            }
+            for kind in parse_condition_list(&enemy_cond)? {
+                enemy.conditions.push(ActiveCondition::indefinite(kind));
+            }
+            enemy.effects = parse_effect_list(&enemy_effect, "setup")?;
 
             println!(
-                "Duel: Actor (AC {}, HP {}) vs {} (AC {}, HP {})",
-                actor_ac, actor_hp, tgt.name, tgt.ac, tgt.hp
+                "Duel: {} (AC {}, HP {}) vs {} (AC {}, HP {})",
+                actor.name,
+                actor.armor_class(),
+                actor.health.hp,
+                enemy.name,
+                enemy.ac,
+                enemy.health.hp
             );
+            let profile = &actor.attacks[0];
             println!(
-                "Initiative -> Actor {} vs {} {} => {} starts",
-                actor_init,
-                tgt.name,
-                tgt_init,
-                if actor_turn { "Actor" } else { &tgt.name }
-            );
-            println!(
-                "Actor weapon: {} [{}] {:?}",
-                resolved.name,
-                dd_to_string(actor_dd),
-                actor_dtype
+                "{} weapon: {} [{}] {:?}",
+                actor.name,
+                profile.name,
+                dd_to_string(profile.dice),
+                profile.damage_type
             );
             println!("---");
 
-            for round in 1..=max_rounds {
-                if matches!(actor_health.state, LifeState::Dead) || cur_tgt_hp <= 0 {
-                    break;
-                }
-                println!("Round {}", round);
-
-                if actor_turn {
-                    if let Some(outcome) = process_death_save_start_of_turn(
-                        "Actor",
-                        &mut actor_health,
-                        || rng.d20(AdMode::Normal) as i32,
-                        |msg| println!("{}", msg),
-                    ) {
-                        println!("[TURN][Actor] death save: {}", outcome);
-                    }
-
-                    process_turn_boundary(
-                        TurnBoundary::StartOfTurn,
-                        "Actor",
-                        &mut actor_conditions,
-                        |ability, _dc| {
-                            let roll = rng.d20(AdMode::Normal) as i32;
-                            let total = roll + actor.save_mod(ability);
-                            (roll, total)
-                        },
-                        |msg| println!("{}", msg),
-                    );
-
-                    match actor_health.state {
-                        LifeState::Dead => {
-                            println!("[TURN][Actor] is dead. Skipping.");
-                        }
-                        LifeState::Unconscious { .. } => {
-                            println!("[TURN][Actor] is unconscious. Skipping actions.");
-                        }
-                        LifeState::Conscious => {
-                            let style = if resolved.ranged {
-                                AttackStyle::Ranged
-                            } else {
-                                AttackStyle::Melee
-                            };
-                            let base_vantage: Vantage = actor_mode.into();
-                            let cond_vantage = vantage_from_conditions(
-                                &actor_conditions,
-                                &enemy_conditions,
-                                style,
-                            );
-                            let final_mode: AdMode = base_vantage.combine(cond_vantage).into();
-                            let enemy_effective_ac = tgt.ac + enemy_cover.ac_bonus();
-                            log_defense(&tgt.name, tgt.ac, enemy_cover);
-                            let atk = engine::attack(
-                                &mut rng,
-                                final_mode,
-                                actor_atk_bonus,
-                                enemy_effective_ac,
-                            );
-                            log_attack_event("Actor", &atk);
-                            if atk.hit {
-                                let is_crit = atk.is_crit;
-                                let raw =
-                                    engine::damage(&mut rng, actor_dd, actor_dmg_mod, is_crit);
-                                log_damage_event(
-                                    "Actor",
-                                    actor_dd,
-                                    actor_dmg_mod,
-                                    is_crit,
-                                    raw,
-                                    Some(actor_dtype),
-                                );
-                                let adj = engine::adjust_damage_by_type(
-                                    raw,
-                                    actor_dtype,
-                                    &resist,
-                                    &vuln,
-                                    &immune,
-                                );
-                                if adj != raw {
-                                    println!(
-                                        "[DMG][Actor] adjusted for resistances/vulnerabilities => {}",
-                                        adj
-                                    );
-                                }
-                                cur_tgt_hp = (cur_tgt_hp - adj).max(0);
-                                println!("[HP][{}] {} HP remaining", tgt.name, cur_tgt_hp);
-                            } else {
-                                println!("[HP][{}] {} HP remaining", tgt.name, cur_tgt_hp);
-                            }
-                        }
-                    }
-
-                    process_turn_boundary(
-                        TurnBoundary::EndOfTurn,
-                        "Actor",
-                        &mut actor_conditions,
-                        |ability, _dc| {
-                            let roll = rng.d20(AdMode::Normal) as i32;
-                            let total = roll + actor.save_mod(ability);
-                            (roll, total)
-                        },
-                        |msg| println!("{}", msg),
-                    );
-                } else {
-                    process_turn_boundary(
-                        TurnBoundary::StartOfTurn,
-                        &tgt.name,
-                        &mut enemy_conditions,
-                        |ability, _dc| {
-                            let roll = rng.d20(AdMode::Normal) as i32;
-                            let total = roll + tgt.ability_mod(ability);
-                            (roll, total)
-                        },
-                        |msg| println!("{}", msg),
-                    );
-
-                    let style = if tgt_attack.ranged {
-                        AttackStyle::Ranged
-                    } else {
-                        AttackStyle::Melee
-                    };
-                    let base_vantage = Vantage::Normal;
-                    let cond_vantage =
-                        vantage_from_conditions(&enemy_conditions, &actor_conditions, style);
-                    let final_mode: AdMode = base_vantage.combine(cond_vantage).into();
-                    println!("[ACTION][{}] uses {}", tgt.name, tgt_attack.name);
-                    let enemy_label = format!("{} ({})", tgt.name, tgt_attack.name);
-                    let effective_actor_ac = actor_ac + actor_cover.ac_bonus();
-                    log_defense("Actor", actor_ac, actor_cover);
-                    let atk =
-                        engine::attack(&mut rng, final_mode, tgt_attack.to_hit, effective_actor_ac);
-                    log_attack_event(&enemy_label, &atk);
-                    if atk.hit {
-                        let is_crit = atk.is_crit;
-                        let dmg = engine::damage(&mut rng, tgt_attack.dice, 0, is_crit);
-                        log_damage_event(
-                            &enemy_label,
-                            tgt_attack.dice,
-                            0,
-                            is_crit,
-                            dmg,
-                            Some(tgt_dtype),
-                        );
-                        let dropped = apply_damage(
-                            "Actor",
-                            &mut actor_health,
-                            &mut actor_conditions,
-                            dmg,
-                            |msg| println!("{}", msg),
-                        );
-                        println!("[HP][Actor] {} HP remaining", actor_health.hp);
-                        if dropped && auto_potion_left {
-                            heal("Actor", &mut actor_health, 7, |msg| println!("{}", msg));
-                            auto_potion_left = false;
-                            println!("[ITEM][Actor] Auto-potion consumed (2d4+2 ~ 7)");
-                        }
-                        if let Some(spec) = tgt_attack.apply_condition.as_ref() {
-                            maybe_apply_on_hit_condition(
-                                "Actor",
-                                &mut actor_conditions,
-                                spec,
-                                |ability, _dc| {
-                                    let roll = rng.d20(AdMode::Normal) as i32;
-                                    let total = roll + actor.save_mod(ability);
-                                    (roll, total)
-                                },
-                                |msg| println!("{}", msg),
-                            );
-                        }
-                    } else {
-                        println!("[HP][Actor] {} HP remaining", actor_health.hp);
-                    }
-
-                    process_turn_boundary(
-                        TurnBoundary::EndOfTurn,
-                        &tgt.name,
-                        &mut enemy_conditions,
-                        |ability, _dc| {
-                            let roll = rng.d20(AdMode::Normal) as i32;
-                            let total = roll + tgt.ability_mod(ability);
-                            (roll, total)
-                        },
-                        |msg| println!("{}", msg),
-                    );
-                }
-
-                if matches!(actor_health.state, LifeState::Dead) || cur_tgt_hp <= 0 {
-                    break;
-                }
-                actor_turn = !actor_turn;
-            }
+            let mut tactics = load_tactics(tactics.as_deref())?;
+            tactics.nonlethal |= nonlethal;
+            let opts = CombatOptions {
+                max_rounds,
+                tactics,
+                ambush: ambush.map(SideArg::into_side),
+                crit_rule: crit_rule.into_rule(),
+                sunlight,
+                ..CombatOptions::default()
+            };
+            let mut dice = Dice::from_seed(seed);
+            let out = run_combat(vec![actor, enemy], &opts, &mut dice);
+            print_combat_log(&out, explain);
 
             println!("---");
-            let actor_dead = matches!(actor_health.state, LifeState::Dead);
-            let actor_unconscious = matches!(actor_health.state, LifeState::Unconscious { .. });
-            let actor_hp_left = actor_health.hp;
-            if cur_tgt_hp <= 0 && actor_hp_left > 0 {
-                println!("Result: Actor defeats {}.", tgt.name);
-            } else if actor_dead {
-                println!("Result: {} defeats Actor.", tgt.name);
-            } else if cur_tgt_hp <= 0 && actor_hp_left <= 0 {
+            let (actor, enemy) = (&out.combatants[0], &out.combatants[1]);
+            let enemy_down = enemy.health.hp <= 0;
+            if enemy_down && actor.health.hp > 0 {
+                println!("Result: {} defeats {}.", actor.name, enemy.name);
+            } else if actor.is_dead() {
+                println!("Result: {} defeats {}.", enemy.name, actor.name);
+            } else if enemy_down {
                 println!("Result: Mutual KO.");
-            } else if actor_unconscious && cur_tgt_hp > 0 {
+            } else if actor.health.hp <= 0 {
                 println!(
-                    "Result: Actor is unconscious at 0 HP; {} still stands.",
-                    tgt.name
+                    "Result: {} is unconscious at 0 HP; {} still stands.",
+                    actor.name, enemy.name
                 );
             } else {
                 println!(
                     "Result: Max rounds reached ({} HP vs {} HP).",
-                    actor_hp_left, cur_tgt_hp
+                    actor.health.hp, enemy.health.hp
                 );
             }
 
-            if short_rest && !actor_dead {
-                heal("Actor", &mut actor_health, 5, |msg| println!("{}", msg));
-                println!("[REST][Actor] Short rest: +5 HP");
-            }
+            let mut actor = actor.clone();
+            let policy = ShortRestPolicy {
+                heal_to_pct: heal_to,
+                reserve: keep_hit_dice,
+            };
+            take_rest(&mut actor, rest, &policy, &mut dice);
         }
         Cmd::Encounter {
             encounter,
             actor_ac,
             actor_hp,
-            auto_potion,
-            short_rest,
+            actor_temp_hp,
+            potions,
+            items,
+            rest,
+            heal_to,
+            keep_hit_dice,
             max_rounds,
             focus,
+            downed,
             actor_cond,
             enemy_cond,
+            effect,
+            enemy_effect,
+            explain,
+            crit_rule,
+            sunlight,
             weapon,
             dice,
             ability,
@@ -1221,556 +1494,165 @@ fn main() -> anyhow::Result<()> {
             two_handed,
             weapons,
             dtype,
+            magic_weapon,
+            material,
             seed,
             adv,
             actor_cover: actor_cover_opt,
             enemy_cover: enemy_cover_opt,
+            ambush,
+            init_adv,
+            tactics,
+            nonlethal,
             file,
         } => {
-            let actor = if let Some(path) = file {
-                let text = read_text_auto(&path)?;
-                serde_json::from_str::<Actor>(&text)?
-            } else {
-                sample_fighter()
-            };
-
-            let actor_cover = actor_cover_opt
-                .map(|c| c.into_cover())
-                .unwrap_or(Cover::None);
-            let enemy_cover_override = enemy_cover_opt.map(|c| c.into_cover());
-
-            let encounter_data = read_encounter_auto(&encounter)?;
-            if encounter_data.enemies.is_empty() {
-                anyhow::bail!("encounter must contain at least one enemy");
-            }
-
             let resolved = resolve_weapon(&weapon, weapons.as_deref())?;
-            let dtype = resolve_damage_type(dtype, &resolved);
-            let chosen_ability = pick_ability(ability, &resolved);
-            let proficient = !no_prof;
-
-            let dmg_spec = if let Some(ref s) = dice {
-                parse_damage_dice(s)?
-            } else if two_handed {
-                resolved.versatile.unwrap_or(resolved.dice)
-            } else {
-                resolved.dice
+            let attack = AttackArgs {
+                dice,
+                ability,
+                no_prof,
+                two_handed,
+                dtype,
+                magic_weapon,
+                material,
             };
-
-            let attack_bonus = actor.attack_bonus(chosen_ability, proficient);
-            let damage_mod = actor.damage_mod(chosen_ability);
-
-            let mut rng = Dice::from_seed(seed);
-            let mode = to_mode(adv);
-            let actor_dex_mod = actor.ability_mod(Ability::Dex);
-
-            let mut focus_strategy = focus.to_lowercase();
-            let file_focus = encounter_data.focus.to_lowercase();
-            if focus_strategy == "first" && file_focus != "first" {
-                focus_strategy = file_focus;
-            }
-
-            struct EnemyState {
-                name: String,
-                ac: i32,
-                hp: i32,
-                cover: Cover,
-                dex_mod: i32,
-                abilities: Option<AbilityScores>,
-                attacks: Vec<TargetAttack>,
-                resist: HashSet<engine::DamageType>,
-                vuln: HashSet<engine::DamageType>,
-                immune: HashSet<engine::DamageType>,
-                conditions: Vec<ActiveCondition>,
+            let catalog = load_item_catalog(items.as_deref())?;
+            let mut actor =
+                party_combatant(file, &resolved, &attack, actor_ac, actor_hp, &catalog)?;
+            actor.attack_vantage = to_mode(adv).into();
+            actor.cover = actor_cover_opt.map(|c| c.into_cover()).unwrap_or_default();
+            actor.initiative_advantage |= init_adv;
+            actor.health.temp_hp = actor_temp_hp.max(0);
+            add_potions(&mut actor, potions, &catalog);
+            for kind in parse_condition_list(&actor_cond)? {
+                actor.conditions.push(ActiveCondition::indefinite(kind));
             }
+            actor.effects = parse_effect_list(&effect, "setup")?;
+            let enemy_conds = parse_condition_list(&enemy_cond)?;
+            let enemy_effects = parse_effect_list(&enemy_effect, "setup")?;
+            let mut tactics = load_tactics(tactics.as_deref())?;
+            tactics.nonlethal |= nonlethal;
+            let policy = ShortRestPolicy {
+                heal_to_pct: heal_to,
+                reserve: keep_hit_dice,
+            };
+            let mut dice = Dice::from_seed(seed);
 
-            impl EnemyState {
-                fn from_enc(
-                    e: EncounterEnemy,
-                    override_cover: Option<Cover>,
-                    mut log: impl FnMut(String),
-                ) -> Self {
-                    let resist = e
-                        .resistances
-                        .iter()
-                        .filter_map(|s| parse_dtype_str(s))
-                        .collect();
-                    let vuln = e
-                        .vulnerabilities
-                        .iter()
-                        .filter_map(|s| parse_dtype_str(s))
-                        .collect();
-                    let immune = e
-                        .immunities
-                        .iter()
-                        .filter_map(|s| parse_dtype_str(s))
-                        .collect();
-                    let mut conditions = Vec::new();
-                    add_initial_conditions(&e.name, e.conditions.clone(), &mut conditions, |msg| {
-                        log(msg);
-                    });
-                    let cover = override_cover.unwrap_or_else(|| e.cover.unwrap_or(Cover::None));
-                    EnemyState {
-                        name: e.name,
-                        ac: e.ac,
-                        hp: e.hp,
-                        cover,
-                        dex_mod: e.dex_mod,
-                        abilities: e.abilities,
-                        attacks: e.attacks,
-                        resist,
-                        vuln,
-                        immune,
-                        conditions,
+            for path in &encounter {
+                let encounter_data = read_encounter_auto(path)?;
+                if encounter_data.enemies.is_empty() {
+                    anyhow::bail!("encounter must contain at least one enemy");
+                }
+                println!(
+                    "Encounter: {} — {} (AC {}, HP {}) vs {} enemies",
+                    encounter_data.name,
+                    actor.name,
+                    actor.armor_class(),
+                    actor.health.hp,
+                    encounter_data.enemies.len()
+                );
+                let mut combatants = vec![actor];
+                for data in &encounter_data.enemies {
+                    let mut enemy = Combatant::from_target(data);
+                    if let Some(cover) = enemy_cover_opt {
+                        enemy.cover = cover.into_cover();
+                    }
+                    for &kind in &enemy_conds {
+                        enemy.conditions.push(ActiveCondition::indefinite(kind));
                     }
+                    enemy.effects = enemy_effects.clone();
+                    combatants.push(enemy);
                 }
-
-                fn dexterity_mod(&self) -> i32 {
-                    if let Some(ref scores) = self.abilities {
-                        scores.mod_of(Ability::Dex)
+                println!("---");
+
+                let opts = CombatOptions {
+                    max_rounds,
+                    focus: focus
+                        .map(|f| f.into_focus())
+                        .unwrap_or(encounter_data.focus),
+                    tactics,
+                    ambush: ambush.map(SideArg::into_side).or(encounter_data.ambush),
+                    crit_rule: crit_rule.into_rule(),
+                    sunlight: sunlight || encounter_data.sunlight,
+                    downed: downed
+                        .map(DownedArg::into_policy)
+                        .unwrap_or(encounter_data.downed),
+                };
+                let out = run_combat(combatants, &opts, &mut dice);
+                print_combat_log(&out, explain);
+
+                println!("---");
+                let mut survivors = out.combatants.into_iter();
+                actor = survivors.next().expect("party member comes first");
+                let remaining: Vec<_> = survivors
+                    .filter(|e| e.health.hp > 0)
+                    .map(|e| format!("{} ({} HP)", e.name, e.health.hp))
+                    .collect();
+                let enemies_down = remaining.is_empty();
+                if actor.is_dead() && enemies_down {
+                    println!("Result: Mutual KO.");
+                } else if actor.is_dead() {
+                    println!(
+                        "Result: {} falls. Remaining: {}",
+                        actor.name,
+                        remaining.join(", ")
+                    );
+                } else if enemies_down {
+                    if actor.health.hp > 0 {
+                        println!(
+                            "Result: {} victorious with {} HP left.",
+                            actor.name, actor.health.hp
+                        );
                     } else {
-                        self.dex_mod
+                        println!("Result: {} victorious but at 0 HP.", actor.name);
                     }
-                }
-            }
-
-            let mut enemies: Vec<EnemyState> = encounter_data
-                .enemies
-                .into_iter()
-                .map(|e| EnemyState::from_enc(e, enemy_cover_override, |msg| println!("{}", msg)))
-                .collect();
-
-            let mut actor_conditions: Vec<ActiveCondition> = Vec::new();
-            add_initial_conditions(
-                "Actor",
-                parse_condition_list(&actor_cond),
-                &mut actor_conditions,
-                |msg| println!("{}", msg),
-            );
-
-            let enemy_cli_conditions = parse_condition_list(&enemy_cond);
-            if !enemy_cli_conditions.is_empty() {
-                for enemy in &mut enemies {
-                    add_initial_conditions(
-                        &enemy.name,
-                        enemy_cli_conditions.clone(),
-                        &mut enemy.conditions,
-                        |msg| println!("{}", msg),
+                } else if actor.health.hp <= 0 {
+                    println!(
+                        "Result: {} is unconscious at 0 HP. Remaining: {}",
+                        actor.name,
+                        remaining.join(", ")
+                    );
+                } else {
+                    println!(
+                        "Result: Max rounds reached ({} {} HP, Enemies: {}).",
+                        actor.name,
+                        actor.health.hp,
+                        remaining.join(", ")
                     );
                 }
-            }
 
-            fn enemies_defeated(enemies: &[EnemyState]) -> bool {
-                enemies.iter().all(|e| e.hp <= 0)
-            }
-
-            fn select_enemy_target(
-                strategy: &str,
-                enemies: &[EnemyState],
-                rng: &mut Dice,
-            ) -> Option<usize> {
-                let alive: Vec<(usize, i32)> = enemies
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, e)| e.hp > 0)
-                    .map(|(idx, e)| (idx, e.hp))
-                    .collect();
-                if alive.is_empty() {
-                    return None;
+                if !enemies_down {
+                    break;
                 }
-                match strategy {
-                    "lowest" => alive
-                        .into_iter()
-                        .min_by_key(|&(idx, hp)| (hp, idx))
-                        .map(|(idx, _)| idx),
-                    "random" => {
-                        let len = alive.len();
-                        let sides = len.min(u8::MAX as usize) as u8;
-                        let roll = rng.die(sides) as usize - 1;
-                        let choice = roll.min(len - 1);
-                        Some(alive[choice].0)
-                    }
-                    _ => alive
-                        .into_iter()
-                        .min_by_key(|&(idx, _)| idx)
-                        .map(|(idx, _)| idx),
+                take_rest(&mut actor, rest, &policy, &mut dice);
+                if !actor.is_standing() {
+                    break;
                 }
+                println!();
             }
-
-            struct InitiativeEntry {
-                total: i32,
-                roll: i32,
-                kind: u8,
-                index: usize,
-            }
-
-            let mut initiative: Vec<InitiativeEntry> = Vec::new();
-            let actor_roll = rng.d20(engine::AdMode::Normal) as i32;
-            initiative.push(InitiativeEntry {
-                total: actor_roll + actor_dex_mod,
-                roll: actor_roll,
-                kind: 0,
-                index: 0,
-            });
-
-            for (idx, enemy) in enemies.iter().enumerate() {
-                let roll = rng.d20(engine::AdMode::Normal) as i32;
-                initiative.push(InitiativeEntry {
-                    total: roll + enemy.dexterity_mod(),
-                    roll,
-                    kind: 1,
-                    index: idx,
-                });
-            }
-
-            initiative.sort_by(|a, b| {
-                b.total
-                    .cmp(&a.total)
-                    .then_with(|| b.roll.cmp(&a.roll))
-                    .then_with(|| a.kind.cmp(&b.kind))
-                    .then_with(|| a.index.cmp(&b.index))
-            });
-
-            let encounter_name = if encounter_data.name.is_empty() {
-                "Encounter".to_string()
+        }
+        Cmd::Validate { dir, file, kind } => {
+            let (files_checked, issues) = if let Some(path) = file {
+                let issues = validate_file(&path, kind.map(KindArg::into_kind))?;
+                (1, issues)
             } else {
-                encounter_data.name
+                let report = validate_content_dir(&dir)?;
+                (report.files_checked, report.issues)
             };
-
-            println!(
-                "Encounter: {} vs {} enemies (focus: {})",
-                encounter_name,
-                enemies.len(),
-                focus_strategy
-            );
-            println!(
-                "Actor: AC {} HP {} | Weapon: {} [{}] using {:?}{}",
-                actor_ac,
-                actor_hp,
-                resolved.name,
-                dd_to_string(dmg_spec),
-                chosen_ability,
-                if proficient {
-                    " (proficient)"
-                } else {
-                    " (no prof)"
-                }
-            );
-            println!("Enemies:");
-            for enemy in &enemies {
-                println!("  - {} (AC {} HP {})", enemy.name, enemy.ac, enemy.hp);
+            for issue in &issues {
+                println!("{}", issue);
             }
-
-            let mut actor_health = Health::new(actor_hp);
-            let mut auto_potion_left = auto_potion;
-            let mut round = 1;
-
-            while round <= max_rounds
-                && !matches!(actor_health.state, LifeState::Dead)
-                && !enemies_defeated(&enemies)
-            {
-                println!("=== Round {} ===", round);
-                for entry in &initiative {
-                    if matches!(actor_health.state, LifeState::Dead) || enemies_defeated(&enemies) {
-                        break;
-                    }
-                    match entry.kind {
-                        0 => {
-                            if let Some(outcome) = process_death_save_start_of_turn(
-                                "Actor",
-                                &mut actor_health,
-                                || rng.d20(AdMode::Normal) as i32,
-                                |msg| println!("{}", msg),
-                            ) {
-                                println!("[TURN][Actor] death save: {}", outcome);
-                            }
-
-                            process_turn_boundary(
-                                TurnBoundary::StartOfTurn,
-                                "Actor",
-                                &mut actor_conditions,
-                                |ability, _dc| {
-                                    let roll = rng.d20(AdMode::Normal) as i32;
-                                    let total = roll + actor.save_mod(ability);
-                                    (roll, total)
-                                },
-                                |msg| println!("{}", msg),
-                            );
-
-                            match actor_health.state {
-                                LifeState::Dead => {
-                                    println!("[TURN][Actor] is dead. Skipping.");
-                                }
-                                LifeState::Unconscious { .. } => {
-                                    println!("[TURN][Actor] is unconscious. Skipping actions.");
-                                }
-                                LifeState::Conscious => {
-                                    if let Some(target_idx) =
-                                        select_enemy_target(&focus_strategy, &enemies, &mut rng)
-                                    {
-                                        let enemy = &mut enemies[target_idx];
-                                        if enemy.hp > 0 {
-                                            let style = if resolved.ranged {
-                                                AttackStyle::Ranged
-                                            } else {
-                                                AttackStyle::Melee
-                                            };
-                                            let base_vantage: Vantage = mode.into();
-                                            let cond_vantage = vantage_from_conditions(
-                                                &actor_conditions,
-                                                &enemy.conditions,
-                                                style,
-                                            );
-                                            let final_mode: AdMode =
-                                                base_vantage.combine(cond_vantage).into();
-                                            let enemy_effective_ac =
-                                                enemy.ac + enemy.cover.ac_bonus();
-                                            println!("[ACTION][Actor] targets {}", enemy.name);
-                                            log_defense(&enemy.name, enemy.ac, enemy.cover);
-                                            let atk = engine::attack(
-                                                &mut rng,
-                                                final_mode,
-                                                attack_bonus,
-                                                enemy_effective_ac,
-                                            );
-                                            log_attack_event("Actor", &atk);
-                                            if atk.hit {
-                                                let is_crit = atk.is_crit;
-                                                let raw = engine::damage(
-                                                    &mut rng, dmg_spec, damage_mod, is_crit,
-                                                );
-                                                log_damage_event(
-                                                    "Actor",
-                                                    dmg_spec,
-                                                    damage_mod,
-                                                    is_crit,
-                                                    raw,
-                                                    Some(dtype),
-                                                );
-                                                let dmg = engine::adjust_damage_by_type(
-                                                    raw,
-                                                    dtype,
-                                                    &enemy.resist,
-                                                    &enemy.vuln,
-                                                    &enemy.immune,
-                                                );
-                                                if dmg != raw {
-                                                    println!(
-                                                        "[DMG][Actor] adjusted for resistances/vulnerabilities => {}",
-                                                        dmg
-                                                    );
-                                                }
-                                                enemy.hp = (enemy.hp - dmg).max(0);
-                                                println!(
-                                                    "[HP][{}] {} HP remaining",
-                                                    enemy.name, enemy.hp
-                                                );
-                                            } else {
-                                                println!(
-                                                    "[HP][{}] {} HP remaining",
-                                                    enemy.name, enemy.hp
-                                                );
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-
-                            process_turn_boundary(
-                                TurnBoundary::EndOfTurn,
-                                "Actor",
-                                &mut actor_conditions,
-                                |ability, _dc| {
-                                    let roll = rng.d20(AdMode::Normal) as i32;
-                                    let total = roll + actor.save_mod(ability);
-                                    (roll, total)
-                                },
-                                |msg| println!("{}", msg),
-                            );
-                        }
-                        _ => {
-                            if let Some(enemy) = enemies.get_mut(entry.index) {
-                                if enemy.hp <= 0 {
-                                    continue;
-                                }
-
-                                let abilities_ref = enemy.abilities.clone();
-                                let dex_mod = enemy.dex_mod;
-                                process_turn_boundary(
-                                    TurnBoundary::StartOfTurn,
-                                    &enemy.name,
-                                    &mut enemy.conditions,
-                                    |ability, _dc| {
-                                        let roll = rng.d20(AdMode::Normal) as i32;
-                                        let modifier = abilities_ref
-                                            .as_ref()
-                                            .map(|scores| scores.mod_of(ability))
-                                            .unwrap_or_else(|| {
-                                                if ability == Ability::Dex {
-                                                    dex_mod
-                                                } else {
-                                                    0
-                                                }
-                                            });
-                                        let total = roll + modifier;
-                                        (roll, total)
-                                    },
-                                    |msg| println!("{}", msg),
-                                );
-
-                                if let Some(attack) = enemy.attacks.first() {
-                                    let style = if attack.ranged {
-                                        AttackStyle::Ranged
-                                    } else {
-                                        AttackStyle::Melee
-                                    };
-                                    let base_vantage = Vantage::Normal;
-                                    let cond_vantage = vantage_from_conditions(
-                                        &enemy.conditions,
-                                        &actor_conditions,
-                                        style,
-                                    );
-                                    let final_mode: AdMode =
-                                        base_vantage.combine(cond_vantage).into();
-                                    println!("[ACTION][{}] uses {}", enemy.name, attack.name);
-                                    let enemy_label = format!("{} ({})", enemy.name, attack.name);
-                                    let effective_actor_ac = actor_ac + actor_cover.ac_bonus();
-                                    log_defense("Actor", actor_ac, actor_cover);
-                                    let atk = engine::attack(
-                                        &mut rng,
-                                        final_mode,
-                                        attack.to_hit,
-                                        effective_actor_ac,
-                                    );
-                                    log_attack_event(&enemy_label, &atk);
-                                    if atk.hit {
-                                        let is_crit = atk.is_crit;
-                                        let dmg = engine::damage(&mut rng, attack.dice, 0, is_crit);
-                                        log_damage_event(
-                                            &enemy_label,
-                                            attack.dice,
-                                            0,
-                                            is_crit,
-                                            dmg,
-                                            attack.damage_type,
-                                        );
-                                        let dropped = apply_damage(
-                                            "Actor",
-                                            &mut actor_health,
-                                            &mut actor_conditions,
-                                            dmg,
-                                            |msg| println!("{}", msg),
-                                        );
-                                        println!("[HP][Actor] {} HP remaining", actor_health.hp);
-                                        if dropped && auto_potion_left {
-                                            heal("Actor", &mut actor_health, 7, |msg| {
-                                                println!("{}", msg)
-                                            });
-                                            auto_potion_left = false;
-                                            println!(
-                                                "[ITEM][Actor] Auto-potion consumed (2d4+2 ~ 7)"
-                                            );
-                                        }
-                                        if let Some(spec) = attack.apply_condition.as_ref() {
-                                            maybe_apply_on_hit_condition(
-                                                "Actor",
-                                                &mut actor_conditions,
-                                                spec,
-                                                |ability, _dc| {
-                                                    let roll = rng.d20(AdMode::Normal) as i32;
-                                                    let total = roll + actor.save_mod(ability);
-                                                    (roll, total)
-                                                },
-                                                |msg| println!("{}", msg),
-                                            );
-                                        }
-                                    } else {
-                                        println!("[HP][Actor] {} HP remaining", actor_health.hp);
-                                    }
-                                }
-
-                                let abilities_ref_end = enemy.abilities.clone();
-                                let dex_mod_end = enemy.dex_mod;
-                                process_turn_boundary(
-                                    TurnBoundary::EndOfTurn,
-                                    &enemy.name,
-                                    &mut enemy.conditions,
-                                    |ability, _dc| {
-                                        let roll = rng.d20(AdMode::Normal) as i32;
-                                        let modifier = abilities_ref_end
-                                            .as_ref()
-                                            .map(|scores| scores.mod_of(ability))
-                                            .unwrap_or_else(|| {
-                                                if ability == Ability::Dex {
-                                                    dex_mod_end
-                                                } else {
-                                                    0
-                                                }
-                                            });
-                                        let total = roll + modifier;
-                                        (roll, total)
-                                    },
-                                    |msg| println!("{}", msg),
-                                );
-                            }
-                        }
-                    }
-                }
-                round += 1;
-            }
-
-            println!("---");
-            let enemies_down = enemies_defeated(&enemies);
-            let actor_dead = matches!(actor_health.state, LifeState::Dead);
-            let actor_unconscious = matches!(actor_health.state, LifeState::Unconscious { .. });
-            let actor_hp_left = actor_health.hp;
-            if actor_dead && enemies_down {
-                println!("Result: Mutual KO.");
-            } else if actor_dead {
-                let remaining: Vec<_> = enemies
-                    .iter()
-                    .filter(|e| e.hp > 0)
-                    .map(|e| format!("{} ({} HP)", e.name, e.hp))
-                    .collect();
-                println!("Result: Actor falls. Remaining: {}", remaining.join(", "));
-            } else if enemies_down {
-                if actor_hp_left > 0 {
-                    println!("Result: Actor victorious with {} HP left.", actor_hp_left);
-                } else {
-                    println!("Result: Actor victorious but at 0 HP.");
-                }
-            } else if actor_unconscious {
-                let remaining: Vec<_> = enemies
-                    .iter()
-                    .filter(|e| e.hp > 0)
-                    .map(|e| format!("{} ({} HP)", e.name, e.hp))
-                    .collect();
-                println!(
-                    "Result: Actor is unconscious at 0 HP. Remaining: {}",
-                    remaining.join(", ")
-                );
-            } else {
-                let remaining: Vec<_> = enemies
-                    .iter()
-                    .filter(|e| e.hp > 0)
-                    .map(|e| format!("{} ({} HP)", e.name, e.hp))
-                    .collect();
-                println!(
-                    "Result: Max rounds reached (Actor {} HP, Enemies: {}).",
-                    actor_hp_left,
-                    if remaining.is_empty() {
-                        "all down".to_string()
-                    } else {
-                        remaining.join(", ")
-                    }
+            if !issues.is_empty() {
+                anyhow::bail!(
+                    "{} issue(s) found in {} file(s) checked",
+                    issues.len(),
+                    files_checked
                 );
             }
-
-            if short_rest && !actor_dead {
-                heal("Actor", &mut actor_health, 5, |msg| println!("{}", msg));
-                println!("[REST][Actor] Short rest: +5 HP");
-            }
+            println!("OK: {} file(s) checked, no issues", files_checked);
         }
+        Cmd::Character { cmd } => run_character(cmd)?,
+        Cmd::Campaign { cmd } => run_campaign_cmd(cmd)?,
         Cmd::FfiVersion => {
             println!("solo5e-ffi 0.1.0");
         }
@@ -1782,44 +1664,67 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn demo_checks(actor: Actor, seed: u64, mode: AdMode, dc: i32) {
+fn conditions_from(src: &Option<String>) -> anyhow::Result<Vec<ActiveCondition>> {
+    Ok(parse_condition_list(src)?
+        .into_iter()
+        .map(ActiveCondition::indefinite)
+        .collect())
+}
+
+/// ` (disadvantage (Poisoned))`, or nothing when no source applies.
+fn vantage_suffix(vantage: &VantageBreakdown) -> String {
+    if vantage.sources.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", vantage)
+    }
+}
+
+fn demo_checks(actor: Actor, seed: u64, mode: AdMode, dc: i32, conds: &[ActiveCondition]) {
     let mut dice = Dice::from_seed(seed);
 
     // Ability check: STR
     let str_mod = actor.ability_mod(Ability::Str);
-    let a = actor.ability_check(&mut dice, Ability::Str, mode, dc);
+    let a = actor.ability_check_with_conditions(&mut dice, Ability::Str, conds, mode, dc);
     println!(
-        "ability STR (mod={:+}): roll={} total={} vs dc={} => {}",
+        "ability STR (mod={:+}): roll={} total={} vs dc={} => {}{}",
         str_mod,
-        a.roll,
-        a.total,
-        a.dc,
-        if a.passed { "SUCCESS" } else { "FAIL" }
+        a.result.roll,
+        a.result.total,
+        a.result.dc,
+        if a.result.passed { "SUCCESS" } else { "FAIL" },
+        vantage_suffix(&a.vantage)
     );
 
     // Skill check: Athletics
     let ath_mod = actor.skill_mod(Skill::Athletics);
-    let s = actor.skill_check(&mut dice, Skill::Athletics, mode, dc);
+    let s = actor.skill_check_with_conditions(&mut dice, Skill::Athletics, conds, mode, dc);
     println!(
-        "skill Athletics (mod={:+}): roll={} total={} vs dc={} => {}",
+        "skill Athletics (mod={:+}): roll={} total={} vs dc={} => {}{}",
         ath_mod,
-        s.roll,
-        s.total,
-        s.dc,
-        if s.passed { "SUCCESS" } else { "FAIL" }
+        s.result.roll,
+        s.result.total,
+        s.result.dc,
+        if s.result.passed { "SUCCESS" } else { "FAIL" },
+        vantage_suffix(&s.vantage)
     );
 
-    // Saving throw: CON
-    let con_mod = actor.save_mod(Ability::Con);
-    let sv = actor.saving_throw(&mut dice, Ability::Con, mode, dc);
-    println!(
-        "save CON (mod={:+}): roll={} total={} vs dc={} => {}",
-        con_mod,
-        sv.roll,
-        sv.total,
-        sv.dc,
-        if sv.passed { "SUCCESS" } else { "FAIL" }
-    );
+    // Saving throws: CON, then DEX (Restrained, Stunned and Paralyzed affect it)
+    for ability in [Ability::Con, Ability::Dex] {
+        let save_mod = actor.save_mod(ability);
+        let sv = actor.saving_throw_with_conditions(&mut dice, ability, conds, mode, dc);
+        println!(
+            "save {} (mod={:+}): roll={} total={} vs dc={} => {}{}{}",
+            format!("{:?}", ability).to_uppercase(),
+            save_mod,
+            sv.roll,
+            sv.total,
+            dc,
+            if sv.success { "SUCCESS" } else { "FAIL" },
+            sv.note.map(|n| format!(" [{}]", n)).unwrap_or_default(),
+            vantage_suffix(&sv.vantage)
+        );
+    }
 }
 
 fn read_text_auto(path: &std::path::Path) -> anyhow::Result<String> {
@@ -1832,14 +1737,14 @@ fn read_text_auto(path: &std::path::Path) -> anyhow::Result<String> {
     }
 }
 
-fn read_target_auto(path: &std::path::Path) -> anyhow::Result<Target> {
+fn read_target_auto(path: &std::path::Path) -> anyhow::Result<TargetData> {
     let text = read_text_auto(path)?;
-    Ok(serde_json::from_str(&text)?)
+    parse_target(&text).with_context(|| format!("failed to load {}", path.display()))
 }
 
-fn read_encounter_auto(path: &std::path::Path) -> anyhow::Result<Encounter> {
+fn read_encounter_auto(path: &std::path::Path) -> anyhow::Result<EncounterData> {
     let text = read_text_auto(path)?;
-    Ok(serde_json::from_str(&text)?)
+    parse_encounter(&text).with_context(|| format!("failed to load {}", path.display()))
 }
 
 fn parse_damage_dice(s: &str) -> anyhow::Result<engine::DamageDice> {
@@ -1858,8 +1763,7 @@ fn parse_damage_dice(s: &str) -> anyhow::Result<engine::DamageDice> {
 
 fn load_weapons_file(path: &std::path::Path) -> anyhow::Result<Vec<engine::Weapon>> {
     let text = read_text_auto(path)?;
-    let v: Vec<engine::Weapon> = serde_json::from_str(&text)?;
-    Ok(v)
+    Ok(parse_weapons(&text)?)
 }
 
 fn find_weapon_in<'a>(name: &str, list: &'a [engine::Weapon]) -> Option<&'a engine::Weapon> {
@@ -1878,6 +1782,8 @@ struct ResolvedWeapon {
     ranged: bool,
     versatile: Option<engine::DamageDice>,
     damage_type: Option<engine::DamageType>,
+    two_handed: bool,
+    source: AttackSource,
 }
 
 fn resolve_weapon(
@@ -1885,7 +1791,7 @@ fn resolve_weapon(
     weapons_path: Option<&std::path::Path>,
 ) -> anyhow::Result<ResolvedWeapon> {
     let loaded: Option<Vec<engine::Weapon>> = if let Some(path) = weapons_path {
-        load_weapons_file(path).ok()
+        Some(load_weapons_file(path)?)
     } else {
         let default = std::path::Path::new("content/weapons/basic.json");
         load_weapons_file(default).ok()
@@ -1900,6 +1806,8 @@ fn resolve_weapon(
                 ranged: w.ranged,
                 versatile: w.versatile,
                 damage_type: w.damage_type,
+                two_handed: w.two_handed,
+                source: w.source(),
             });
         }
     }
@@ -1915,9 +1823,176 @@ fn resolve_weapon(
             None => None,
         },
         damage_type: preset_damage_type(preset.name),
+        two_handed: preset.two_handed,
+        source: AttackSource::default(),
     })
 }
 
+/// HP for a bare actor JSON (character sheets carry their own max HP).
+const DEFAULT_ACTOR_HP: i32 = 12;
+
+/// Weapon flags shared by `duel` and `encounter`.
+struct AttackArgs {
+    dice: Option<String>,
+    ability: AbilityChoice,
+    no_prof: bool,
+    two_handed: bool,
+    dtype: Option<DType>,
+    magic_weapon: bool,
+    material: Option<MaterialArg>,
+}
+
+/// Build the party member from `--file`: a character sheet (has `class`) keeps its
+/// features and max HP, a bare actor gets `--actor-hp` or 12; no file means the sample fighter.
+fn party_combatant(
+    file: Option<PathBuf>,
+    weapon: &ResolvedWeapon,
+    args: &AttackArgs,
+    ac: i32,
+    hp: Option<i32>,
+    catalog: &[ItemDef],
+) -> anyhow::Result<Combatant> {
+    let (character, actor) = match file {
+        Some(path) => {
+            let text = read_text_auto(&path)?;
+            let value: serde_json::Value = serde_json::from_str(&text)?;
+            if value.get("class").is_some() {
+                let c = parse_content::<Character>("character", &text)?;
+                let actor = c.actor.clone();
+                (Some(c), actor)
+            } else {
+                (None, parse_content::<Actor>("actor", &text)?)
+            }
+        }
+        None => {
+            let mut c = sample_fighter();
+            c.name = "Actor".to_string();
+            let actor = c.actor.clone();
+            (Some(c), actor)
+        }
+    };
+    build_party_member(character, actor, weapon, args, ac, hp, catalog)
+}
+
+fn build_party_member(
+    character: Option<Character>,
+    actor: Actor,
+    weapon: &ResolvedWeapon,
+    args: &AttackArgs,
+    ac: i32,
+    hp: Option<i32>,
+    catalog: &[ItemDef],
+) -> anyhow::Result<Combatant> {
+    let ability = pick_ability(args.ability, weapon);
+    let dice = match args.dice {
+        Some(ref s) => parse_damage_dice(s)?,
+        None if args.two_handed => weapon.versatile.unwrap_or(weapon.dice),
+        None => weapon.dice,
+    };
+    let attack = AttackProfile {
+        name: weapon.name.clone(),
+        to_hit: actor.attack_bonus(ability, !args.no_prof),
+        dice,
+        damage_mod: actor.damage_mod(ability),
+        damage_type: resolve_damage_type(args.dtype, weapon),
+        ranged: weapon.ranged,
+        finesse: weapon.finesse,
+        two_handed: weapon.two_handed || (args.two_handed && weapon.versatile.is_some()),
+        ability: Some(ability),
+        weapon: true,
+        source: AttackSource {
+            magical: weapon.source.magical || args.magic_weapon,
+            material: args
+                .material
+                .map(MaterialArg::into_material)
+                .or(weapon.source.material),
+        },
+        apply_condition: None,
+        drain: None,
+    };
+
+    let mut member = match character {
+        Some(c) => {
+            let mut member = Combatant::from_character(&c, ac, attack);
+            member.inventory = resolve_inventory(&c.inventory, catalog)?;
+            member
+        }
+        None => Combatant::from_actor("Actor", actor, DEFAULT_ACTOR_HP, ac, attack),
+    };
+    if let Some(hp) = hp {
+        member.health = Health::new(hp);
+    }
+    Ok(member)
+}
+
+fn take_rest(
+    actor: &mut Combatant,
+    rest: Option<RestArg>,
+    policy: &ShortRestPolicy,
+    dice: &mut Dice,
+) {
+    match rest {
+        Some(RestArg::Short) => actor.short_rest(policy, dice, |msg| println!("{}", msg)),
+        Some(RestArg::Long) => actor.long_rest(|msg| println!("{}", msg)),
+        None => {}
+    }
+}
+
+/// Built-in items plus an optional catalog file; the file's ids win on a clash.
+fn load_item_catalog(path: Option<&std::path::Path>) -> anyhow::Result<Vec<ItemDef>> {
+    let mut catalog = match path {
+        Some(p) => parse_items(&read_text_auto(p)?)
+            .with_context(|| format!("failed to parse items {}", p.display()))?,
+        None => Vec::new(),
+    };
+    for text in builtin_items().values() {
+        catalog.extend(parse_items(text)?);
+    }
+    Ok(catalog)
+}
+
+fn add_potions(actor: &mut Combatant, count: u32, catalog: &[ItemDef]) {
+    if count == 0 {
+        return;
+    }
+    match actor
+        .inventory
+        .iter_mut()
+        .find(|s| s.def.id == "potion_of_healing")
+    {
+        Some(stack) => stack.quantity += count,
+        None => {
+            let def = find_item(catalog, "potion_of_healing").expect("built-in item");
+            actor.inventory.push(ItemStack::new(def.clone(), count));
+        }
+    }
+}
+
+/// `id` or `id:count`, as taken by `character new --inventory`.
+fn parse_inventory_entry(src: &str) -> Result<InventoryEntry, String> {
+    let (item, quantity) = match src.split_once(':') {
+        Some((id, n)) => (
+            id,
+            n.trim()
+                .parse::<u32>()
+                .map_err(|_| format!("bad item count in '{}'", src))?,
+        ),
+        None => (src, 1),
+    };
+    Ok(InventoryEntry {
+        item: item.trim().to_string(),
+        quantity,
+        charges: None,
+    })
+}
+
+fn load_tactics(path: Option<&std::path::Path>) -> anyhow::Result<Tactics> {
+    match path {
+        Some(p) => Ok(parse_content("tactics", &read_text_auto(p)?)?),
+        None => Ok(Tactics::default()),
+    }
+}
+
 fn resolve_damage_type(dtype: Option<DType>, weapon: &ResolvedWeapon) -> engine::DamageType {
     dtype
         .map(to_engine_dtype)
@@ -1958,26 +2033,6 @@ fn to_engine_dtype(dt: DType) -> engine::DamageType {
     }
 }
 
-fn parse_dtype_str(s: &str) -> Option<engine::DamageType> {
-    use engine::DamageType::*;
-    match &*s.to_lowercase() {
-        "bludgeoning" => Some(Bludgeoning),
-        "piercing" => Some(Piercing),
-        "slashing" => Some(Slashing),
-        "fire" => Some(Fire),
-        "cold" => Some(Cold),
-        "lightning" => Some(Lightning),
-        "acid" => Some(Acid),
-        "poison" => Some(Poison),
-        "psychic" => Some(Psychic),
-        "radiant" => Some(Radiant),
-        "necrotic" => Some(Necrotic),
-        "thunder" => Some(Thunder),
-        "force" => Some(Force),
-        _ => None,
-    }
-}
-
 fn preset_damage_type(name: &str) -> Option<engine::DamageType> {
     match name.to_lowercase().as_str() {
         "longsword" | "greatsword" => Some(engine::DamageType::Slashing),
@@ -1995,6 +2050,7 @@ struct WeaponPreset {
     finesse: bool,
     ranged: bool,
     versatile: Option<&'static str>, // two-handed dice like "1d10"
+    two_handed: bool,
 }
 
 const WEAPONS: &[WeaponPreset] = &[
@@ -2004,6 +2060,7 @@ const WEAPONS: &[WeaponPreset] = &[
         finesse: false,
         ranged: false,
         versatile: Some("1d10"),
+        two_handed: false,
     },
     WeaponPreset {
         name: "shortsword",
@@ -2011,6 +2068,7 @@ const WEAPONS: &[WeaponPreset] = &[
         finesse: true,
         ranged: false,
         versatile: None,
+        two_handed: false,
     },
     WeaponPreset {
         name: "dagger",
@@ -2018,6 +2076,7 @@ const WEAPONS: &[WeaponPreset] = &[
         finesse: true,
         ranged: false,
         versatile: None,
+        two_handed: false,
     },
     WeaponPreset {
         name: "greatsword",
@@ -2025,6 +2084,7 @@ const WEAPONS: &[WeaponPreset] = &[
         finesse: false,
         ranged: false,
         versatile: None,
+        two_handed: true,
     },
     WeaponPreset {
         name: "longbow",
@@ -2032,6 +2092,7 @@ const WEAPONS: &[WeaponPreset] = &[
         finesse: false,
         ranged: true,
         versatile: None,
+        two_handed: true,
     },
 ];
 
@@ -1,22 +1,36 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 
-use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::conditions::{
-    maybe_apply_on_hit_condition, process_turn_boundary, vantage_from_conditions, ActiveCondition,
-    AttackStyle, ConditionKind, TurnBoundary, Vantage,
+use crate::campaign::{Campaign, CampaignNode, CampaignState, Loadout};
+use crate::character::{sample_fighter, Character};
+use crate::combat::{
+    run_combat, weapon_ability, AttackProfile, CombatOptions, Combatant, Side, DEFAULT_MAX_ROUNDS,
 };
-use crate::life::{apply_damage, process_death_save_start_of_turn, Health, LifeState};
-use crate::{Ability, AbilityScores, Actor, AdMode, Cover, DamageDice, DamageType, Dice, Weapon};
+use crate::conditions::{ActiveCondition, ConditionKind};
+use crate::content::{
+    parse_campaign, parse_content, parse_effects, parse_encounter, parse_items, parse_target,
+    parse_weapons, DownedPolicy, EncounterData,
+};
+use crate::effects::{resolve_effects, EffectDef};
+use crate::error::{EngineError, Result};
+use crate::explain::RollExplanation;
+use crate::features::Tactics;
+use crate::items::{resolve_inventory, ItemDef};
+use crate::life::{Health, ShortRestPolicy};
+use crate::{CritRule, Dice, Weapon};
 
 const DEFAULT_ACTOR_AC: i32 = 16;
-const DEFAULT_ACTOR_HP: i32 = 12;
-const MAX_ROUNDS: u32 = 30;
+const MAX_ROUNDS: u32 = DEFAULT_MAX_ROUNDS;
+/// Upper bound on `simulate_duel_many` samples so a bad request can't hang the caller.
+pub const MAX_SAMPLES: u32 = 100_000;
+/// Upper bound on steps in `run_campaign`; campaign graphs may loop.
+pub const MAX_CAMPAIGN_STEPS: u32 = 1_000;
 
 #[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub struct DuelConfig {
     #[serde(default)]
     pub target_path: Option<String>,
@@ -28,13 +42,42 @@ pub struct DuelConfig {
     pub weapons_id: Option<String>,
     pub weapon: String,
     #[serde(default)]
-    pub actor_conditions: Vec<String>,
+    pub actor_conditions: Vec<ConditionKind>,
     #[serde(default)]
-    pub enemy_conditions: Vec<String>,
+    pub enemy_conditions: Vec<ConditionKind>,
     #[serde(default)]
     pub seed: u64,
+    /// Defaults to the character's max HP.
     #[serde(default)]
     pub actor_hp: Option<i32>,
+    /// Temporary hit points the actor starts with.
+    #[serde(default)]
+    pub actor_temp_hp: i32,
+    /// Character JSON (from `character new`); the sample fighter when absent.
+    #[serde(default)]
+    pub character_path: Option<String>,
+    /// Extra item catalog for the character's inventory (the built-in items always load).
+    #[serde(default)]
+    pub items_path: Option<String>,
+    #[serde(default)]
+    pub tactics: Tactics,
+    /// The side that starts hidden (`party` or `enemies`); none rolls for surprise.
+    #[serde(default)]
+    pub ambush: Option<Side>,
+    /// Roll the actor's initiative with advantage.
+    #[serde(default)]
+    pub initiative_advantage: bool,
+    /// Built-in effect ids on the actor at the start (`bless`, `shield_of_faith`).
+    #[serde(default)]
+    pub actor_effects: Vec<String>,
+    #[serde(default)]
+    pub enemy_effects: Vec<String>,
+    /// How critical hits roll damage (`double_dice`, `max_plus_roll`, `double_total`).
+    #[serde(default)]
+    pub crit_rule: CritRule,
+    /// Fight in sunlight (Sunlight Sensitivity).
+    #[serde(default)]
+    pub sunlight: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -44,7 +87,10 @@ pub struct DuelResult {
     pub rounds: u32,
     pub actor_hp_end: i32,
     pub enemy_hp_end: i32,
+    pub crit_rule: CritRule,
     pub log: Vec<String>,
+    /// Every roll explained; `log_index` points into `log`.
+    pub rolls: Vec<RollExplanation>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -55,10 +101,11 @@ pub struct DuelStats {
     pub enemy_wins: u32,
     pub draws: u32,
     pub avg_rounds: f32,
+    pub crit_rule: CritRule,
 }
 
 #[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub struct EncounterConfig {
     #[serde(default)]
     pub encounter_path: Option<String>,
@@ -69,7 +116,35 @@ pub struct EncounterConfig {
     #[serde(default)]
     pub actor_hp: Option<i32>,
     #[serde(default)]
-    pub actor_conditions: Vec<String>,
+    pub actor_temp_hp: i32,
+    #[serde(default)]
+    pub actor_conditions: Vec<ConditionKind>,
+    #[serde(default)]
+    pub character_path: Option<String>,
+    /// Extra item catalog for the character's inventory (the built-in items always load).
+    #[serde(default)]
+    pub items_path: Option<String>,
+    #[serde(default)]
+    pub tactics: Tactics,
+    /// Overrides the encounter file's ambush side.
+    #[serde(default)]
+    pub ambush: Option<Side>,
+    #[serde(default)]
+    pub initiative_advantage: bool,
+    /// Built-in effect ids on the actor at the start.
+    #[serde(default)]
+    pub actor_effects: Vec<String>,
+    /// Built-in effect ids on every enemy at the start.
+    #[serde(default)]
+    pub enemy_effects: Vec<String>,
+    #[serde(default)]
+    pub crit_rule: CritRule,
+    /// Fight in sunlight even if the encounter file doesn't say so.
+    #[serde(default)]
+    pub sunlight: bool,
+    /// Overrides whether the encounter's enemies attack downed party members.
+    #[serde(default)]
+    pub downed: Option<DownedPolicy>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -78,86 +153,66 @@ pub struct EncounterResult {
     pub survived: bool,
     pub rounds: u32,
     pub remaining_enemies: u32,
+    pub crit_rule: CritRule,
     pub log: Vec<String>,
+    /// Every roll explained; `log_index` points into `log`.
+    pub rolls: Vec<RollExplanation>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
-struct TargetAttack {
-    name: String,
-    #[serde(rename = "to_hit")]
-    to_hit: i32,
-    dice: DamageDice,
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CampaignConfig {
     #[serde(default)]
-    damage_type: Option<DamageType>,
+    pub campaign_path: Option<String>,
     #[serde(default)]
-    ranged: bool,
+    pub campaign_id: Option<String>,
+    /// Character JSON for a fresh run; the sample fighter when absent. Ignored with `state`.
     #[serde(default)]
-    apply_condition: Option<crate::conditions::ConditionSpec>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct TargetData {
-    name: String,
-    ac: i32,
-    hp: i32,
+    pub character_path: Option<String>,
+    #[serde(default = "default_campaign_weapon")]
+    pub weapon: String,
     #[serde(default)]
-    dex_mod: i32,
+    pub weapons_path: Option<String>,
     #[serde(default)]
-    abilities: Option<AbilityScores>,
+    pub items_path: Option<String>,
     #[serde(default)]
-    attacks: Vec<TargetAttack>,
+    pub actor_ac: Option<i32>,
+    /// Step `n` rolls with `seed + n`, so stepping and running give the same results.
     #[serde(default)]
-    resistances: Vec<String>,
+    pub seed: u64,
     #[serde(default)]
-    vulnerabilities: Vec<String>,
+    pub tactics: Tactics,
     #[serde(default)]
-    immunities: Vec<String>,
+    pub rest_policy: ShortRestPolicy,
     #[serde(default)]
-    conditions: Vec<ConditionKind>,
+    pub crit_rule: CritRule,
+    /// State returned by the previous step; absent to start at the campaign's first node.
     #[serde(default)]
-    cover: Cover,
+    pub state: Option<CampaignState>,
 }
 
-impl TargetData {
-    fn dexterity_mod(&self) -> i32 {
-        if let Some(scores) = &self.abilities {
-            scores.mod_of(Ability::Dex)
-        } else {
-            self.dex_mod
-        }
-    }
-
-    fn ability_mod(&self, ability: Ability) -> i32 {
-        if let Some(scores) = &self.abilities {
-            scores.mod_of(ability)
-        } else if ability == Ability::Dex {
-            self.dex_mod
-        } else {
-            0
-        }
-    }
+fn default_campaign_weapon() -> String {
+    "longsword".to_string()
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct EncounterData {
-    #[serde(default)]
-    name: String,
-    enemies: Vec<TargetData>,
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CampaignStep {
+    pub state: CampaignState,
+    pub log: Vec<String>,
 }
 
 fn load_json_from_path_or_builtin(
+    what: &'static str,
     path: &Option<String>,
     id: &Option<String>,
     map: &HashMap<&'static str, &'static str>,
 ) -> Result<String> {
+    let mut read_error = None;
     if let Some(p) = path {
         match fs::read_to_string(p) {
             Ok(s) => return Ok(s),
-            Err(e) => {
-                if id.is_none() {
-                    return Err(anyhow!("{}", e).context(format!("failed to read JSON from {}", p)));
-                }
-            }
+            Err(e) => read_error = Some(e.to_string()),
         }
     }
 
@@ -165,324 +220,119 @@ fn load_json_from_path_or_builtin(
         if let Some(&text) = map.get(i.as_str()) {
             return Ok(text.to_string());
         } else if path.is_none() {
-            bail!("built-in id '{}' not found", i);
+            return Err(EngineError::not_found(
+                what,
+                i.as_str(),
+                "no built-in with that id",
+            ));
         }
     }
 
     if let Some(p) = path {
-        bail!("failed to load content from path {}", p);
+        return Err(EngineError::not_found(
+            what,
+            p.as_str(),
+            read_error.unwrap_or_default(),
+        ));
     }
 
-    bail!("no content found (path or built-in id required)")
+    Err(EngineError::InvalidConfig(format!(
+        "{what} path or built-in id required"
+    )))
 }
 
 pub fn simulate_duel(cfg: DuelConfig) -> Result<DuelResult> {
     let target_json = {
         let builtins = crate::content::builtin_targets();
-        load_json_from_path_or_builtin(&cfg.target_path, &cfg.target_id, &builtins)?
+        load_json_from_path_or_builtin("target", &cfg.target_path, &cfg.target_id, &builtins)?
     };
     let weapons_json = {
         let builtins = crate::content::builtin_weapons();
-        load_json_from_path_or_builtin(&cfg.weapons_path, &cfg.weapons_id, &builtins)?
+        load_json_from_path_or_builtin("weapons", &cfg.weapons_path, &cfg.weapons_id, &builtins)?
     };
 
-    let target = parse_target_json(&target_json)?;
+    let target = parse_target(&target_json)?;
     if target.attacks.is_empty() {
-        bail!("target has no attacks");
+        return Err(EngineError::InvalidConfig(format!(
+            "target '{}' has no attacks",
+            target.name
+        )));
     }
-    let target_attack = target.attacks[0].clone();
-
-    let weapons = parse_weapons_json(&weapons_json)?;
-    let weapon = find_weapon(&weapons, &cfg.weapon)
-        .cloned()
-        .ok_or_else(|| anyhow!("weapon '{}' not found", cfg.weapon))?;
-
-    let actor = sample_fighter();
-    let actor_hp = cfg.actor_hp.unwrap_or(DEFAULT_ACTOR_HP);
-    let actor_ac = DEFAULT_ACTOR_AC;
-    let mut actor_health = Health::new(actor_hp);
-    let mut enemy_hp = target.hp;
-
-    let actor_weapon_dice = weapon.versatile.unwrap_or(weapon.dice);
-    let actor_damage_type = weapon
-        .damage_type
-        .or_else(|| preset_damage_type(&weapon.name));
-    let actor_style = if weapon.ranged {
-        AttackStyle::Ranged
-    } else {
-        AttackStyle::Melee
-    };
-    let actor_ability = if weapon.ranged || weapon.finesse {
-        Ability::Dex
-    } else {
-        Ability::Str
-    };
-    let actor_attack_bonus = actor.attack_bonus(actor_ability, true);
-    let actor_damage_mod = actor.damage_mod(actor_ability);
-    let actor_mode: Vantage = AdMode::Normal.into();
-
-    let mut logs = Vec::new();
-    let mut actor_conditions: Vec<ActiveCondition> = Vec::new();
-    for cond in parse_condition_list(&cfg.actor_conditions) {
-        logs.push(format!("[COND][Actor] starts with {:?}", cond.kind));
-        actor_conditions.push(cond);
-    }
-
-    let mut enemy_conditions: Vec<ActiveCondition> = Vec::new();
-    for cond in target.conditions.iter().cloned() {
-        logs.push(format!("[COND][{}] starts with {:?}", target.name, cond));
-        enemy_conditions.push(make_active_condition(cond));
-    }
-    for cond in parse_condition_list(&cfg.enemy_conditions) {
-        logs.push(format!(
-            "[COND][{}] starts with {:?}",
-            target.name, cond.kind
-        ));
-        enemy_conditions.push(cond);
-    }
-
-    let mut rng = Dice::from_seed(cfg.seed);
-    let actor_init = rng.d20(AdMode::Normal) as i32 + actor.ability_mod(Ability::Dex);
-    let enemy_init = rng.d20(AdMode::Normal) as i32 + target.dexterity_mod();
-    let mut actor_turn = actor_init >= enemy_init;
-
-    logs.push(format!(
-        "[START] Actor (AC {}, HP {}) vs {} (AC {}, HP {})",
-        actor_ac, actor_hp, target.name, target.ac, target.hp
-    ));
-    logs.push(format!(
-        "[INIT] Actor {} vs {} {} → {} starts",
-        actor_init,
-        target.name,
-        enemy_init,
-        if actor_turn {
-            "Actor"
-        } else {
-            target.name.as_str()
-        }
-    ));
 
-    let resist: HashSet<_> = target
-        .resistances
-        .iter()
-        .filter_map(|s| parse_damage_type(s))
-        .collect();
-    let vuln: HashSet<_> = target
-        .vulnerabilities
-        .iter()
-        .filter_map(|s| parse_damage_type(s))
-        .collect();
-    let immune: HashSet<_> = target
-        .immunities
-        .iter()
-        .filter_map(|s| parse_damage_type(s))
-        .collect();
-
-    let mut rounds = 0u32;
-    while rounds < MAX_ROUNDS && !matches!(actor_health.state, LifeState::Dead) && enemy_hp > 0 {
-        rounds += 1;
-        logs.push(format!(
-            "[ROUND] {} → {}",
-            rounds,
-            if actor_turn {
-                "Actor"
-            } else {
-                target.name.as_str()
-            }
-        ));
-
-        if actor_turn {
-            if let Some(outcome) = process_death_save_start_of_turn(
-                "Actor",
-                &mut actor_health,
-                || rng.d20(AdMode::Normal) as i32,
-                |msg| logs.push(msg),
-            ) {
-                logs.push(format!("[TURN][Actor] death save: {}", outcome));
-            }
-
-            process_turn_boundary(
-                TurnBoundary::StartOfTurn,
-                "Actor",
-                &mut actor_conditions,
-                |ability, _dc| {
-                    let roll = rng.d20(AdMode::Normal) as i32;
-                    let total = roll + actor.save_mod(ability);
-                    (roll, total)
-                },
-                |msg| logs.push(msg),
-            );
-
-            match actor_health.state {
-                LifeState::Dead => {
-                    logs.push("[TURN][Actor] is dead; skipping".to_string());
-                }
-                LifeState::Unconscious { .. } => {
-                    logs.push("[TURN][Actor] is unconscious; skipping actions".to_string());
-                }
-                LifeState::Conscious => {
-                    let cond_vantage =
-                        vantage_from_conditions(&actor_conditions, &enemy_conditions, actor_style);
-                    let final_mode: AdMode = actor_mode.combine(cond_vantage).into();
-                    let effective_enemy_ac = target.ac + target.cover.ac_bonus();
-                    log_defense(&mut logs, &target.name, target.ac, target.cover);
-                    let atk =
-                        crate::attack(&mut rng, final_mode, actor_attack_bonus, effective_enemy_ac);
-                    log_attack(&mut logs, "Actor", &atk);
-                    if atk.hit {
-                        let is_crit = atk.is_crit;
-                        let raw =
-                            crate::damage(&mut rng, actor_weapon_dice, actor_damage_mod, is_crit);
-                        let dtype = actor_damage_type.unwrap_or(DamageType::Slashing);
-                        let dmg = crate::adjust_damage_by_type(raw, dtype, &resist, &vuln, &immune);
-                        let before = enemy_hp;
-                        enemy_hp = (enemy_hp - dmg).max(0);
-                        log_damage(
-                            &mut logs,
-                            "Actor",
-                            actor_weapon_dice,
-                            actor_damage_mod,
-                            is_crit,
-                            dmg,
-                            Some(dtype),
-                        );
-                        logs.push(format!("[HP][{}] {} → {}", target.name, before, enemy_hp));
-                    }
-                }
-            }
-
-            process_turn_boundary(
-                TurnBoundary::EndOfTurn,
-                "Actor",
-                &mut actor_conditions,
-                |ability, _dc| {
-                    let roll = rng.d20(AdMode::Normal) as i32;
-                    let total = roll + actor.save_mod(ability);
-                    (roll, total)
-                },
-                |msg| logs.push(msg),
-            );
-        } else {
-            process_turn_boundary(
-                TurnBoundary::StartOfTurn,
-                &target.name,
-                &mut enemy_conditions,
-                |ability, _dc| {
-                    let roll = rng.d20(AdMode::Normal) as i32;
-                    let total = roll + target.ability_mod(ability);
-                    (roll, total)
-                },
-                |msg| logs.push(msg),
-            );
-
-            if enemy_hp > 0 {
-                let cond_vantage = vantage_from_conditions(
-                    &enemy_conditions,
-                    &actor_conditions,
-                    if target_attack.ranged {
-                        AttackStyle::Ranged
-                    } else {
-                        AttackStyle::Melee
-                    },
-                );
-                let final_mode: AdMode = Vantage::Normal.combine(cond_vantage).into();
-                let effective_actor_ac = actor_ac + Cover::None.ac_bonus();
-                log_defense(&mut logs, "Actor", actor_ac, Cover::None);
-                let atk = crate::attack(
-                    &mut rng,
-                    final_mode,
-                    target_attack.to_hit,
-                    effective_actor_ac,
-                );
-                log_attack(&mut logs, &target_attack.name, &atk);
-                if atk.hit {
-                    let is_crit = atk.is_crit;
-                    let dtype = target_attack.damage_type.unwrap_or(DamageType::Slashing);
-                    let dmg = crate::damage(&mut rng, target_attack.dice, 0, is_crit);
-                    log_damage(
-                        &mut logs,
-                        &target_attack.name,
-                        target_attack.dice,
-                        0,
-                        is_crit,
-                        dmg,
-                        Some(dtype),
-                    );
-                    let dropped = apply_damage(
-                        "Actor",
-                        &mut actor_health,
-                        &mut actor_conditions,
-                        dmg,
-                        |msg| logs.push(msg),
-                    );
-                    logs.push(format!("[HP][Actor] {} HP", actor_health.hp));
-                    if dropped {
-                        logs.push("[ITEM][Actor] drops to 0 HP".to_string());
-                    }
-                    if let Some(spec) = target_attack.apply_condition.as_ref() {
-                        maybe_apply_on_hit_condition(
-                            "Actor",
-                            &mut actor_conditions,
-                            spec,
-                            |ability, _dc| {
-                                let roll = rng.d20(AdMode::Normal) as i32;
-                                let total = roll + actor.save_mod(ability);
-                                (roll, total)
-                            },
-                            |msg| logs.push(msg),
-                        );
-                    }
-                }
-            }
-
-            process_turn_boundary(
-                TurnBoundary::EndOfTurn,
-                &target.name,
-                &mut enemy_conditions,
-                |ability, _dc| {
-                    let roll = rng.d20(AdMode::Normal) as i32;
-                    let total = roll + target.ability_mod(ability);
-                    (roll, total)
-                },
-                |msg| logs.push(msg),
-            );
-        }
+    let weapons = parse_weapons(&weapons_json)?;
+    let weapon = find_weapon(&weapons, &cfg.weapon).cloned().ok_or_else(|| {
+        EngineError::not_found("weapon", cfg.weapon.as_str(), "not in weapons list")
+    })?;
 
-        if matches!(actor_health.state, LifeState::Dead) || enemy_hp <= 0 {
-            break;
-        }
-        actor_turn = !actor_turn;
+    let mut actor = party_member(&cfg.character_path, &cfg.items_path, &weapon, cfg.actor_hp)?;
+    for kind in cfg.actor_conditions.iter().copied() {
+        actor.conditions.push(ActiveCondition::indefinite(kind));
     }
-
-    let winner = if enemy_hp <= 0 && actor_health.hp > 0 {
-        "actor"
-    } else if enemy_hp <= 0 && actor_health.hp <= 0 {
-        "draw"
-    } else if matches!(actor_health.state, LifeState::Dead) || actor_health.hp <= 0 {
-        "enemy"
-    } else {
-        "draw"
+    actor.initiative_advantage |= cfg.initiative_advantage;
+    actor.health.temp_hp = cfg.actor_temp_hp.max(0);
+    let effects = effect_catalog()?;
+    actor.effects = resolve_effects(&cfg.actor_effects, &effects, "setup")?;
+    let mut enemy = Combatant::from_target(&target);
+    for kind in cfg.enemy_conditions.iter().copied() {
+        enemy.conditions.push(ActiveCondition::indefinite(kind));
+    }
+    enemy.effects = resolve_effects(&cfg.enemy_effects, &effects, "setup")?;
+
+    let start = format!(
+        "[START] {} (AC {}, HP {}) vs {} (AC {}, HP {})",
+        actor.name,
+        actor.armor_class(),
+        actor.health.hp,
+        enemy.name,
+        enemy.ac,
+        enemy.health.hp
+    );
+    let opts = CombatOptions {
+        max_rounds: MAX_ROUNDS,
+        tactics: cfg.tactics,
+        ambush: cfg.ambush,
+        crit_rule: cfg.crit_rule,
+        sunlight: cfg.sunlight,
+        ..CombatOptions::default()
     };
+    let out = run_combat(vec![actor, enemy], &opts, &mut Dice::from_seed(cfg.seed));
 
+    let winner = match out.winner {
+        Some(Side::Party) => "actor",
+        Some(Side::Enemies) => "enemy",
+        None => "draw",
+    };
+    let actor_hp_end = out.combatants[0].health.hp;
+    let enemy_hp_end = out.combatants[1].health.hp;
+    let mut logs = vec![start];
+    logs.extend(out.log);
     logs.push(format!(
         "[END] winner={} actor_hp={} enemy_hp={} rounds={}",
-        winner, actor_health.hp, enemy_hp, rounds
+        winner, actor_hp_end, enemy_hp_end, out.rounds
     ));
 
     Ok(DuelResult {
         winner: winner.to_string(),
-        rounds,
-        actor_hp_end: actor_health.hp,
-        enemy_hp_end: enemy_hp,
+        rounds: out.rounds,
+        actor_hp_end,
+        enemy_hp_end,
+        crit_rule: cfg.crit_rule,
         log: logs,
+        rolls: shift_rolls(out.rolls, 1),
     })
 }
 
 pub fn simulate_duel_many(cfg: DuelConfig, samples: u32) -> Result<DuelStats> {
+    if samples > MAX_SAMPLES {
+        return Err(EngineError::SimulationLimit(format!(
+            "{samples} samples requested, at most {MAX_SAMPLES} allowed"
+        )));
+    }
     let mut actor_wins = 0u32;
     let mut enemy_wins = 0u32;
     let mut draws = 0u32;
     let mut sum_rounds = 0u64;
+    let crit_rule = cfg.crit_rule;
 
     for i in 0..samples {
         let mut run = cfg.clone();
@@ -502,455 +352,283 @@ pub fn simulate_duel_many(cfg: DuelConfig, samples: u32) -> Result<DuelStats> {
         enemy_wins,
         draws,
         avg_rounds: (sum_rounds as f32) / samples.max(1) as f32,
+        crit_rule,
     })
 }
 
 pub fn simulate_encounter(cfg: EncounterConfig) -> Result<EncounterResult> {
     let encounter_json = {
         let builtins = crate::content::builtin_encounters();
-        load_json_from_path_or_builtin(&cfg.encounter_path, &cfg.encounter_id, &builtins)?
+        load_json_from_path_or_builtin(
+            "encounter",
+            &cfg.encounter_path,
+            &cfg.encounter_id,
+            &builtins,
+        )?
     };
-    let encounter: EncounterData =
-        serde_json::from_str(&encounter_json).context("failed to parse encounter JSON")?;
+    let encounter = parse_encounter(&encounter_json)?;
     if encounter.enemies.is_empty() {
-        bail!("encounter must contain at least one enemy");
+        return Err(EngineError::InvalidConfig(
+            "encounter must contain at least one enemy".to_string(),
+        ));
     }
 
     let weapons_json = {
         let builtins = crate::content::builtin_weapons();
-        load_json_from_path_or_builtin(&None, &Some("basic".to_string()), &builtins)?
+        load_json_from_path_or_builtin("weapons", &None, &Some("basic".to_string()), &builtins)?
     };
-    let weapons = parse_weapons_json(&weapons_json)?;
-    let weapon = find_weapon(&weapons, "longsword")
-        .cloned()
-        .ok_or_else(|| anyhow!("failed to find default longsword weapon"))?;
-
-    let actor = sample_fighter();
-    let actor_hp = cfg.actor_hp.unwrap_or(DEFAULT_ACTOR_HP);
-    let actor_ac = DEFAULT_ACTOR_AC;
-    let mut actor_health = Health::new(actor_hp);
-    let mut actor_conditions: Vec<ActiveCondition> = Vec::new();
-    for cond in parse_condition_list(&cfg.actor_conditions) {
-        actor_conditions.push(cond);
+    let weapons = parse_weapons(&weapons_json)?;
+    let weapon = find_weapon(&weapons, "longsword").cloned().ok_or_else(|| {
+        EngineError::not_found("weapon", "longsword", "missing from built-in weapons")
+    })?;
+
+    let mut actor = party_member(&cfg.character_path, &cfg.items_path, &weapon, cfg.actor_hp)?;
+    for kind in cfg.actor_conditions.iter().copied() {
+        actor.conditions.push(ActiveCondition::indefinite(kind));
     }
-
-    let actor_weapon_dice = weapon.versatile.unwrap_or(weapon.dice);
-    let actor_damage_type = weapon
-        .damage_type
-        .or_else(|| preset_damage_type(&weapon.name))
-        .unwrap_or(DamageType::Slashing);
-    let actor_style = if weapon.ranged {
-        AttackStyle::Ranged
-    } else {
-        AttackStyle::Melee
+    actor.initiative_advantage |= cfg.initiative_advantage;
+    actor.health.temp_hp = cfg.actor_temp_hp.max(0);
+    let effects = effect_catalog()?;
+    actor.effects = resolve_effects(&cfg.actor_effects, &effects, "setup")?;
+    let enemy_effects = resolve_effects(&cfg.enemy_effects, &effects, "setup")?;
+    let mut combatants = vec![actor];
+    combatants.extend(encounter.enemies.iter().map(|t| {
+        let mut enemy = Combatant::from_target(t);
+        enemy.effects = enemy_effects.clone();
+        enemy
+    }));
+
+    let opts = CombatOptions {
+        max_rounds: MAX_ROUNDS * 4,
+        focus: encounter.focus,
+        tactics: cfg.tactics,
+        ambush: cfg.ambush.or(encounter.ambush),
+        crit_rule: cfg.crit_rule,
+        sunlight: cfg.sunlight || encounter.sunlight,
+        downed: cfg.downed.unwrap_or(encounter.downed),
     };
-    let actor_ability = if weapon.ranged || weapon.finesse {
-        Ability::Dex
-    } else {
-        Ability::Str
-    };
-    let actor_attack_bonus = actor.attack_bonus(actor_ability, true);
-    let actor_damage_mod = actor.damage_mod(actor_ability);
-    let actor_mode: Vantage = AdMode::Normal.into();
+    let out = run_combat(combatants, &opts, &mut Dice::from_seed(cfg.seed));
 
-    let mut rng = Dice::from_seed(cfg.seed);
-    let mut logs = Vec::new();
-    logs.push(format!(
+    let mut logs = vec![format!(
         "[ENCOUNTER] {} vs {} enemies",
         encounter.name,
         encounter.enemies.len()
-    ));
-
-    struct EnemyState {
-        data: TargetData,
-        hp: i32,
-        resist: HashSet<DamageType>,
-        vuln: HashSet<DamageType>,
-        immune: HashSet<DamageType>,
-        conditions: Vec<ActiveCondition>,
-    }
-
-    let mut enemies: Vec<EnemyState> = Vec::new();
-    for target in encounter.enemies.into_iter() {
-        let mut conditions = Vec::new();
-        for cond in target.conditions.iter().cloned() {
-            logs.push(format!("[COND][{}] starts with {:?}", target.name, cond));
-            conditions.push(make_active_condition(cond));
-        }
-        enemies.push(EnemyState {
-            hp: target.hp,
-            resist: collect_damage_types(&target.resistances),
-            vuln: collect_damage_types(&target.vulnerabilities),
-            immune: collect_damage_types(&target.immunities),
-            conditions,
-            data: target,
-        });
-    }
-
-    let mut rounds = 0u32;
-    while rounds < MAX_ROUNDS * 4 {
-        if matches!(actor_health.state, LifeState::Dead) || actor_health.hp <= 0 {
-            break;
-        }
-        if enemies.iter().all(|e| e.hp <= 0) {
-            break;
-        }
-
-        rounds += 1;
-        logs.push(format!("[ROUND] {}", rounds));
-
-        if let Some(outcome) = process_death_save_start_of_turn(
-            "Actor",
-            &mut actor_health,
-            || rng.d20(AdMode::Normal) as i32,
-            |msg| logs.push(msg),
-        ) {
-            logs.push(format!("[TURN][Actor] death save: {}", outcome));
-        }
-
-        process_turn_boundary(
-            TurnBoundary::StartOfTurn,
-            "Actor",
-            &mut actor_conditions,
-            |ability, _dc| {
-                let roll = rng.d20(AdMode::Normal) as i32;
-                let total = roll + actor.save_mod(ability);
-                (roll, total)
-            },
-            |msg| logs.push(msg),
-        );
-
-        if matches!(actor_health.state, LifeState::Conscious) {
-            if let Some(enemy) = enemies.iter_mut().find(|e| e.hp > 0) {
-                let cond_vantage =
-                    vantage_from_conditions(&actor_conditions, &enemy.conditions, actor_style);
-                let final_mode: AdMode = actor_mode.combine(cond_vantage).into();
-                let effective_ac = enemy.data.ac + enemy.data.cover.ac_bonus();
-                log_defense(&mut logs, &enemy.data.name, enemy.data.ac, enemy.data.cover);
-                let atk = crate::attack(&mut rng, final_mode, actor_attack_bonus, effective_ac);
-                log_attack(&mut logs, "Actor", &atk);
-                if atk.hit {
-                    let is_crit = atk.is_crit;
-                    let raw = crate::damage(&mut rng, actor_weapon_dice, actor_damage_mod, is_crit);
-                    let dmg = crate::adjust_damage_by_type(
-                        raw,
-                        actor_damage_type,
-                        &enemy.resist,
-                        &enemy.vuln,
-                        &enemy.immune,
-                    );
-                    let before = enemy.hp;
-                    enemy.hp = (enemy.hp - dmg).max(0);
-                    log_damage(
-                        &mut logs,
-                        "Actor",
-                        actor_weapon_dice,
-                        actor_damage_mod,
-                        is_crit,
-                        dmg,
-                        Some(actor_damage_type),
-                    );
-                    logs.push(format!(
-                        "[HP][{}] {} → {}",
-                        enemy.data.name, before, enemy.hp
-                    ));
-                    if enemy.hp == 0 {
-                        logs.push(format!("[ENEMY] {} defeated", enemy.data.name));
-                    }
-                } else {
-                    logs.push(format!("[HP][{}] {} HP", enemy.data.name, enemy.hp));
-                }
-            }
-        }
-
-        process_turn_boundary(
-            TurnBoundary::EndOfTurn,
-            "Actor",
-            &mut actor_conditions,
-            |ability, _dc| {
-                let roll = rng.d20(AdMode::Normal) as i32;
-                let total = roll + actor.save_mod(ability);
-                (roll, total)
-            },
-            |msg| logs.push(msg),
-        );
-
-        for enemy in enemies.iter_mut() {
-            if enemy.hp <= 0 {
-                continue;
-            }
-
-            let name = enemy.data.name.clone();
-            process_turn_boundary(
-                TurnBoundary::StartOfTurn,
-                &name,
-                &mut enemy.conditions,
-                |ability, _dc| {
-                    let roll = rng.d20(AdMode::Normal) as i32;
-                    let total = roll + enemy.data.ability_mod(ability);
-                    (roll, total)
-                },
-                |msg| logs.push(msg),
-            );
-
-            if enemy.hp > 0 {
-                if let Some(atk_spec) = enemy.data.attacks.first() {
-                    let style = if atk_spec.ranged {
-                        AttackStyle::Ranged
-                    } else {
-                        AttackStyle::Melee
-                    };
-                    let cond_vantage =
-                        vantage_from_conditions(&enemy.conditions, &actor_conditions, style);
-                    let final_mode: AdMode = Vantage::Normal.combine(cond_vantage).into();
-                    log_defense(&mut logs, "Actor", actor_ac, Cover::None);
-                    let atk = crate::attack(&mut rng, final_mode, atk_spec.to_hit, actor_ac);
-                    log_attack(&mut logs, &atk_spec.name, &atk);
-                    if atk.hit {
-                        let is_crit = atk.is_crit;
-                        let dtype = atk_spec.damage_type.unwrap_or(DamageType::Slashing);
-                        let dmg = crate::damage(&mut rng, atk_spec.dice, 0, is_crit);
-                        log_damage(
-                            &mut logs,
-                            &atk_spec.name,
-                            atk_spec.dice,
-                            0,
-                            is_crit,
-                            dmg,
-                            Some(dtype),
-                        );
-                        let dropped = apply_damage(
-                            "Actor",
-                            &mut actor_health,
-                            &mut actor_conditions,
-                            dmg,
-                            |msg| logs.push(msg),
-                        );
-                        logs.push(format!("[HP][Actor] {} HP", actor_health.hp));
-                        if dropped {
-                            logs.push("[ITEM][Actor] drops to 0 HP".to_string());
-                        }
-                        if let Some(spec) = atk_spec.apply_condition.as_ref() {
-                            maybe_apply_on_hit_condition(
-                                "Actor",
-                                &mut actor_conditions,
-                                spec,
-                                |ability, _dc| {
-                                    let roll = rng.d20(AdMode::Normal) as i32;
-                                    let total = roll + actor.save_mod(ability);
-                                    (roll, total)
-                                },
-                                |msg| logs.push(msg),
-                            );
-                        }
-                    }
-                }
-            }
-
-            process_turn_boundary(
-                TurnBoundary::EndOfTurn,
-                &name,
-                &mut enemy.conditions,
-                |ability, _dc| {
-                    let roll = rng.d20(AdMode::Normal) as i32;
-                    let total = roll + enemy.data.ability_mod(ability);
-                    (roll, total)
-                },
-                |msg| logs.push(msg),
-            );
-        }
-    }
-
-    let remaining_enemies = enemies.iter().filter(|e| e.hp > 0).count() as u32;
-    let survived = actor_health.hp > 0 && !matches!(actor_health.state, LifeState::Dead);
-
+    )];
+    logs.extend(out.log);
+    let remaining_enemies = out.combatants[1..]
+        .iter()
+        .filter(|e| e.health.hp > 0)
+        .count() as u32;
+    let survived = !out.combatants[0].is_dead() && out.combatants[0].health.hp > 0;
     logs.push(format!(
         "[ENCOUNTER_END] survived={} remaining_enemies={} rounds={}",
-        survived, remaining_enemies, rounds
+        survived, remaining_enemies, out.rounds
     ));
 
     Ok(EncounterResult {
         survived,
-        rounds,
+        rounds: out.rounds,
         remaining_enemies,
+        crit_rule: cfg.crit_rule,
         log: logs,
+        rolls: shift_rolls(out.rolls, 1),
     })
 }
 
-fn parse_target_json(text: &str) -> Result<TargetData> {
-    serde_json::from_str(text).context("failed to parse target JSON")
+/// A campaign with every encounter it references loaded and its links checked.
+struct LoadedCampaign {
+    campaign: Campaign,
+    encounters: HashMap<String, EncounterData>,
+    loadout: Loadout,
 }
 
-fn parse_weapons_json(text: &str) -> Result<Vec<Weapon>> {
-    serde_json::from_str(text).context("failed to parse weapons JSON")
-}
-
-fn find_weapon<'a>(weapons: &'a [Weapon], name: &str) -> Option<&'a Weapon> {
-    weapons.iter().find(|w| w.name.eq_ignore_ascii_case(name))
-}
+fn load_campaign(cfg: &CampaignConfig) -> Result<LoadedCampaign> {
+    let campaign_json = {
+        let builtins = crate::content::builtin_campaigns();
+        load_json_from_path_or_builtin("campaign", &cfg.campaign_path, &cfg.campaign_id, &builtins)?
+    };
+    let campaign = parse_campaign(&campaign_json)?;
+    campaign.check()?;
+
+    // Encounters are built-in ids or paths relative to the campaign file.
+    let base = cfg
+        .campaign_path
+        .as_deref()
+        .and_then(|p| Path::new(p).parent())
+        .unwrap_or_else(|| Path::new(""));
+    let builtins = crate::content::builtin_encounters();
+    let mut encounters = HashMap::new();
+    for reference in campaign.encounter_refs() {
+        let text = match builtins.get(reference) {
+            Some(&text) => text.to_string(),
+            None => {
+                let path = base.join(reference);
+                fs::read_to_string(&path).map_err(|e| {
+                    EngineError::not_found("encounter", path.display().to_string(), e.to_string())
+                })?
+            }
+        };
+        let data = parse_encounter(&text)?;
+        if data.enemies.is_empty() {
+            return Err(EngineError::InvalidConfig(format!(
+                "encounter '{}' has no enemies",
+                reference
+            )));
+        }
+        encounters.insert(reference.to_string(), data);
+    }
 
-fn parse_condition_list(src: &[String]) -> Vec<ActiveCondition> {
-    src.iter()
-        .filter_map(|s| match s.trim().to_lowercase().as_str() {
-            "poisoned" => Some(ConditionKind::Poisoned),
-            "prone" => Some(ConditionKind::Prone),
-            "restrained" => Some(ConditionKind::Restrained),
-            _ => None,
-        })
-        .map(make_active_condition)
-        .collect()
-}
+    let weapons_json = {
+        let builtins = crate::content::builtin_weapons();
+        load_json_from_path_or_builtin(
+            "weapons",
+            &cfg.weapons_path,
+            &Some("basic".to_string()),
+            &builtins,
+        )?
+    };
+    let weapons = parse_weapons(&weapons_json)?;
+    let weapon = find_weapon(&weapons, &cfg.weapon).cloned().ok_or_else(|| {
+        EngineError::not_found("weapon", cfg.weapon.as_str(), "not in weapons list")
+    })?;
+
+    let items = item_catalog(&cfg.items_path)?;
+    for node in &campaign.nodes {
+        if let CampaignNode::Encounter { loot, .. } = node {
+            resolve_inventory(loot, &items)?;
+        }
+    }
 
-fn collect_damage_types(src: &[String]) -> HashSet<DamageType> {
-    src.iter().filter_map(|s| parse_damage_type(s)).collect()
+    Ok(LoadedCampaign {
+        campaign,
+        encounters,
+        loadout: Loadout {
+            weapon,
+            ac: cfg.actor_ac.unwrap_or(DEFAULT_ACTOR_AC),
+            items,
+            tactics: cfg.tactics,
+            rest_policy: cfg.rest_policy,
+            max_rounds: MAX_ROUNDS * 4,
+            crit_rule: cfg.crit_rule,
+        },
+    })
 }
 
-fn parse_damage_type(s: &str) -> Option<DamageType> {
-    use DamageType::*;
-    match s.to_lowercase().as_str() {
-        "bludgeoning" => Some(Bludgeoning),
-        "piercing" => Some(Piercing),
-        "slashing" => Some(Slashing),
-        "fire" => Some(Fire),
-        "cold" => Some(Cold),
-        "lightning" => Some(Lightning),
-        "acid" => Some(Acid),
-        "poison" => Some(Poison),
-        "psychic" => Some(Psychic),
-        "radiant" => Some(Radiant),
-        "necrotic" => Some(Necrotic),
-        "thunder" => Some(Thunder),
-        "force" => Some(Force),
-        _ => None,
+fn campaign_start(cfg: &CampaignConfig, campaign: &Campaign) -> Result<CampaignState> {
+    if let Some(state) = &cfg.state {
+        return Ok(state.clone());
     }
+    let character = match &cfg.character_path {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .map_err(|e| EngineError::not_found("character", path.as_str(), e.to_string()))?;
+            parse_content::<Character>("character", &text)?
+        }
+        None => sample_fighter(),
+    };
+    Ok(CampaignState::new(campaign, character))
 }
 
-fn preset_damage_type(name: &str) -> Option<DamageType> {
-    match name.to_lowercase().as_str() {
-        "longsword" | "greatsword" => Some(DamageType::Slashing),
-        "shortsword" | "dagger" | "longbow" => Some(DamageType::Piercing),
-        _ => None,
-    }
+/// Run one node of the campaign: from `cfg.state`, or from the start when it is absent.
+pub fn step_campaign(cfg: CampaignConfig) -> Result<CampaignStep> {
+    let loaded = load_campaign(&cfg)?;
+    let mut state = campaign_start(&cfg, &loaded.campaign)?;
+    let mut dice = Dice::from_seed(cfg.seed.wrapping_add(state.steps as u64));
+    let log = crate::campaign::step(
+        &loaded.campaign,
+        &loaded.encounters,
+        &loaded.loadout,
+        &mut state,
+        &mut dice,
+    )?;
+    Ok(CampaignStep { state, log })
 }
 
-fn sample_fighter() -> Actor {
-    let abilities = AbilityScores {
-        str_: 16,
-        dex: 14,
-        con: 14,
-        int_: 10,
-        wis: 12,
-        cha: 8,
-    };
-    let mut save = HashSet::new();
-    save.insert(Ability::Str);
-    save.insert(Ability::Con);
-    let mut skills = HashSet::new();
-    skills.insert(crate::Skill::Athletics);
-    skills.insert(crate::Skill::Perception);
-    Actor {
-        abilities,
-        proficiency_bonus: 2,
-        save_proficiencies: save,
-        skill_proficiencies: skills,
+/// Run the campaign to victory or defeat.
+pub fn run_campaign(cfg: CampaignConfig) -> Result<CampaignStep> {
+    let loaded = load_campaign(&cfg)?;
+    let mut state = campaign_start(&cfg, &loaded.campaign)?;
+    let mut log = vec![format!("[CAMPAIGN] {}", loaded.campaign.name)];
+    while !state.is_over() {
+        if state.steps >= MAX_CAMPAIGN_STEPS {
+            return Err(EngineError::SimulationLimit(format!(
+                "campaign still running after {MAX_CAMPAIGN_STEPS} steps"
+            )));
+        }
+        let mut dice = Dice::from_seed(cfg.seed.wrapping_add(state.steps as u64));
+        log.extend(crate::campaign::step(
+            &loaded.campaign,
+            &loaded.encounters,
+            &loaded.loadout,
+            &mut state,
+            &mut dice,
+        )?);
     }
+    Ok(CampaignStep { state, log })
 }
 
-fn make_active_condition(kind: ConditionKind) -> ActiveCondition {
-    ActiveCondition {
-        kind,
-        save_ends_each_turn: false,
-        end_phase: None,
-        end_save: None,
-        pending_one_turn: false,
+/// The party side: a character sheet if given (HP from its max), else the sample fighter.
+fn party_member(
+    character_path: &Option<String>,
+    items_path: &Option<String>,
+    weapon: &Weapon,
+    hp: Option<i32>,
+) -> Result<Combatant> {
+    let character = match character_path {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .map_err(|e| EngineError::not_found("character", path.as_str(), e.to_string()))?;
+            parse_content::<Character>("character", &text)?
+        }
+        None => {
+            let mut c = sample_fighter();
+            c.name = "Actor".to_string();
+            c
+        }
+    };
+    let ability = weapon_ability(&character.actor, weapon);
+    let attack = AttackProfile::from_weapon(&character.actor, weapon, ability, true, true);
+    let mut member = Combatant::from_character(&character, DEFAULT_ACTOR_AC, attack);
+    member.inventory = resolve_inventory(&character.inventory, &item_catalog(items_path)?)?;
+    if let Some(hp) = hp {
+        member.health = Health::new(hp);
     }
+    Ok(member)
 }
 
-fn format_d20_sequence(raw: &[u8], kept: i32) -> String {
-    match raw {
-        [] => format!("d20=? (keep={})", kept),
-        [only] => format!("d20={} (keep={})", only, kept),
-        [first, second] => format!("d20={} vs d20={} (keep={})", first, second, kept),
-        _ => {
-            let joined = raw
-                .iter()
-                .map(|r| r.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
-            format!("d20s=[{}] (keep={})", joined, kept)
+/// Items from `items_path` (checked first) followed by the built-in catalog.
+fn item_catalog(items_path: &Option<String>) -> Result<Vec<ItemDef>> {
+    let mut catalog = match items_path {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .map_err(|e| EngineError::not_found("items", path.as_str(), e.to_string()))?;
+            parse_items(&text)?
         }
+        None => Vec::new(),
+    };
+    for text in crate::content::builtin_items().values() {
+        catalog.extend(parse_items(text)?);
     }
+    Ok(catalog)
 }
 
-fn format_modifier(modifier: i32) -> String {
-    if modifier >= 0 {
-        format!("+{}", modifier)
-    } else {
-        format!("-{}", modifier.abs())
+/// The built-in effects (`content/effects/`).
+fn effect_catalog() -> Result<Vec<EffectDef>> {
+    let mut catalog = Vec::new();
+    for text in crate::content::builtin_effects().values() {
+        catalog.extend(parse_effects(text)?);
     }
+    Ok(catalog)
 }
 
-fn log_attack(logs: &mut Vec<String>, name: &str, atk: &crate::AttackResult) {
-    let rolls = format_d20_sequence(&atk.raw_rolls, atk.roll);
-    let outcome = if atk.is_crit {
-        "CRIT!"
-    } else if atk.hit {
-        "HIT"
-    } else if atk.nat1 {
-        "MISS (NAT1)"
-    } else {
-        "MISS"
-    };
-    let mark = if atk.hit { "✔" } else { "✖" };
-    logs.push(format!(
-        "[ATTACK][{}] {} → {} to-hit={} vs AC={} {}",
-        name, rolls, outcome, atk.total, atk.ac, mark
-    ));
-}
-
-fn log_damage(
-    logs: &mut Vec<String>,
-    name: &str,
-    dice: DamageDice,
-    modifier: i32,
-    crit: bool,
-    total: i32,
-    dtype: Option<DamageType>,
-) {
-    let dice_expr = if crit {
-        format!("2×({}d{})", dice.count, dice.sides)
-    } else {
-        format!("{}d{}", dice.count, dice.sides)
-    };
-    let prefix = if crit { "crit: " } else { "" };
-    match dtype {
-        Some(dt) => logs.push(format!(
-            "[DMG][{}] {}rolled {} {} = {} [{:?}]",
-            name,
-            prefix,
-            dice_expr,
-            format_modifier(modifier),
-            total,
-            dt
-        )),
-        None => logs.push(format!(
-            "[DMG][{}] {}rolled {} {} = {}",
-            name,
-            prefix,
-            dice_expr,
-            format_modifier(modifier),
-            total
-        )),
+/// Point combat rolls at the result log, which has `lines` extra lines in front.
+fn shift_rolls(mut rolls: Vec<RollExplanation>, lines: usize) -> Vec<RollExplanation> {
+    for r in &mut rolls {
+        r.log_index += lines;
     }
+    rolls
 }
 
-fn log_defense(logs: &mut Vec<String>, name: &str, base_ac: i32, cover: Cover) {
-    let bonus = cover.ac_bonus();
-    logs.push(format!(
-        "[DEF][{}] AC {} + cover({:+}) = {}",
-        name,
-        base_ac,
-        bonus,
-        base_ac + bonus
-    ));
+fn find_weapon<'a>(weapons: &'a [Weapon], name: &str) -> Option<&'a Weapon> {
+    weapons.iter().find(|w| w.name.eq_ignore_ascii_case(name))
 }
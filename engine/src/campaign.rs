@@ -0,0 +1,499 @@
+//! Campaigns: a graph of encounter, rest and check nodes walked one step at a time.
+//!
+//! The character's HP, conditions, feature uses, hit dice and inventory live in a
+//! [`CampaignState`] that is carried from node to node and serializes to JSON, so a
+//! front-end can hand it back to take the next step.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::character::Character;
+use crate::checks::passive_score;
+use crate::combat::{run_combat, weapon_ability, AttackProfile, CombatOptions, Combatant, Side};
+use crate::conditions::{check_vantage_from_conditions, ActiveCondition, ConditionKind};
+use crate::content::{EncounterData, Focus};
+use crate::error::{EngineError, Result};
+use crate::features::{RestKind, Tactics};
+use crate::items::{find_item, resolve_inventory, InventoryEntry, ItemDef};
+use crate::life::{
+    process_death_save_start_of_turn, recover_from_stable, Health, LifeState, ShortRestPolicy,
+};
+use crate::{check, Ability, AdMode, CheckInput, CritRule, DamageType, Dice, Skill, Weapon};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Campaign {
+    #[serde(default)]
+    pub name: String,
+    /// Id of the first node.
+    pub start: String,
+    pub nodes: Vec<CampaignNode>,
+}
+
+/// One scene. `next`/`success`/`failure` name the node that follows; leaving it out
+/// ends the campaign in victory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CampaignNode {
+    /// Fight an encounter (a built-in id, or a path relative to the campaign file).
+    /// Losing ends the campaign; winning grants `loot`.
+    Encounter {
+        id: String,
+        #[serde(default)]
+        text: String,
+        encounter: String,
+        /// Overrides the encounter file's focus.
+        #[serde(default)]
+        focus: Option<Focus>,
+        /// Overrides the encounter file's ambush side.
+        #[serde(default)]
+        ambush: Option<Side>,
+        /// Knock the enemies out instead of killing them, to capture them.
+        #[serde(default)]
+        nonlethal: bool,
+        #[serde(default)]
+        loot: Vec<InventoryEntry>,
+        #[serde(default)]
+        next: Option<String>,
+    },
+    Rest {
+        id: String,
+        #[serde(default)]
+        text: String,
+        rest: RestKind,
+        #[serde(default)]
+        next: Option<String>,
+    },
+    /// A skill or ability check against a DC that branches on the result.
+    Check {
+        id: String,
+        #[serde(default)]
+        text: String,
+        #[serde(default)]
+        skill: Option<Skill>,
+        #[serde(default)]
+        ability: Option<Ability>,
+        dc: i32,
+        /// Compare the passive score (10 + modifier) instead of rolling, e.g. spotting a trap.
+        #[serde(default)]
+        passive: bool,
+        #[serde(default)]
+        success: Option<String>,
+        #[serde(default)]
+        failure: Option<String>,
+    },
+}
+
+impl CampaignNode {
+    pub fn id(&self) -> &str {
+        match self {
+            CampaignNode::Encounter { id, .. }
+            | CampaignNode::Rest { id, .. }
+            | CampaignNode::Check { id, .. } => id,
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            CampaignNode::Encounter { text, .. }
+            | CampaignNode::Rest { text, .. }
+            | CampaignNode::Check { text, .. } => text,
+        }
+    }
+
+    /// Every node id this node can lead to.
+    pub fn exits(&self) -> Vec<&str> {
+        match self {
+            CampaignNode::Encounter { next, .. } | CampaignNode::Rest { next, .. } => {
+                next.iter().map(String::as_str).collect()
+            }
+            CampaignNode::Check {
+                success, failure, ..
+            } => success.iter().chain(failure).map(String::as_str).collect(),
+        }
+    }
+}
+
+impl Campaign {
+    pub fn node(&self, id: &str) -> Option<&CampaignNode> {
+        self.nodes.iter().find(|n| n.id() == id)
+    }
+
+    /// Encounter references in node order, without duplicates.
+    pub fn encounter_refs(&self) -> Vec<&str> {
+        let mut refs: Vec<&str> = Vec::new();
+        for node in &self.nodes {
+            if let CampaignNode::Encounter { encounter, .. } = node {
+                if !refs.contains(&encounter.as_str()) {
+                    refs.push(encounter);
+                }
+            }
+        }
+        refs
+    }
+
+    /// Structural checks: unique ids, a real start node, no dangling links, and
+    /// checks that name exactly one of `skill`/`ability`.
+    pub fn check(&self) -> Result<()> {
+        let invalid = |msg: String| Err(EngineError::InvalidConfig(msg));
+        let mut seen = HashSet::new();
+        for node in &self.nodes {
+            if !seen.insert(node.id()) {
+                return invalid(format!("duplicate campaign node id '{}'", node.id()));
+            }
+        }
+        if self.node(&self.start).is_none() {
+            return invalid(format!("start node '{}' does not exist", self.start));
+        }
+        for node in &self.nodes {
+            for exit in node.exits() {
+                if !seen.contains(exit) {
+                    return invalid(format!(
+                        "node '{}' leads to unknown node '{}'",
+                        node.id(),
+                        exit
+                    ));
+                }
+            }
+            if let CampaignNode::Check { skill, ability, .. } = node {
+                if skill.is_some() == ability.is_some() {
+                    return invalid(format!(
+                        "check node '{}' needs exactly one of skill or ability",
+                        node.id()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignStatus {
+    InProgress,
+    Victory,
+    Defeat,
+}
+
+/// Everything about the run that changes between nodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CampaignState {
+    /// Node to run next; None once the campaign is over.
+    pub node: Option<String>,
+    pub status: CampaignStatus,
+    /// Steps taken so far.
+    pub steps: u32,
+    /// Resources, hit dice, exhaustion and inventory are kept up to date on the sheet.
+    pub character: Character,
+    pub health: Health,
+    #[serde(default)]
+    pub conditions: Vec<ActiveCondition>,
+    /// Potion effects that last until the next rest.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub item_resistances: HashSet<DamageType>,
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub save_advantage: HashSet<ConditionKind>,
+    /// Hours of campaign time passed: rests take time, and so does recovering from 0 HP.
+    #[serde(default)]
+    pub hours: u32,
+}
+
+impl CampaignState {
+    /// A fresh run at the start node with the character at full HP.
+    pub fn new(campaign: &Campaign, mut character: Character) -> Self {
+        // Logs name the character, so give an unnamed sheet the combat default.
+        if character.name.is_empty() {
+            character.name = "Actor".to_string();
+        }
+        Self {
+            node: Some(campaign.start.clone()),
+            status: CampaignStatus::InProgress,
+            steps: 0,
+            health: Health::new(character.max_hp),
+            character,
+            conditions: Vec::new(),
+            item_resistances: HashSet::new(),
+            save_advantage: HashSet::new(),
+            hours: 0,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status != CampaignStatus::InProgress
+    }
+
+    fn combatant(&self, loadout: &Loadout) -> Result<Combatant> {
+        let c = &self.character;
+        let ability = weapon_ability(&c.actor, &loadout.weapon);
+        let attack = AttackProfile::from_weapon(&c.actor, &loadout.weapon, ability, true, true);
+        let mut member = Combatant::from_character(c, loadout.ac, attack);
+        member.health = self.health;
+        member.conditions = self.conditions.clone();
+        member.item_resistances = self.item_resistances.clone();
+        member.save_advantage = self.save_advantage.clone();
+        member.inventory = resolve_inventory(&c.inventory, &loadout.items)?;
+        Ok(member)
+    }
+
+    /// Copy what the scene changed back onto the state.
+    fn absorb(&mut self, member: &Combatant) {
+        self.health = member.health;
+        self.conditions = member.conditions.clone();
+        self.item_resistances = member.item_resistances.clone();
+        self.save_advantage = member.save_advantage.clone();
+        let c = &mut self.character;
+        if let Some(kit) = &member.kit {
+            c.resources = kit.resources.clone();
+        }
+        if let Some(hd) = member.hit_dice {
+            c.hit_dice_spent = hd.max - hd.remaining;
+        }
+        c.exhaustion = member.exhaustion;
+        c.inventory = member
+            .inventory
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_entry())
+            .collect();
+    }
+
+    fn pass_time(&mut self, hours: u32, why: &str, log: &mut Vec<String>) {
+        self.hours += hours;
+        log.push(format!("[TIME] {}: +{}h (hour {})", why, hours, self.hours));
+    }
+
+    /// Out of combat, a dying character keeps rolling death saves until it dies,
+    /// stabilizes or wakes.
+    fn settle_death_saves(&mut self, dice: &mut Dice, log: &mut Vec<String>) {
+        while self.health.hp == 0 && self.health.state == (LifeState::Unconscious { stable: false })
+        {
+            process_death_save_start_of_turn(
+                &self.character.name,
+                &mut self.health,
+                || dice.d20(AdMode::Normal) as i32,
+                |msg| log.push(msg),
+            );
+        }
+    }
+
+    /// A character stable at 0 HP regains 1 HP after 1d4 hours.
+    fn recover(&mut self, dice: &mut Dice, log: &mut Vec<String>) {
+        if self.health.state != (LifeState::Unconscious { stable: true }) {
+            return;
+        }
+        let hours = u32::from(dice.die(4));
+        self.pass_time(hours, "unconscious", log);
+        if recover_from_stable(&self.character.name, &mut self.health, hours, |msg| {
+            log.push(msg)
+        }) {
+            self.conditions.retain(|c| c.kind != ConditionKind::Prone);
+        }
+    }
+
+    fn advance(&mut self, next: Option<&String>, log: &mut Vec<String>) {
+        self.node = next.cloned();
+        if self.node.is_none() {
+            self.status = CampaignStatus::Victory;
+            log.push("[CAMPAIGN] victory".to_string());
+        }
+    }
+
+    fn defeat(&mut self, log: &mut Vec<String>) {
+        self.node = None;
+        self.status = CampaignStatus::Defeat;
+        log.push("[CAMPAIGN] defeat".to_string());
+    }
+}
+
+/// How the character fights and rests; fixed for the whole run.
+#[derive(Debug, Clone)]
+pub struct Loadout {
+    pub weapon: Weapon,
+    pub ac: i32,
+    /// Catalog the character's inventory ids are resolved against.
+    pub items: Vec<ItemDef>,
+    pub tactics: Tactics,
+    pub rest_policy: ShortRestPolicy,
+    pub max_rounds: u32,
+    pub crit_rule: CritRule,
+}
+
+/// Run the current node and move to the next one. `encounters` maps each
+/// [`Campaign::encounter_refs`] entry to its loaded data.
+pub fn step(
+    campaign: &Campaign,
+    encounters: &HashMap<String, EncounterData>,
+    loadout: &Loadout,
+    state: &mut CampaignState,
+    dice: &mut Dice,
+) -> Result<Vec<String>> {
+    let Some(id) = state.node.clone().filter(|_| !state.is_over()) else {
+        return Err(EngineError::InvalidConfig(
+            "campaign is already over".to_string(),
+        ));
+    };
+    let node = campaign
+        .node(&id)
+        .ok_or_else(|| EngineError::not_found("campaign node", id.as_str(), "not in campaign"))?;
+
+    let mut log = vec![format!("[NODE] {}", id)];
+    if !node.text().is_empty() {
+        log.push(format!("[STORY] {}", node.text()));
+    }
+    state.steps += 1;
+    state.recover(dice, &mut log);
+
+    match node {
+        CampaignNode::Encounter {
+            encounter,
+            focus,
+            ambush,
+            nonlethal,
+            loot,
+            next,
+            ..
+        } => {
+            let data = encounters.get(encounter).ok_or_else(|| {
+                EngineError::not_found("encounter", encounter.as_str(), "not loaded")
+            })?;
+            log.push(format!(
+                "[ENCOUNTER] {} vs {} enemies",
+                data.name,
+                data.enemies.len()
+            ));
+            let mut combatants = vec![state.combatant(loadout)?];
+            combatants.extend(data.enemies.iter().map(Combatant::from_target));
+            let opts = CombatOptions {
+                max_rounds: loadout.max_rounds,
+                focus: focus.unwrap_or(data.focus),
+                tactics: Tactics {
+                    nonlethal: loadout.tactics.nonlethal || *nonlethal,
+                    ..loadout.tactics
+                },
+                ambush: ambush.or(data.ambush),
+                crit_rule: loadout.crit_rule,
+                sunlight: data.sunlight,
+                downed: data.downed,
+            };
+            let out = run_combat(combatants, &opts, dice);
+            log.extend(out.log);
+            state.absorb(&out.combatants[0]);
+            let won = out.winner == Some(Side::Party);
+            log.push(format!(
+                "[ENCOUNTER_END] won={} hp={} rounds={}",
+                won, state.health.hp, out.rounds
+            ));
+            let left_down = !won && state.health.hp == 0 && state.health.state != LifeState::Dead;
+            state.settle_death_saves(dice, &mut log);
+            if won {
+                for enemy in out.combatants.iter().filter(|c| c.is_down()) {
+                    log.push(format!(
+                        "[CAPTURED][{}] {} (unconscious)",
+                        out.combatants[0].name, enemy.name
+                    ));
+                }
+                for entry in loot {
+                    let name = find_item(&loadout.items, &entry.item)
+                        .map_or(entry.item.as_str(), |d| d.name.as_str());
+                    log.push(format!(
+                        "[LOOT][{}] {} ×{}",
+                        out.combatants[0].name, name, entry.quantity
+                    ));
+                    add_to_inventory(&mut state.character.inventory, entry);
+                }
+                state.advance(next.as_ref(), &mut log);
+            } else if left_down && state.health.state != LifeState::Dead {
+                // The enemies leave the character for dead; the fight waits at this node.
+                log.push(format!(
+                    "[CAMPAIGN] {} is left for dead",
+                    state.character.name
+                ));
+            } else {
+                state.defeat(&mut log);
+            }
+        }
+        CampaignNode::Rest { rest, next, .. } => {
+            let mut member = state.combatant(loadout)?;
+            match rest {
+                RestKind::Short => {
+                    member.short_rest(&loadout.rest_policy, dice, |msg| log.push(msg))
+                }
+                RestKind::Long => member.long_rest(|msg| log.push(msg)),
+            }
+            state.absorb(&member);
+            let hours = match rest {
+                RestKind::Short => 1,
+                RestKind::Long => 8,
+            };
+            state.pass_time(hours, "rest", &mut log);
+            state.advance(next.as_ref(), &mut log);
+        }
+        CampaignNode::Check {
+            skill,
+            ability,
+            dc,
+            passive,
+            success,
+            failure,
+            ..
+        } => {
+            let actor = &state.character.actor;
+            let (label, modifier) = match (skill, ability) {
+                (Some(s), _) => (format!("{:?}", s), actor.skill_mod(*s)),
+                (None, Some(a)) => (format!("{:?}", a), actor.ability_mod(*a)),
+                (None, None) => {
+                    return Err(EngineError::InvalidConfig(format!(
+                        "check node '{}' needs a skill or ability",
+                        id
+                    )))
+                }
+            };
+            let vantage = check_vantage_from_conditions(&state.conditions);
+            let mode: AdMode = vantage.net().into();
+            let (mut detail, passed) = if *passive {
+                let score = passive_score(modifier, mode);
+                (format!("passive={}", score), score >= *dc)
+            } else {
+                let result = check(
+                    dice,
+                    CheckInput {
+                        dc: *dc,
+                        modifier,
+                        mode,
+                    },
+                );
+                (
+                    format!("roll={} total={}", result.roll, result.total),
+                    result.passed,
+                )
+            };
+            if !vantage.sources.is_empty() {
+                detail = format!("{} {}", detail, vantage);
+            }
+            log.push(format!(
+                "[CHECK][{}] {} DC {}: {} → {}",
+                state.character.name,
+                label,
+                dc,
+                detail,
+                if passed { "SUCCESS" } else { "FAIL" }
+            ));
+            let next = if passed { success } else { failure };
+            state.advance(next.as_ref(), &mut log);
+        }
+    }
+    Ok(log)
+}
+
+fn add_to_inventory(inventory: &mut Vec<InventoryEntry>, loot: &InventoryEntry) {
+    match inventory
+        .iter_mut()
+        .find(|e| e.item.eq_ignore_ascii_case(&loot.item))
+    {
+        Some(e) => e.quantity += loot.quantity,
+        None => inventory.push(loot.clone()),
+    }
+}
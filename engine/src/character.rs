@@ -0,0 +1,788 @@
+//! Character creation and leveling (SRD 5.1 classes, species and the Acolyte background).
+//!
+//! A [`Character`] serializes with its [`Actor`] fields flattened, so character JSON
+//! still loads anywhere an actor is expected.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EngineError, Result};
+use crate::features::{expertise_count, fighting_styles, FightingStyle, Resources, Subclass};
+use crate::items::InventoryEntry;
+use crate::life::HitDice;
+use crate::{parse_name, Ability, AbilityScores, Actor, Dice, Skill};
+
+pub const MAX_LEVEL: u8 = 20;
+pub const STANDARD_ARRAY: [i32; 6] = [15, 14, 13, 12, 10, 8];
+pub const POINT_BUY_BUDGET: i32 = 27;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Class {
+    Barbarian,
+    Bard,
+    Cleric,
+    Druid,
+    Fighter,
+    Monk,
+    Paladin,
+    Ranger,
+    Rogue,
+    Sorcerer,
+    Warlock,
+    Wizard,
+}
+
+impl FromStr for Class {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        parse_name("class", s)
+    }
+}
+
+impl Class {
+    pub fn hit_die(self) -> u8 {
+        match self {
+            Class::Barbarian => 12,
+            Class::Fighter | Class::Paladin | Class::Ranger => 10,
+            Class::Sorcerer | Class::Wizard => 6,
+            _ => 8,
+        }
+    }
+
+    pub fn saving_throws(self) -> [Ability; 2] {
+        use Ability::*;
+        match self {
+            Class::Barbarian | Class::Fighter => [Str, Con],
+            Class::Bard => [Dex, Cha],
+            Class::Cleric | Class::Paladin | Class::Warlock => [Wis, Cha],
+            Class::Druid | Class::Wizard => [Int, Wis],
+            Class::Monk | Class::Ranger => [Str, Dex],
+            Class::Rogue => [Dex, Int],
+            Class::Sorcerer => [Con, Cha],
+        }
+    }
+
+    /// How many class skills are chosen at 1st level, and from which list.
+    pub fn skill_choices(self) -> (usize, Vec<Skill>) {
+        use Skill::*;
+        match self {
+            Class::Barbarian => (
+                2,
+                vec![
+                    AnimalHandling,
+                    Athletics,
+                    Intimidation,
+                    Nature,
+                    Perception,
+                    Survival,
+                ],
+            ),
+            Class::Bard => (3, Skill::ALL.to_vec()),
+            Class::Cleric => (2, vec![History, Insight, Medicine, Persuasion, Religion]),
+            Class::Druid => (
+                2,
+                vec![
+                    Arcana,
+                    AnimalHandling,
+                    Insight,
+                    Medicine,
+                    Nature,
+                    Perception,
+                    Religion,
+                    Survival,
+                ],
+            ),
+            Class::Fighter => (
+                2,
+                vec![
+                    Acrobatics,
+                    AnimalHandling,
+                    Athletics,
+                    History,
+                    Insight,
+                    Intimidation,
+                    Perception,
+                    Survival,
+                ],
+            ),
+            Class::Monk => (
+                2,
+                vec![Acrobatics, Athletics, History, Insight, Religion, Stealth],
+            ),
+            Class::Paladin => (
+                2,
+                vec![
+                    Athletics,
+                    Insight,
+                    Intimidation,
+                    Medicine,
+                    Persuasion,
+                    Religion,
+                ],
+            ),
+            Class::Ranger => (
+                3,
+                vec![
+                    AnimalHandling,
+                    Athletics,
+                    Insight,
+                    Investigation,
+                    Nature,
+                    Perception,
+                    Stealth,
+                    Survival,
+                ],
+            ),
+            Class::Rogue => (
+                4,
+                vec![
+                    Acrobatics,
+                    Athletics,
+                    Deception,
+                    Insight,
+                    Intimidation,
+                    Investigation,
+                    Perception,
+                    Performance,
+                    Persuasion,
+                    SleightOfHand,
+                    Stealth,
+                ],
+            ),
+            Class::Sorcerer => (
+                2,
+                vec![
+                    Arcana,
+                    Deception,
+                    Insight,
+                    Intimidation,
+                    Persuasion,
+                    Religion,
+                ],
+            ),
+            Class::Warlock => (
+                2,
+                vec![
+                    Arcana,
+                    Deception,
+                    History,
+                    Intimidation,
+                    Investigation,
+                    Nature,
+                    Religion,
+                ],
+            ),
+            Class::Wizard => (
+                2,
+                vec![Arcana, History, Insight, Investigation, Medicine, Religion],
+            ),
+        }
+    }
+
+    /// Default order in which standard-array or rolled scores are assigned.
+    pub fn ability_priority(self) -> [Ability; 6] {
+        use Ability::*;
+        match self {
+            Class::Barbarian => [Str, Con, Dex, Wis, Cha, Int],
+            Class::Bard => [Cha, Dex, Con, Wis, Int, Str],
+            Class::Cleric => [Wis, Con, Str, Cha, Dex, Int],
+            Class::Druid => [Wis, Con, Dex, Int, Cha, Str],
+            Class::Fighter => [Str, Con, Dex, Wis, Int, Cha],
+            Class::Monk => [Dex, Wis, Con, Str, Int, Cha],
+            Class::Paladin => [Str, Cha, Con, Wis, Dex, Int],
+            Class::Ranger => [Dex, Wis, Con, Str, Int, Cha],
+            Class::Rogue => [Dex, Con, Int, Wis, Cha, Str],
+            Class::Sorcerer => [Cha, Con, Dex, Wis, Int, Str],
+            Class::Warlock => [Cha, Con, Dex, Wis, Int, Str],
+            Class::Wizard => [Int, Con, Dex, Wis, Cha, Str],
+        }
+    }
+}
+
+/// SRD 5.1 species, base traits only (no subraces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Species {
+    Dwarf,
+    Elf,
+    Halfling,
+    Human,
+    Dragonborn,
+    Gnome,
+    HalfElf,
+    HalfOrc,
+    Tiefling,
+}
+
+impl FromStr for Species {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        parse_name("species", s)
+    }
+}
+
+impl Species {
+    /// Fixed ability score increases. Half-elves also pick two +1s (see [`CharacterSpec::species_bonus`]).
+    pub fn ability_bonuses(self) -> Vec<(Ability, i32)> {
+        use Ability::*;
+        match self {
+            Species::Dwarf => vec![(Con, 2)],
+            Species::Elf | Species::Halfling => vec![(Dex, 2)],
+            Species::Human => Ability::ALL.iter().map(|&a| (a, 1)).collect(),
+            Species::Dragonborn => vec![(Str, 2), (Cha, 1)],
+            Species::Gnome => vec![(Int, 2)],
+            Species::HalfElf => vec![(Cha, 2)],
+            Species::HalfOrc => vec![(Str, 2), (Con, 1)],
+            Species::Tiefling => vec![(Cha, 2), (Int, 1)],
+        }
+    }
+
+    /// Skill proficiencies granted outright (Keen Senses, Menacing).
+    pub fn skills(self) -> Vec<Skill> {
+        match self {
+            Species::Elf => vec![Skill::Perception],
+            Species::HalfOrc => vec![Skill::Intimidation],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Number of free +1 ability increases and free skills the species lets you choose.
+    fn free_choices(self) -> (usize, usize) {
+        match self {
+            Species::HalfElf => (2, 2),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// Acolyte is the only background in SRD 5.1; `custom` takes any two skills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Background {
+    #[default]
+    Acolyte,
+    Custom,
+}
+
+impl FromStr for Background {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        parse_name("background", s)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AbilityMethod {
+    /// 15, 14, 13, 12, 10, 8 assigned in priority order.
+    #[default]
+    StandardArray,
+    /// Base scores (before species bonuses), each 8..=15, costing at most 27 points.
+    PointBuy { scores: AbilityScores },
+    /// Six rolls of 4d6-drop-lowest, highest first into the priority order.
+    Roll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HpMethod {
+    #[default]
+    Average,
+    Roll,
+}
+
+/// Everything needed to build a character; omitted choices get sensible defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CharacterSpec {
+    #[serde(default)]
+    pub name: String,
+    pub class: Class,
+    pub species: Species,
+    #[serde(default)]
+    pub background: Background,
+    #[serde(default)]
+    pub abilities: AbilityMethod,
+    /// Abilities to fill first; the rest follow the class default order.
+    #[serde(default)]
+    pub priority: Vec<Ability>,
+    /// Class skill picks; if empty, the first eligible ones from the class list.
+    #[serde(default)]
+    pub skills: Vec<Skill>,
+    /// Required for a `custom` background (exactly two).
+    #[serde(default)]
+    pub background_skills: Vec<Skill>,
+    /// Half-elf Skill Versatility picks; auto-filled if empty.
+    #[serde(default)]
+    pub species_skills: Vec<Skill>,
+    /// Half-elf +1 picks (not Cha); defaults to the two highest other scores.
+    #[serde(default)]
+    pub species_bonus: Vec<Ability>,
+    /// Rogue/Bard Expertise picks among proficient skills; auto-filled (Stealth and
+    /// Perception first) if fewer than the class allows.
+    #[serde(default)]
+    pub expertise: Vec<Skill>,
+    /// Fighter/Paladin/Ranger style; defaults to Defense when the class gains one.
+    #[serde(default)]
+    pub fighting_style: Option<FightingStyle>,
+    /// Picked at creation; takes effect at the subclass level (Champion: 3rd).
+    #[serde(default)]
+    pub subclass: Option<Subclass>,
+    #[serde(default = "default_level")]
+    pub level: u8,
+    #[serde(default)]
+    pub hp_method: HpMethod,
+    /// Starting gear; ids are checked when the character is loaded for combat.
+    #[serde(default)]
+    pub inventory: Vec<InventoryEntry>,
+}
+
+fn default_level() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Character {
+    #[serde(default)]
+    pub name: String,
+    pub class: Class,
+    pub species: Species,
+    pub background: Background,
+    pub level: u8,
+    pub max_hp: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fighting_style: Option<FightingStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subclass: Option<Subclass>,
+    /// Remaining uses of limited features and spell slots.
+    #[serde(default, skip_serializing_if = "is_empty_resources")]
+    pub resources: Resources,
+    /// Hit dice spent on short rests and not yet recovered by a long rest.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub hit_dice_spent: u8,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub exhaustion: u8,
+    /// Carried items by id; see `content/items/`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inventory: Vec<InventoryEntry>,
+    #[serde(flatten)]
+    pub actor: Actor,
+}
+
+fn is_zero(n: &u8) -> bool {
+    *n == 0
+}
+
+fn is_empty_resources(r: &Resources) -> bool {
+    r.0.is_empty()
+}
+
+/// Proficiency bonus by character level: +2 at 1–4, rising by one every four levels.
+pub fn proficiency_bonus(level: u8) -> i32 {
+    2 + (level.clamp(1, MAX_LEVEL) as i32 - 1) / 4
+}
+
+/// Point-buy cost of a single base score, or None outside 8..=15.
+pub fn point_buy_cost(score: i32) -> Option<i32> {
+    match score {
+        8..=13 => Some(score - 8),
+        14 => Some(7),
+        15 => Some(9),
+        _ => None,
+    }
+}
+
+/// Roll 4d6 and drop the lowest die.
+pub fn roll_4d6_drop_lowest(dice: &mut Dice) -> i32 {
+    let mut rolls = [0u8; 4];
+    for r in rolls.iter_mut() {
+        *r = dice.die(6);
+    }
+    rolls.sort_unstable();
+    rolls[1..].iter().map(|&r| r as i32).sum()
+}
+
+fn invalid(msg: impl Into<String>) -> EngineError {
+    EngineError::InvalidConfig(msg.into())
+}
+
+fn full_priority(class: Class, given: &[Ability]) -> Result<Vec<Ability>> {
+    let mut order: Vec<Ability> = Vec::new();
+    for &a in given {
+        if order.contains(&a) {
+            return Err(invalid(format!("ability {:?} listed twice in priority", a)));
+        }
+        order.push(a);
+    }
+    for a in class.ability_priority() {
+        if !order.contains(&a) {
+            order.push(a);
+        }
+    }
+    Ok(order)
+}
+
+fn assign_in_order(order: &[Ability], values: &[i32]) -> AbilityScores {
+    let mut scores = AbilityScores {
+        str_: 0,
+        dex: 0,
+        con: 0,
+        int_: 0,
+        wis: 0,
+        cha: 0,
+    };
+    for (&a, &v) in order.iter().zip(values) {
+        scores.set(a, v);
+    }
+    scores
+}
+
+fn base_scores(spec: &CharacterSpec, dice: &mut Dice) -> Result<AbilityScores> {
+    let order = full_priority(spec.class, &spec.priority)?;
+    match &spec.abilities {
+        AbilityMethod::StandardArray => Ok(assign_in_order(&order, &STANDARD_ARRAY)),
+        AbilityMethod::Roll => {
+            let mut rolled: Vec<i32> = (0..6).map(|_| roll_4d6_drop_lowest(dice)).collect();
+            rolled.sort_unstable_by(|a, b| b.cmp(a));
+            Ok(assign_in_order(&order, &rolled))
+        }
+        AbilityMethod::PointBuy { scores } => {
+            let mut spent = 0;
+            for a in Ability::ALL {
+                let v = scores.get(a);
+                spent += point_buy_cost(v).ok_or_else(|| {
+                    invalid(format!(
+                        "point buy score for {:?} must be 8..=15, got {}",
+                        a, v
+                    ))
+                })?;
+            }
+            if spent > POINT_BUY_BUDGET {
+                return Err(invalid(format!(
+                    "point buy spends {} points, budget is {}",
+                    spent, POINT_BUY_BUDGET
+                )));
+            }
+            Ok(scores.clone())
+        }
+    }
+}
+
+fn apply_species_bonuses(spec: &CharacterSpec, scores: &mut AbilityScores) -> Result<()> {
+    for (a, bonus) in spec.species.ability_bonuses() {
+        scores.set(a, scores.get(a) + bonus);
+    }
+    let (free, _) = spec.species.free_choices();
+    if free == 0 {
+        if !spec.species_bonus.is_empty() {
+            return Err(invalid(format!(
+                "{:?} has no ability score choices",
+                spec.species
+            )));
+        }
+        return Ok(());
+    }
+    let picks: Vec<Ability> = if spec.species_bonus.is_empty() {
+        let mut others: Vec<Ability> = Ability::ALL
+            .into_iter()
+            .filter(|&a| a != Ability::Cha)
+            .collect();
+        // Stable sort keeps Str..Wis order among ties.
+        others.sort_by_key(|&a| -scores.get(a));
+        others.into_iter().take(free).collect()
+    } else {
+        spec.species_bonus.clone()
+    };
+    let unique: HashSet<_> = picks.iter().collect();
+    if picks.len() != free || unique.len() != free || picks.contains(&Ability::Cha) {
+        return Err(invalid(format!(
+            "species_bonus needs {} different abilities other than cha",
+            free
+        )));
+    }
+    for a in picks {
+        scores.set(a, scores.get(a) + 1);
+    }
+    Ok(())
+}
+
+/// Take `count` skills from `wanted` (validated against `allowed`), or auto-pick from
+/// `allowed` when `wanted` is empty. Picks may not repeat anything in `known`.
+fn choose_skills(
+    what: &str,
+    count: usize,
+    wanted: &[Skill],
+    allowed: &[Skill],
+    known: &mut HashSet<Skill>,
+) -> Result<()> {
+    if wanted.is_empty() {
+        let picks: Vec<Skill> = allowed
+            .iter()
+            .copied()
+            .filter(|s| !known.contains(s))
+            .take(count)
+            .collect();
+        known.extend(picks);
+        return Ok(());
+    }
+    if wanted.len() != count {
+        return Err(invalid(format!(
+            "{} needs exactly {} skill(s), got {}",
+            what,
+            count,
+            wanted.len()
+        )));
+    }
+    for &s in wanted {
+        if !allowed.contains(&s) {
+            return Err(invalid(format!("{:?} is not a {} option", s, what)));
+        }
+        if !known.insert(s) {
+            return Err(invalid(format!(
+                "{:?} picked for {} is already proficient",
+                s, what
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Build a character at `spec.level`, rolling on `dice` where the spec asks for it.
+pub fn create_character(spec: &CharacterSpec, dice: &mut Dice) -> Result<Character> {
+    if spec.level == 0 || spec.level > MAX_LEVEL {
+        return Err(invalid(format!(
+            "level must be 1..={}, got {}",
+            MAX_LEVEL, spec.level
+        )));
+    }
+
+    let mut abilities = base_scores(spec, dice)?;
+    apply_species_bonuses(spec, &mut abilities)?;
+    for a in Ability::ALL {
+        abilities.set(a, abilities.get(a).min(20));
+    }
+
+    let mut skills: HashSet<Skill> = spec.species.skills().into_iter().collect();
+    match spec.background {
+        Background::Acolyte => {
+            if !spec.background_skills.is_empty() {
+                return Err(invalid(
+                    "background_skills only apply to a custom background",
+                ));
+            }
+            skills.extend([Skill::Insight, Skill::Religion]);
+        }
+        Background::Custom => {
+            if spec.background_skills.len() != 2 {
+                return Err(invalid(
+                    "a custom background needs exactly 2 background_skills",
+                ));
+            }
+            choose_skills(
+                "background",
+                2,
+                &spec.background_skills,
+                &Skill::ALL,
+                &mut skills,
+            )?;
+        }
+    }
+    let (count, list) = spec.class.skill_choices();
+    choose_skills("class", count, &spec.skills, &list, &mut skills)?;
+    let (_, free_skills) = spec.species.free_choices();
+    if free_skills > 0 {
+        choose_skills(
+            "species",
+            free_skills,
+            &spec.species_skills,
+            &Skill::ALL,
+            &mut skills,
+        )?;
+    } else if !spec.species_skills.is_empty() {
+        return Err(invalid(format!("{:?} has no skill choices", spec.species)));
+    }
+
+    let allowed = expertise_count(spec.class, spec.level);
+    if spec.expertise.len() > allowed {
+        return Err(invalid(format!(
+            "{:?} has {} Expertise pick(s) at level {}, got {}",
+            spec.class,
+            allowed,
+            spec.level,
+            spec.expertise.len()
+        )));
+    }
+    let mut expertise = HashSet::new();
+    for &s in &spec.expertise {
+        if !skills.contains(&s) {
+            return Err(invalid(format!("Expertise in {:?} needs proficiency", s)));
+        }
+        if !expertise.insert(s) {
+            return Err(invalid(format!("Expertise in {:?} picked twice", s)));
+        }
+    }
+
+    let actor = Actor {
+        proficiency_bonus: proficiency_bonus(1),
+        save_proficiencies: spec.class.saving_throws().into_iter().collect(),
+        skill_proficiencies: skills,
+        expertise,
+        abilities,
+    };
+    if let Some(style) = spec.fighting_style {
+        match fighting_styles(spec.class) {
+            Some((_, allowed)) if allowed.contains(&style) => {}
+            _ => {
+                return Err(invalid(format!(
+                    "{:?} cannot take the {:?} fighting style",
+                    spec.class, style
+                )))
+            }
+        }
+    }
+
+    if let Some(sub) = spec.subclass {
+        let (class, _) = sub.class_and_level();
+        if class != spec.class {
+            return Err(invalid(format!(
+                "{:?} is a {:?} subclass, not {:?}",
+                sub, class, spec.class
+            )));
+        }
+    }
+
+    let con = actor.ability_mod(Ability::Con);
+    let mut character = Character {
+        name: spec.name.clone(),
+        class: spec.class,
+        species: spec.species,
+        background: spec.background,
+        level: 1,
+        max_hp: (spec.class.hit_die() as i32 + con).max(1),
+        fighting_style: spec.fighting_style,
+        subclass: spec.subclass,
+        resources: Resources::default(),
+        hit_dice_spent: 0,
+        exhaustion: 0,
+        inventory: spec.inventory.clone(),
+        actor,
+    };
+    character.sync_features();
+    while character.level < spec.level {
+        character.level_up(spec.hp_method, dice)?;
+    }
+    Ok(character)
+}
+
+impl Character {
+    pub fn hit_die(&self) -> u8 {
+        self.class.hit_die()
+    }
+
+    /// One hit die per level, less those already spent.
+    pub fn hit_dice(&self) -> HitDice {
+        HitDice {
+            sides: self.hit_die(),
+            remaining: self.level.saturating_sub(self.hit_dice_spent),
+            max: self.level,
+        }
+    }
+
+    /// Gain a level: hit die (rolled or average) + Con mod, minimum 1. Returns HP gained.
+    pub fn level_up(&mut self, method: HpMethod, dice: &mut Dice) -> Result<i32> {
+        if self.level >= MAX_LEVEL {
+            return Err(invalid(format!("already at level {}", MAX_LEVEL)));
+        }
+        let die = self.hit_die();
+        let base = match method {
+            HpMethod::Average => die as i32 / 2 + 1,
+            HpMethod::Roll => dice.die(die) as i32,
+        };
+        let gained = (base + self.actor.ability_mod(Ability::Con)).max(1);
+        self.level += 1;
+        self.max_hp += gained;
+        self.actor.proficiency_bonus = proficiency_bonus(self.level);
+        self.sync_features();
+        Ok(gained)
+    }
+
+    /// Bring feature pools, the fighting style and Expertise in line with class and level.
+    pub fn sync_features(&mut self) {
+        let wanted = expertise_count(self.class, self.level);
+        let preferred = [Skill::Stealth, Skill::Perception]
+            .into_iter()
+            .chain(Skill::ALL);
+        for s in preferred {
+            if self.actor.expertise.len() >= wanted {
+                break;
+            }
+            if self.actor.skill_proficiencies.contains(&s) {
+                self.actor.expertise.insert(s);
+            }
+        }
+        match fighting_styles(self.class) {
+            Some((at, _)) if self.level >= at => {
+                self.fighting_style.get_or_insert(FightingStyle::Defense);
+            }
+            Some(_) => {} // a style picked early takes effect at the class's style level
+            None => self.fighting_style = None,
+        }
+        self.resources.sync(self.class, self.level);
+    }
+
+    /// The subclass once the character reaches its level, if any.
+    pub fn active_subclass(&self) -> Option<Subclass> {
+        self.subclass.filter(|s| {
+            let (class, at) = s.class_and_level();
+            class == self.class && self.level >= at
+        })
+    }
+
+    /// The fighting style in effect at the current level, if any.
+    pub fn active_fighting_style(&self) -> Option<FightingStyle> {
+        match fighting_styles(self.class) {
+            Some((at, _)) if self.level >= at => self.fighting_style,
+            _ => None,
+        }
+    }
+}
+
+/// The baked-in L1 Fighter used by demos and simulations: STR/CON saves,
+/// Athletics and Perception, 12 HP, Second Wind; no fighting style.
+pub fn sample_fighter() -> Character {
+    let abilities = AbilityScores {
+        str_: 16,
+        dex: 14,
+        con: 14,
+        int_: 10,
+        wis: 12,
+        cha: 8,
+    };
+    Character {
+        name: "Sample Fighter".to_string(),
+        class: Class::Fighter,
+        species: Species::Human,
+        background: Background::Custom,
+        level: 1,
+        max_hp: 12,
+        fighting_style: None,
+        subclass: None,
+        resources: Resources::for_class(Class::Fighter, 1),
+        hit_dice_spent: 0,
+        exhaustion: 0,
+        inventory: Vec::new(),
+        actor: Actor {
+            abilities,
+            proficiency_bonus: 2,
+            save_proficiencies: HashSet::from([Ability::Str, Ability::Con]),
+            skill_proficiencies: HashSet::from([Skill::Athletics, Skill::Perception]),
+            expertise: HashSet::new(),
+        },
+    }
+}
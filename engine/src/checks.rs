@@ -0,0 +1,156 @@
+//! Checks beyond a single d20 against a DC: passive scores, contests between two
+//! creatures and group checks.
+
+use crate::conditions::{check_vantage_from_conditions, ActiveCondition};
+use crate::explain::VantageBreakdown;
+use crate::{check, Ability, Actor, AdMode, CheckInput, CheckResult, Dice, Skill};
+
+/// 10 + modifier; advantage adds 5 and disadvantage subtracts 5.
+pub fn passive_score(modifier: i32, mode: AdMode) -> i32 {
+    let vantage = match mode {
+        AdMode::Normal => 0,
+        AdMode::Advantage => 5,
+        AdMode::Disadvantage => -5,
+    };
+    10 + modifier + vantage
+}
+
+/// One side of a contest, or one member of a group check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contestant {
+    pub modifier: i32,
+    pub mode: AdMode,
+}
+
+impl Contestant {
+    pub fn new(modifier: i32, mode: AdMode) -> Self {
+        Self { modifier, mode }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ContestResult {
+    pub initiator_roll: i32,
+    pub initiator_total: i32,
+    pub defender_roll: i32,
+    pub defender_total: i32,
+    /// A tie leaves things as they were, so the defender holds.
+    pub initiator_wins: bool,
+}
+
+/// Both sides roll; the initiator (grappler, shover, hider) must beat the defender.
+pub fn contest(dice: &mut Dice, initiator: Contestant, defender: Contestant) -> ContestResult {
+    let initiator_roll = dice.d20(initiator.mode) as i32;
+    let defender_roll = dice.d20(defender.mode) as i32;
+    let initiator_total = initiator_roll + initiator.modifier;
+    let defender_total = defender_roll + defender.modifier;
+    ContestResult {
+        initiator_roll,
+        initiator_total,
+        defender_roll,
+        defender_total,
+        initiator_wins: initiator_total > defender_total,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupCheckResult {
+    pub results: Vec<CheckResult>,
+    pub successes: usize,
+    /// At least half the group succeeded.
+    pub passed: bool,
+}
+
+/// Everyone rolls against the same DC; the group succeeds if at least half do.
+pub fn group_check(dice: &mut Dice, dc: i32, members: &[Contestant]) -> GroupCheckResult {
+    let results: Vec<CheckResult> = members
+        .iter()
+        .map(|m| {
+            check(
+                dice,
+                CheckInput {
+                    dc,
+                    modifier: m.modifier,
+                    mode: m.mode,
+                },
+            )
+        })
+        .collect();
+    let successes = results.iter().filter(|r| r.passed).count();
+    GroupCheckResult {
+        passed: !members.is_empty() && successes * 2 >= members.len(),
+        successes,
+        results,
+    }
+}
+
+/// The skill a defender resists a grapple or shove with: the better of Athletics and
+/// Acrobatics, given a way to look up skill modifiers.
+pub fn grapple_defense(skill_mod: impl Fn(Skill) -> i32) -> Skill {
+    if skill_mod(Skill::Acrobatics) > skill_mod(Skill::Athletics) {
+        Skill::Acrobatics
+    } else {
+        Skill::Athletics
+    }
+}
+
+/// A check rolled with the creature's conditions applied, and where its advantage or
+/// disadvantage came from.
+#[derive(Debug, Clone)]
+pub struct ConditionedCheck {
+    pub result: CheckResult,
+    pub mode: AdMode,
+    pub vantage: VantageBreakdown,
+}
+
+fn conditioned_check(
+    dice: &mut Dice,
+    modifier: i32,
+    conds: &[ActiveCondition],
+    mode: AdMode,
+    dc: i32,
+) -> ConditionedCheck {
+    let mut vantage = VantageBreakdown::default();
+    vantage.add("situational", mode.into());
+    vantage.extend(check_vantage_from_conditions(conds));
+    let mode = vantage.net().into();
+    ConditionedCheck {
+        result: check(dice, CheckInput { dc, modifier, mode }),
+        mode,
+        vantage,
+    }
+}
+
+impl Actor {
+    /// An ability check with the creature's conditions applied; `mode` is any advantage
+    /// or disadvantage the caller knows of besides them.
+    pub fn ability_check_with_conditions(
+        &self,
+        dice: &mut Dice,
+        a: Ability,
+        conds: &[ActiveCondition],
+        mode: AdMode,
+        dc: i32,
+    ) -> ConditionedCheck {
+        conditioned_check(dice, self.ability_mod(a), conds, mode, dc)
+    }
+
+    pub fn skill_check_with_conditions(
+        &self,
+        dice: &mut Dice,
+        s: Skill,
+        conds: &[ActiveCondition],
+        mode: AdMode,
+        dc: i32,
+    ) -> ConditionedCheck {
+        conditioned_check(dice, self.skill_mod(s), conds, mode, dc)
+    }
+
+    pub fn passive(&self, s: Skill, mode: AdMode) -> i32 {
+        passive_score(self.skill_mod(s), mode)
+    }
+
+    pub fn contestant(&self, s: Skill, mode: AdMode) -> Contestant {
+        Contestant::new(self.skill_mod(s), mode)
+    }
+}
@@ -0,0 +1,2611 @@
+//! Turn-based combat shared by the API, CLI and simulators: surprise, initiative,
+//! turns, attacks, conditions, death saves and class features chosen by [`Tactics`].
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::character::{Character, Class, Species};
+use crate::checks::{contest, grapple_defense, passive_score, ContestResult, Contestant};
+use crate::conditions::{
+    apply_condition, check_vantage_from_conditions, end_conditions_at_rest,
+    maybe_apply_on_hit_condition, process_source_boundary, process_turn_boundary,
+    vantage_sources_from_conditions, ActiveCondition, Applied, AttackStyle, ConditionKind,
+    ConditionSource, ConditionSpec, NamedTrigger, TurnBoundary, TurnTrigger, Vantage,
+};
+use crate::content::{DownedPolicy, Focus, TargetAttack, TargetData};
+use crate::effects::{
+    self, process_effect_boundary, process_effect_source_boundary, roll_terms, ActiveEffect, Stat,
+};
+use crate::explain::{Breakdown, RollExplanation, RollKind, Term, VantageBreakdown};
+use crate::features::{
+    attacks_per_action, aura_of_protection, crit_threshold, has_cunning_action, has_divine_smite,
+    has_feral_instinct, rage_damage_bonus, sneak_attack_dice, FightingStyle, Resource, Resources,
+    RestKind, SmitePolicy, Tactics, RAGE_ROUNDS,
+};
+use crate::items::{ItemAction, ItemEffect, ItemStack};
+use crate::legendary::{next_lair_action, LairAction, LegendaryActions, MonsterAction};
+use crate::life::{
+    apply_damage, apply_nonlethal_damage, heal, long_rest, process_death_save_start_of_turn,
+    reduce_max_hp, short_rest, soak_damage, Health, HitDice, LifeState, ShortRestPolicy,
+};
+use crate::saves::{SaveContext, SaveOutcome, SaveProfile, SaveScope};
+use crate::traits::{undead_fortitude_dc, Trait};
+use crate::{
+    Ability, AbilityScores, Actor, AdMode, AttackResult, AttackSource, Cover, CritRule, DamageDice,
+    DamageType, Defense, Dice, QualifiedDefense, SavingThrow, Skill, Weapon,
+};
+
+pub const DEFAULT_MAX_ROUNDS: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Party,
+    Enemies,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttackProfile {
+    pub name: String,
+    pub to_hit: i32,
+    pub dice: DamageDice,
+    pub damage_mod: i32,
+    pub damage_type: DamageType,
+    pub ranged: bool,
+    pub finesse: bool,
+    /// Wielded in two hands (a two-handed weapon, or a versatile one used two-handed).
+    pub two_handed: bool,
+    /// Ability behind the attack; Rage only boosts Strength attacks.
+    pub ability: Option<Ability>,
+    /// A weapon attack (Sneak Attack, Archery); false for thrown items like alchemist's fire.
+    pub weapon: bool,
+    /// Magic and material, for defenses against nonmagical or unsilvered attacks.
+    pub source: AttackSource,
+    pub apply_condition: Option<ConditionSpec>,
+    /// Save against losing hit point maximum equal to the damage (Life Drain).
+    pub drain: Option<SavingThrow>,
+}
+
+impl AttackProfile {
+    /// A weapon attack; `use_versatile` wields a versatile weapon two-handed.
+    pub fn from_weapon(
+        actor: &Actor,
+        weapon: &Weapon,
+        ability: Ability,
+        proficient: bool,
+        use_versatile: bool,
+    ) -> Self {
+        let versatile = weapon.versatile.filter(|_| use_versatile);
+        Self {
+            name: weapon.name.clone(),
+            to_hit: actor.attack_bonus(ability, proficient),
+            dice: versatile.unwrap_or(weapon.dice),
+            damage_mod: actor.damage_mod(ability),
+            damage_type: weapon
+                .damage_type
+                .or_else(|| preset_damage_type(&weapon.name))
+                .unwrap_or(DamageType::Slashing),
+            ranged: weapon.ranged,
+            finesse: weapon.finesse,
+            two_handed: weapon.two_handed || versatile.is_some(),
+            ability: Some(ability),
+            weapon: true,
+            source: weapon.source(),
+            apply_condition: None,
+            drain: None,
+        }
+    }
+
+    pub fn from_target_attack(attack: &TargetAttack) -> Self {
+        Self {
+            name: attack.name.clone(),
+            to_hit: attack.to_hit,
+            dice: attack.dice,
+            damage_mod: 0,
+            damage_type: attack.damage_type.unwrap_or(DamageType::Slashing),
+            ranged: attack.ranged,
+            finesse: false,
+            two_handed: false,
+            ability: None,
+            weapon: true,
+            source: AttackSource {
+                magical: attack.magical,
+                material: None,
+            },
+            apply_condition: attack.apply_condition.clone(),
+            drain: attack.drain,
+        }
+    }
+
+    /// A thrown consumable: an improvised ranged attack with Dex and no proficiency.
+    pub fn thrown_item(
+        actor: &Actor,
+        name: &str,
+        dice: DamageDice,
+        damage_type: DamageType,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            to_hit: actor.attack_bonus(Ability::Dex, false),
+            dice,
+            damage_mod: 0,
+            damage_type,
+            ranged: true,
+            finesse: false,
+            two_handed: false,
+            ability: None,
+            weapon: false,
+            source: AttackSource::default(),
+            apply_condition: None,
+            drain: None,
+        }
+    }
+
+    pub fn style(&self) -> AttackStyle {
+        if self.ranged {
+            AttackStyle::Ranged
+        } else {
+            AttackStyle::Melee
+        }
+    }
+}
+
+/// Dex for ranged weapons, the better of Str/Dex for finesse, otherwise Str.
+pub fn weapon_ability(actor: &Actor, weapon: &Weapon) -> Ability {
+    let dex_better = actor.ability_mod(Ability::Dex) > actor.ability_mod(Ability::Str);
+    if weapon.ranged || (weapon.finesse && dex_better) {
+        Ability::Dex
+    } else {
+        Ability::Str
+    }
+}
+
+fn preset_damage_type(name: &str) -> Option<DamageType> {
+    match name.to_lowercase().as_str() {
+        "longsword" | "greatsword" => Some(DamageType::Slashing),
+        "shortsword" | "dagger" | "longbow" => Some(DamageType::Piercing),
+        _ => None,
+    }
+}
+
+/// Class, level and feature pools a player character brings into combat.
+#[derive(Debug, Clone)]
+pub struct ClassKit {
+    pub class: Class,
+    pub level: u8,
+    pub fighting_style: Option<FightingStyle>,
+    pub resources: Resources,
+    /// Rounds of Rage left; 0 when not raging.
+    pub rage_rounds: u8,
+}
+
+impl ClassKit {
+    pub fn from_character(c: &Character) -> Self {
+        Self {
+            class: c.class,
+            level: c.level,
+            fighting_style: c.active_fighting_style(),
+            resources: c.resources.clone(),
+            rage_rounds: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Combatant {
+    pub name: String,
+    pub side: Side,
+    pub actor: Actor,
+    pub ac: i32,
+    pub cover: Cover,
+    pub health: Health,
+    pub conditions: Vec<ActiveCondition>,
+    pub attacks: Vec<AttackProfile>,
+    pub resistances: HashSet<DamageType>,
+    pub vulnerabilities: HashSet<DamageType>,
+    pub immunities: HashSet<DamageType>,
+    /// Defenses that depend on the attack's magic or material.
+    pub qualified_defenses: Vec<QualifiedDefense>,
+    /// Conditions that can't be applied to this combatant (undead: Poisoned).
+    pub condition_immunities: HashSet<ConditionKind>,
+    /// Advantage/disadvantage applied to every attack this combatant makes.
+    pub attack_vantage: Vantage,
+    pub kit: Option<ClassKit>,
+    /// Player characters roll death saves at 0 HP; monsters die outright unless their
+    /// stat block opts in.
+    pub death_saves: bool,
+    /// Carried items; consumables are used on this combatant's turn per [`Tactics`].
+    pub inventory: Vec<ItemStack>,
+    /// Resistances granted by potions until the next rest.
+    pub item_resistances: HashSet<DamageType>,
+    /// Conditions this combatant saves against with advantage (antitoxin) until the next rest.
+    pub save_advantage: HashSet<ConditionKind>,
+    /// Hit dice for short rests; None for creatures without a class.
+    pub hit_dice: Option<HitDice>,
+    pub exhaustion: u8,
+    /// Stat-block skill bonuses that replace the actor's own (monsters).
+    pub skill_bonuses: HashMap<Skill, i32>,
+    /// Roll initiative with advantage (Feral Instinct, or set by the caller).
+    pub initiative_advantage: bool,
+    /// Caught off guard: loses its first turn and can't react until that turn ends.
+    pub surprised: bool,
+    /// Conditions saved against with advantage by nature (Dwarven Resilience: Poisoned).
+    pub resilience: HashSet<ConditionKind>,
+    /// Advantage on saves against spells and other magical effects.
+    pub magic_resistance: bool,
+    /// Legendary Resistance uses left today: a failed save can succeed instead.
+    pub legendary_resistance: u8,
+    /// Buffs and debuffs on this combatant until they expire or the next rest.
+    pub effects: Vec<ActiveEffect>,
+    /// Lowest d20 roll that scores a critical hit (Improved Critical: 19).
+    pub crit_on: u8,
+    /// Critical hits against it become normal hits (adamantine armor).
+    pub crit_immune: bool,
+    /// Its own start- and end-of-turn triggers (a troll's Regeneration).
+    pub triggers: Vec<NamedTrigger>,
+    /// Stat-block traits (Pack Tactics, Nimble Escape).
+    pub traits: HashSet<Trait>,
+    /// Damage types it has taken since its last turn started, for suppressed healing.
+    pub damage_taken: HashSet<DamageType>,
+    pub legendary_actions: Option<LegendaryActions>,
+    /// Legendary actions left until the start of its next turn.
+    pub legendary_left: u32,
+    /// Lair actions on initiative count 20.
+    pub lair_actions: Vec<LairAction>,
+    /// The lair action used last round, which can't be used again this round.
+    last_lair: Option<usize>,
+    hidden: bool,
+    /// Index of the combatant holding this one Grappled, within the current fight.
+    grappled_by: Option<usize>,
+    sneak_attack_used: bool,
+}
+
+impl Combatant {
+    /// A party member without class features (e.g. a bare actor JSON).
+    pub fn from_actor(
+        name: impl Into<String>,
+        actor: Actor,
+        hp: i32,
+        ac: i32,
+        attack: AttackProfile,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            side: Side::Party,
+            actor,
+            ac,
+            cover: Cover::None,
+            health: Health::new(hp),
+            conditions: Vec::new(),
+            attacks: vec![attack],
+            resistances: HashSet::new(),
+            vulnerabilities: HashSet::new(),
+            immunities: HashSet::new(),
+            qualified_defenses: Vec::new(),
+            condition_immunities: HashSet::new(),
+            attack_vantage: Vantage::Normal,
+            kit: None,
+            death_saves: true,
+            inventory: Vec::new(),
+            item_resistances: HashSet::new(),
+            save_advantage: HashSet::new(),
+            hit_dice: None,
+            exhaustion: 0,
+            skill_bonuses: HashMap::new(),
+            initiative_advantage: false,
+            surprised: false,
+            resilience: HashSet::new(),
+            magic_resistance: false,
+            legendary_resistance: 0,
+            effects: Vec::new(),
+            crit_on: 20,
+            crit_immune: false,
+            triggers: Vec::new(),
+            traits: HashSet::new(),
+            damage_taken: HashSet::new(),
+            legendary_actions: None,
+            legendary_left: 0,
+            lair_actions: Vec::new(),
+            last_lair: None,
+            hidden: false,
+            grappled_by: None,
+            sneak_attack_used: false,
+        }
+    }
+
+    /// A party member with class features; HP starts at the character's max.
+    pub fn from_character(c: &Character, ac: i32, attack: AttackProfile) -> Self {
+        let name = if c.name.is_empty() {
+            "Actor".to_string()
+        } else {
+            c.name.clone()
+        };
+        let mut me = Self::from_actor(name, c.actor.clone(), c.max_hp, ac, attack);
+        me.kit = Some(ClassKit::from_character(c));
+        me.hit_dice = Some(c.hit_dice());
+        me.exhaustion = c.exhaustion;
+        me.initiative_advantage = has_feral_instinct(c.class, c.level);
+        me.crit_on = crit_threshold(c.active_subclass(), c.level);
+        if c.species == Species::Dwarf {
+            me.resistances.insert(DamageType::Poison);
+            me.resilience.insert(ConditionKind::Poisoned);
+        }
+        me
+    }
+
+    /// An enemy stat block. Without full ability scores only Dex is known.
+    pub fn from_target(t: &TargetData) -> Self {
+        let abilities = t.abilities.clone().unwrap_or(AbilityScores {
+            str_: 10,
+            dex: 10 + 2 * t.dex_mod,
+            con: 10,
+            int_: 10,
+            wis: 10,
+            cha: 10,
+        });
+        let actor = Actor {
+            abilities,
+            proficiency_bonus: 0,
+            save_proficiencies: HashSet::new(),
+            skill_proficiencies: HashSet::new(),
+            expertise: HashSet::new(),
+        };
+        Self {
+            name: t.name.clone(),
+            side: Side::Enemies,
+            actor,
+            ac: t.ac,
+            cover: t.cover,
+            health: Health {
+                threshold: t.damage_threshold,
+                ..Health::new(t.hp)
+            },
+            conditions: t
+                .conditions
+                .iter()
+                .map(|&k| ActiveCondition::indefinite(k))
+                .collect(),
+            attacks: t
+                .attacks
+                .iter()
+                .map(AttackProfile::from_target_attack)
+                .collect(),
+            resistances: t.resistances.clone(),
+            vulnerabilities: t.vulnerabilities.clone(),
+            immunities: t.immunities.clone(),
+            qualified_defenses: t.qualified_defenses.clone(),
+            condition_immunities: t.condition_immunities.clone(),
+            attack_vantage: Vantage::Normal,
+            kit: None,
+            death_saves: t.death_saves,
+            inventory: Vec::new(),
+            item_resistances: HashSet::new(),
+            save_advantage: HashSet::new(),
+            hit_dice: None,
+            exhaustion: 0,
+            skill_bonuses: t.skills.clone(),
+            initiative_advantage: false,
+            surprised: false,
+            resilience: HashSet::new(),
+            magic_resistance: t.magic_resistance,
+            legendary_resistance: t.legendary_resistance,
+            crit_on: 20,
+            crit_immune: t.crit_immune,
+            effects: Vec::new(),
+            triggers: t.regeneration.iter().map(|r| r.trigger()).collect(),
+            traits: t.traits.clone(),
+            damage_taken: HashSet::new(),
+            legendary_actions: t.legendary_actions.clone(),
+            legendary_left: t.legendary_actions.as_ref().map_or(0, |l| l.per_round),
+            lair_actions: t.lair_actions.clone(),
+            last_lair: None,
+            hidden: false,
+            grappled_by: None,
+            sneak_attack_used: false,
+        }
+    }
+
+    /// Conscious and able to act.
+    pub fn is_standing(&self) -> bool {
+        self.health.hp > 0 && matches!(self.health.state, LifeState::Conscious)
+    }
+
+    pub fn is_dead(&self) -> bool {
+        matches!(self.health.state, LifeState::Dead)
+    }
+
+    /// Unconscious at 0 HP but not dead: dying or stable.
+    pub fn is_down(&self) -> bool {
+        self.health.hp == 0 && matches!(self.health.state, LifeState::Unconscious { .. })
+    }
+
+    /// Heals itself each turn (a troll's Regeneration), so 0 HP doesn't kill it outright.
+    pub fn regenerates(&self) -> bool {
+        self.triggers
+            .iter()
+            .any(|t| matches!(t.trigger, TurnTrigger::Heal { .. }))
+    }
+
+    /// Paralyzed or unconscious: any hit from within 5 feet is a critical hit.
+    pub fn helpless(&self) -> Option<&'static str> {
+        if self.has_condition(ConditionKind::Paralyzed) {
+            Some("Paralyzed")
+        } else if matches!(self.health.state, LifeState::Unconscious { .. }) {
+            Some("unconscious")
+        } else {
+            None
+        }
+    }
+
+    /// Reactions need the creature standing and not still reeling from surprise.
+    pub fn can_react(&self) -> bool {
+        self.is_standing() && !self.surprised && self.incapacitated().is_none()
+    }
+
+    /// The condition keeping this combatant from acting (Stunned, Paralyzed), if any.
+    pub fn incapacitated(&self) -> Option<ConditionKind> {
+        self.conditions
+            .iter()
+            .map(|c| c.kind)
+            .find(|k| k.incapacitates())
+    }
+
+    pub fn skill_mod(&self, skill: Skill) -> i32 {
+        self.skill_bonuses
+            .get(&skill)
+            .copied()
+            .unwrap_or_else(|| self.actor.skill_mod(skill))
+    }
+
+    /// Disadvantage from conditions (Poisoned) counts as −5, advantage from Keen
+    /// Senses as +5.
+    pub fn passive_perception(&self) -> i32 {
+        self.passive_perception_in(false)
+    }
+
+    /// Passive Perception with Sunlight Sensitivity applied when in sunlight.
+    pub fn passive_perception_in(&self, sunlight: bool) -> i32 {
+        let mut vantage = check_vantage_from_conditions(&self.conditions);
+        if self.has_trait(Trait::KeenSenses) {
+            vantage.add(Trait::KeenSenses.name(), Vantage::Advantage);
+        }
+        if sunlight && self.has_trait(Trait::SunlightSensitivity) {
+            vantage.add(Trait::SunlightSensitivity.name(), Vantage::Disadvantage);
+        }
+        passive_score(self.skill_mod(Skill::Perception), vantage.net().into())
+    }
+
+    pub fn has_trait(&self, t: Trait) -> bool {
+        self.traits.contains(&t)
+    }
+
+    pub fn has_condition(&self, kind: ConditionKind) -> bool {
+        self.conditions.iter().any(|c| c.kind == kind)
+    }
+
+    /// The DC to escape a condition on this combatant, if it has one.
+    pub fn escape_dc(&self, kind: ConditionKind) -> Option<i32> {
+        self.conditions
+            .iter()
+            .find(|c| c.kind == kind)
+            .and_then(|c| c.escape_dc)
+    }
+
+    /// Everything that adjusts this combatant's saves, except allies' auras.
+    pub fn save_profile(&self) -> SaveProfile {
+        let mut profile = SaveProfile {
+            bonuses: effects::flat_terms(&self.effects, Stat::Save),
+            dice: effects::dice_for(&self.effects, Stat::Save),
+            legendary_resistance: self.legendary_resistance,
+            ..SaveProfile::from_conditions(&self.conditions)
+        };
+        for s in effects::vantage_sources(&self.effects, Stat::Save).sources {
+            profile.grant(SaveScope::All, s.source, s.vantage);
+        }
+        if self.raging() {
+            profile.grant(SaveScope::Ability(Ability::Str), "Rage", Vantage::Advantage);
+        }
+        for &kind in &self.resilience {
+            profile.grant(SaveScope::Against(kind), "resilience", Vantage::Advantage);
+        }
+        for &kind in &self.save_advantage {
+            profile.grant(SaveScope::Against(kind), "item", Vantage::Advantage);
+        }
+        if self.magic_resistance {
+            profile.grant(SaveScope::Magical, "Magic Resistance", Vantage::Advantage);
+        }
+        if self.exhaustion >= 3 {
+            profile.grant(
+                SaveScope::All,
+                format!("exhaustion {}", self.exhaustion),
+                Vantage::Disadvantage,
+            );
+        }
+        profile
+    }
+
+    pub fn raging(&self) -> bool {
+        self.kit.as_ref().is_some_and(|k| k.rage_rounds > 0)
+    }
+
+    fn style(&self) -> Option<FightingStyle> {
+        self.kit.as_ref().and_then(|k| k.fighting_style)
+    }
+
+    fn has_class(&self, class: Class) -> bool {
+        self.kit.as_ref().is_some_and(|k| k.class == class)
+    }
+
+    /// AC before cover (includes the Defense fighting style).
+    pub fn armor_class(&self) -> i32 {
+        self.ac
+            + i32::from(self.style() == Some(FightingStyle::Defense))
+            + effects::flat_bonus(&self.effects, Stat::Ac)
+    }
+
+    /// Short rest between fights: spend hit dice per `policy` and refresh short-rest features.
+    pub fn short_rest(
+        &mut self,
+        policy: &ShortRestPolicy,
+        dice: &mut Dice,
+        mut log: impl FnMut(String),
+    ) {
+        if self.is_dead() {
+            return;
+        }
+        let con = self.actor.ability_mod(Ability::Con);
+        match self.hit_dice.as_mut() {
+            Some(hit_dice) => {
+                short_rest(
+                    &self.name,
+                    &mut self.health,
+                    hit_dice,
+                    con,
+                    policy,
+                    |sides| dice.die(sides) as i32,
+                    &mut log,
+                );
+            }
+            None => log(format!("[REST][{}] short rest (no hit dice)", self.name)),
+        }
+        self.end_rage();
+        self.end_timed_effects(RestKind::Short, &mut log);
+        if let Some(kit) = self.kit.as_mut() {
+            kit.resources.refresh(RestKind::Short);
+        }
+    }
+
+    /// Long rest: full HP, half the hit dice back, every feature use restored.
+    pub fn long_rest(&mut self, mut log: impl FnMut(String)) {
+        let rested = long_rest(
+            &self.name,
+            &mut self.health,
+            self.hit_dice.as_mut(),
+            &mut self.exhaustion,
+            &mut log,
+        );
+        self.end_rage();
+        self.end_timed_effects(RestKind::Long, &mut log);
+        if let (true, Some(kit)) = (rested, self.kit.as_mut()) {
+            kit.resources.refresh(RestKind::Long);
+        }
+    }
+
+    fn end_rage(&mut self) {
+        if let Some(kit) = self.kit.as_mut() {
+            kit.rage_rounds = 0;
+        }
+    }
+
+    /// Potion effects last an hour, and effects and conditions with a counted
+    /// duration no longer, so any rest outlasts them; those lasting until a long rest
+    /// survive a short one.
+    fn end_timed_effects(&mut self, kind: RestKind, log: impl FnMut(String)) {
+        self.item_resistances.clear();
+        self.save_advantage.clear();
+        self.effects.retain(|e| !e.ends_at_rest(kind));
+        end_conditions_at_rest(&self.name, &mut self.conditions, kind, log);
+    }
+
+    fn resistances_now(&self) -> HashSet<DamageType> {
+        let mut set = self.resistances.clone();
+        set.extend(self.item_resistances.iter().copied());
+        set.extend(effects::resistances(&self.effects));
+        if self.raging() {
+            set.extend([
+                DamageType::Bludgeoning,
+                DamageType::Piercing,
+                DamageType::Slashing,
+            ]);
+        }
+        set
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CombatOptions {
+    pub max_rounds: u32,
+    /// How party members pick among living enemies.
+    pub focus: Focus,
+    pub tactics: Tactics,
+    /// The side that starts hidden; the other side may be surprised.
+    pub ambush: Option<Side>,
+    /// How critical hit damage is rolled.
+    pub crit_rule: CritRule,
+    /// The fight is in sunlight (Sunlight Sensitivity).
+    pub sunlight: bool,
+    /// Whether enemies attack party members who are down at 0 HP.
+    pub downed: DownedPolicy,
+}
+
+impl Default for CombatOptions {
+    fn default() -> Self {
+        Self {
+            max_rounds: DEFAULT_MAX_ROUNDS,
+            focus: Focus::First,
+            tactics: Tactics::default(),
+            ambush: None,
+            crit_rule: CritRule::default(),
+            sunlight: false,
+            downed: DownedPolicy::default(),
+        }
+    }
+}
+
+/// What a successful shove does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShoveEffect {
+    /// Knock the target Prone.
+    Prone,
+    /// Push it 5 feet away, out of reach of anyone it is grappling.
+    Push,
+}
+
+#[derive(Debug, Clone)]
+pub struct CombatOutcome {
+    pub rounds: u32,
+    /// The side left standing; None if both or neither are (round cap).
+    pub winner: Option<Side>,
+    pub combatants: Vec<Combatant>,
+    pub log: Vec<String>,
+    /// Every attack, damage roll, save and check, explained and tied to its log line.
+    pub rolls: Vec<RollExplanation>,
+}
+
+/// Run combat until one side has nobody standing or `max_rounds` is reached.
+pub fn run_combat(
+    combatants: Vec<Combatant>,
+    opts: &CombatOptions,
+    dice: &mut Dice,
+) -> CombatOutcome {
+    let mut combat = Combat {
+        cs: combatants,
+        dice,
+        log: Vec::new(),
+        rolls: Vec::new(),
+        opts,
+    };
+    for c in &combat.cs {
+        for cond in &c.conditions {
+            combat
+                .log
+                .push(format!("[COND][{}] starts with {:?}", c.name, cond.kind));
+        }
+    }
+
+    if let Some(side) = opts.ambush {
+        combat.surprise(side);
+    }
+    let order = combat.initiative();
+    let mut rounds = 0;
+    while rounds < opts.max_rounds && !combat.over() {
+        rounds += 1;
+        combat.log.push(format!("[ROUND] {}", rounds));
+        for &slot in &order {
+            if combat.over() {
+                break;
+            }
+            match slot {
+                Slot::Turn(i) => {
+                    let had_turn = !combat.cs[i].is_dead();
+                    combat.take_turn(i);
+                    if had_turn {
+                        combat.legendary_actions(i);
+                    }
+                }
+                Slot::Lair(i) => combat.lair_action(i),
+            }
+        }
+    }
+
+    combat.end_grapples();
+
+    let party = combat.side_standing(Side::Party);
+    let enemies = combat.side_standing(Side::Enemies);
+    let winner = match (party, enemies) {
+        (true, false) => Some(Side::Party),
+        (false, true) => Some(Side::Enemies),
+        _ => None,
+    };
+    CombatOutcome {
+        rounds,
+        winner,
+        combatants: combat.cs,
+        log: combat.log,
+        rolls: combat.rolls,
+    }
+}
+
+/// A place in the initiative order.
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Turn(usize),
+    /// Initiative count 20 for this creature's lair.
+    Lair(usize),
+}
+
+struct Combat<'a> {
+    cs: Vec<Combatant>,
+    dice: &'a mut Dice,
+    log: Vec<String>,
+    rolls: Vec<RollExplanation>,
+    opts: &'a CombatOptions,
+}
+
+impl Combat<'_> {
+    fn side_standing(&self, side: Side) -> bool {
+        self.cs.iter().any(|c| c.side == side && c.is_standing())
+    }
+
+    /// A side is still in the fight while someone on it stands, or while a creature on it
+    /// is down but may regenerate. A party member down at 0 HP keeps the party in it
+    /// while dying (it still rolls death saves), or while stable if a standing enemy
+    /// will finish off downed foes.
+    fn side_in_fight(&self, side: Side) -> bool {
+        if self.side_standing(side) {
+            return true;
+        }
+        if self
+            .cs
+            .iter()
+            .any(|c| c.side == side && c.is_down() && c.regenerates())
+        {
+            return true;
+        }
+        if side != Side::Party {
+            return false;
+        }
+        let finishers =
+            self.opts.downed == DownedPolicy::Finish && self.side_standing(Side::Enemies);
+        self.cs.iter().any(|c| {
+            c.side == side
+                && c.is_down()
+                && (c.health.state == (LifeState::Unconscious { stable: false }) || finishers)
+        })
+    }
+
+    fn over(&self) -> bool {
+        !self.side_in_fight(Side::Party) || !self.side_in_fight(Side::Enemies)
+    }
+
+    /// Each ambusher rolls Stealth; a creature on the other side is surprised if every
+    /// ambusher meets or beats its passive Perception.
+    fn surprise(&mut self, ambushers: Side) {
+        let mut lowest: Option<i32> = None;
+        for c in self
+            .cs
+            .iter()
+            .filter(|c| c.side == ambushers && c.is_standing())
+        {
+            let vantage = check_vantage_from_conditions(&c.conditions);
+            let roll = self.dice.d20(vantage.net().into()) as i32;
+            let mut modifiers = Breakdown::new("d20", roll);
+            modifiers.push("Stealth", c.skill_mod(Skill::Stealth));
+            let total = modifiers.total();
+            self.log
+                .push(format!("[SURPRISE][{}] Stealth {}", c.name, total));
+            let mut explained = RollExplanation::new(
+                self.log.len() - 1,
+                &c.name,
+                RollKind::Check,
+                "Stealth",
+                modifiers,
+            );
+            explained.vantage = vantage;
+            self.rolls.push(explained);
+            lowest = Some(lowest.map_or(total, |l| l.min(total)));
+        }
+        let Some(stealth) = lowest else {
+            return;
+        };
+        for c in self.cs.iter_mut() {
+            if c.side == ambushers || !c.is_standing() {
+                continue;
+            }
+            let passive = c.passive_perception_in(self.opts.sunlight);
+            c.surprised = stealth >= passive;
+            self.log.push(format!(
+                "[SURPRISE][{}] passive Perception {} → {}",
+                c.name,
+                passive,
+                if c.surprised {
+                    "surprised"
+                } else {
+                    "notices the ambush"
+                }
+            ));
+        }
+    }
+
+    /// Roll initiative (with advantage where granted); ties go to the higher Dex score,
+    /// then the higher die, then the party, then listing order. Lairs act on 20, losing
+    /// ties.
+    fn initiative(&mut self) -> Vec<Slot> {
+        let mut rolls: Vec<(i32, i32, usize)> = Vec::new();
+        let mut explained = Vec::new();
+        for (i, c) in self.cs.iter().enumerate() {
+            let mut vantage = check_vantage_from_conditions(&c.conditions);
+            if c.initiative_advantage {
+                vantage.add("initiative advantage", Vantage::Advantage);
+            }
+            let roll = self.dice.d20(vantage.net().into()) as i32;
+            let mut modifiers = Breakdown::new("d20", roll);
+            modifiers.push("Dex", c.actor.ability_mod(Ability::Dex));
+            let total = modifiers.total();
+            rolls.push((total, roll, i));
+            let mut roll =
+                RollExplanation::new(0, &c.name, RollKind::Check, "initiative", modifiers);
+            roll.vantage = vantage;
+            explained.push(roll);
+        }
+        let dex = |i: usize| self.cs[i].actor.abilities.dex;
+        rolls.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| dex(b.2).cmp(&dex(a.2)))
+                .then_with(|| b.1.cmp(&a.1))
+                .then_with(|| {
+                    let side = |i: usize| u8::from(self.cs[i].side != Side::Party);
+                    side(a.2).cmp(&side(b.2))
+                })
+                .then_with(|| a.2.cmp(&b.2))
+        });
+        let mut order: Vec<(Slot, i32)> = rolls
+            .iter()
+            .map(|&(total, _, i)| (Slot::Turn(i), total))
+            .collect();
+        let at = order.iter().take_while(|&&(_, total)| total >= 20).count();
+        let lairs = (0..self.cs.len())
+            .filter(|&i| !self.cs[i].lair_actions.is_empty())
+            .map(|i| (Slot::Lair(i), 20));
+        order.splice(at..at, lairs);
+        let listing: Vec<String> = order
+            .iter()
+            .map(|&(slot, total)| match slot {
+                Slot::Turn(i) => format!("{} {}", self.cs[i].name, total),
+                Slot::Lair(i) => format!("Lair ({}) {}", self.cs[i].name, total),
+            })
+            .collect();
+        self.log.push(format!("[INIT] {}", listing.join(", ")));
+        self.record(explained);
+        order.into_iter().map(|(slot, _)| slot).collect()
+    }
+
+    fn take_turn(&mut self, i: usize) {
+        if self.cs[i].is_dead() {
+            return;
+        }
+        self.cs[i].sneak_attack_used = false;
+        let c = &mut self.cs[i];
+        if let Some(legendary) = &c.legendary_actions {
+            c.legendary_left = legendary.per_round;
+        }
+
+        if self.cs[i].death_saves {
+            let c = &mut self.cs[i];
+            let log = &mut self.log;
+            let dice = &mut *self.dice;
+            let line = log.len();
+            let mut rolled = None;
+            if let Some(outcome) = process_death_save_start_of_turn(
+                &c.name,
+                &mut c.health,
+                || *rolled.insert(dice.d20(AdMode::Normal) as i32),
+                |msg| log.push(msg),
+            ) {
+                log.push(format!("[TURN][{}] death save: {}", c.name, outcome));
+            }
+            if let Some(roll) = rolled {
+                let mut save = RollExplanation::new(
+                    line,
+                    &c.name,
+                    RollKind::Save,
+                    "death save",
+                    Breakdown::new("d20", roll),
+                );
+                save.against = Some(10);
+                save.success = Some(roll >= 10);
+                self.rolls.push(save);
+            }
+        }
+        self.boundary(i, TurnBoundary::StartOfTurn);
+
+        match self.cs[i].health.state {
+            LifeState::Dead => {
+                self.log
+                    .push(format!("[TURN][{}] is dead; skipping", self.cs[i].name));
+            }
+            LifeState::Unconscious { .. } => {
+                self.log.push(format!(
+                    "[TURN][{}] is unconscious; skipping actions",
+                    self.cs[i].name
+                ));
+            }
+            LifeState::Conscious if self.cs[i].surprised => {
+                self.log.push(format!(
+                    "[TURN][{}] is surprised; skipping actions",
+                    self.cs[i].name
+                ));
+            }
+            LifeState::Conscious if self.cs[i].incapacitated().is_some() => {
+                let kind = self.cs[i].incapacitated().expect("checked above");
+                self.log.push(format!(
+                    "[TURN][{}] is {}; skipping actions",
+                    self.cs[i].name,
+                    format!("{:?}", kind).to_lowercase()
+                ));
+            }
+            LifeState::Conscious => {
+                self.check_grapple(i);
+                self.act(i);
+            }
+        }
+
+        self.cs[i].surprised = false;
+        self.boundary(i, TurnBoundary::EndOfTurn);
+        self.tick_rage(i);
+    }
+
+    /// Condition expiry and save-ends checks at a turn boundary.
+    fn boundary(&mut self, i: usize, when: TurnBoundary) {
+        let mut saves = self.save_profile(i);
+        let mut rolled = Vec::new();
+        let from = self.log.len();
+        let dice = &mut *self.dice;
+        let log = &mut self.log;
+        let c = &mut self.cs[i];
+        let actor = &c.actor;
+        let mut due: Vec<NamedTrigger> = c
+            .triggers
+            .iter()
+            .filter(|t| t.trigger.fires_at(when))
+            .cloned()
+            .collect();
+        due.extend(process_turn_boundary(
+            when,
+            &c.name,
+            &mut c.conditions,
+            |ctx| roll_save(&mut saves, dice, actor, ctx, &mut rolled),
+            |msg| log.push(msg),
+        ));
+        due.extend(process_effect_boundary(
+            when,
+            &c.name,
+            &mut c.effects,
+            |ctx| roll_save(&mut saves, dice, actor, ctx, &mut rolled),
+            |msg| log.push(msg),
+        ));
+        c.legendary_resistance = saves.legendary_resistance;
+        let name = c.name.clone();
+        self.record_saves(from, &name, rolled);
+        for t in due {
+            self.fire_trigger(i, &t);
+        }
+        if when == TurnBoundary::StartOfTurn {
+            self.cs[i].damage_taken.clear();
+        }
+
+        // Conditions and effects this creature imposed on others "until its next turn".
+        let log = &mut self.log;
+        for (_, other) in self.cs.iter_mut().enumerate().filter(|&(j, _)| j != i) {
+            process_source_boundary(when, i, &name, &other.name, &mut other.conditions, |msg| {
+                log.push(msg)
+            });
+            process_effect_source_boundary(when, &name, &other.name, &mut other.effects, |msg| {
+                log.push(msg)
+            });
+        }
+    }
+
+    /// Ongoing damage, adjusted for resistances, or healing unless suppressed.
+    fn fire_trigger(&mut self, i: usize, due: &NamedTrigger) {
+        if self.cs[i].is_dead() {
+            return;
+        }
+        match &due.trigger {
+            &TurnTrigger::Damage {
+                dice: spec,
+                bonus,
+                damage_type,
+                ..
+            } => {
+                let mut damage = Breakdown::new(
+                    format!("{}d{}", spec.count, spec.sides),
+                    spec.roll_total(self.dice, false),
+                );
+                damage.push("bonus", bonus);
+                self.typed_damage(i, &due.name, damage, damage_type);
+            }
+            TurnTrigger::Heal {
+                amount,
+                suppressed_by,
+                ..
+            } => {
+                let c = &mut self.cs[i];
+                if c.health.hp <= 0 && !c.regenerates() {
+                    return;
+                }
+                let log = &mut self.log;
+                if let Some(why) = suppressed_by.iter().find(|d| c.damage_taken.contains(d)) {
+                    log.push(format!(
+                        "[HEAL][{}] {} suppressed ({:?} damage)",
+                        c.name, due.name, why
+                    ));
+                    if c.is_down() {
+                        c.health.state = LifeState::Dead;
+                        log.push(format!("[ENEMY] {} defeated", c.name));
+                    }
+                    return;
+                }
+                log.push(format!("[HEAL][{}] {}: {}", c.name, due.name, amount));
+                heal(&c.name, &mut c.health, *amount, |msg| log.push(msg));
+            }
+        }
+    }
+
+    /// Damage outside an attack (ongoing damage, a dragon's Wing Attack), adjusted for
+    /// resistances and explained.
+    fn typed_damage(&mut self, i: usize, what: &str, mut damage: Breakdown, dtype: DamageType) {
+        let rolled = damage.total();
+        let c = &mut self.cs[i];
+        let (dealt, why) = adjusted_damage(c, &c.resistances_now(), rolled, dtype, None);
+        damage.push(why, dealt - rolled);
+        self.log.push(format!(
+            "[DMG][{}] {}: {} [{:?}]",
+            c.name, what, damage, dtype
+        ));
+        let line = self.log.len() - 1;
+        self.rolls.push(RollExplanation::new(
+            line,
+            &c.name,
+            RollKind::Damage,
+            format!("{} damage", what),
+            damage,
+        ));
+        if dealt > 0 {
+            c.damage_taken.insert(dtype);
+        }
+        damage_combatant(c, dealt, false, false, &mut self.log);
+    }
+
+    /// After creature `after`'s turn, each other legendary creature able to act may
+    /// spend one legendary action: the costliest it can still pay for.
+    fn legendary_actions(&mut self, after: usize) {
+        for i in 0..self.cs.len() {
+            if i == after || self.over() {
+                continue;
+            }
+            let c = &self.cs[i];
+            if !c.is_standing() || c.incapacitated().is_some() || c.surprised {
+                continue;
+            }
+            let Some(action) = c
+                .legendary_actions
+                .as_ref()
+                .and_then(|l| l.choose(c.legendary_left))
+                .cloned()
+            else {
+                continue;
+            };
+            let c = &mut self.cs[i];
+            c.legendary_left -= action.cost;
+            self.log.push(format!(
+                "[LEGENDARY][{}] {} (cost {}, {} left)",
+                c.name, action.name, action.cost, c.legendary_left
+            ));
+            self.monster_action(i, &action.name, &action.effect);
+        }
+    }
+
+    /// Initiative count 20: the creature's next lair action, if it can act.
+    fn lair_action(&mut self, i: usize) {
+        let c = &mut self.cs[i];
+        if !c.is_standing() || c.incapacitated().is_some() || c.surprised {
+            return;
+        }
+        c.last_lair = next_lair_action(c.lair_actions.len(), c.last_lair);
+        let Some(n) = c.last_lair else {
+            self.log
+                .push(format!("[LAIR][{}] no lair action this round", c.name));
+            return;
+        };
+        let action = c.lair_actions[n].clone();
+        self.log.push(format!("[LAIR][{}] {}", c.name, action.name));
+        self.monster_action(i, &action.name, &action.effect);
+    }
+
+    fn monster_action(&mut self, i: usize, name: &str, action: &MonsterAction) {
+        match action {
+            MonsterAction::Attack { attack } => {
+                let Some(profile) = self.cs[i]
+                    .attacks
+                    .iter()
+                    .find(|a| &a.name == attack)
+                    .cloned()
+                else {
+                    return;
+                };
+                if let Some(t) = self.choose_target(i) {
+                    self.attack(i, t, &profile);
+                }
+            }
+            &MonsterAction::Save {
+                save,
+                dice: spec,
+                bonus,
+                damage_type,
+                half_on_success,
+                ref condition,
+            } => {
+                // One damage roll for everyone in the area.
+                let damage = spec.map(|spec| {
+                    let mut damage = Breakdown::new(
+                        format!("{}d{}", spec.count, spec.sides),
+                        spec.roll_total(self.dice, false),
+                    );
+                    damage.push("bonus", bonus);
+                    damage
+                });
+                let side = self.cs[i].side;
+                let source = ConditionSource::new(i, self.cs[i].name.clone(), name);
+                let foes: Vec<usize> = (0..self.cs.len())
+                    .filter(|&t| self.cs[t].side != side && self.cs[t].is_standing())
+                    .collect();
+                for t in foes {
+                    let ctx = SaveContext::new(save.ability, save.dc);
+                    let success = self.area_save(t, name, &ctx);
+                    if let Some(mut damage) = damage.clone() {
+                        let rolled = damage.total();
+                        if success {
+                            let kept = if half_on_success { rolled / 2 } else { 0 };
+                            damage.push("save", kept - rolled);
+                        }
+                        if damage.total() > 0 {
+                            let dtype = damage_type.unwrap_or(DamageType::Bludgeoning);
+                            self.typed_damage(t, name, damage, dtype);
+                        }
+                    }
+                    if let Some(spec) = condition.as_ref().filter(|_| !success) {
+                        if self.cs[t].is_standing() {
+                            let c = &mut self.cs[t];
+                            let log = &mut self.log;
+                            apply_condition(
+                                &c.name,
+                                &mut c.conditions,
+                                &c.condition_immunities,
+                                ActiveCondition::from_spec_for_application(spec)
+                                    .with_source(source.clone()),
+                                |msg| log.push(msg),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Combatant `t` saves against an area effect named `what`.
+    fn area_save(&mut self, t: usize, what: &str, ctx: &SaveContext) -> bool {
+        let mut saves = self.save_profile(t);
+        let mut rolled = Vec::new();
+        let from = self.log.len();
+        let c = &mut self.cs[t];
+        let outcome = roll_save(&mut saves, self.dice, &c.actor, ctx, &mut rolled);
+        c.legendary_resistance = saves.legendary_resistance;
+        self.log.push(format!(
+            "[SAVE][{}] makes a {:?} save DC {} vs {}: roll={} total={} → {}",
+            c.name,
+            ctx.ability,
+            ctx.dc,
+            what,
+            outcome.roll,
+            outcome.total,
+            if outcome.success { "SUCCESS" } else { "FAIL" }
+        ));
+        let name = c.name.clone();
+        self.record_saves(from, &name, rolled);
+        outcome.success
+    }
+
+    /// A skill check by combatant `i` with its effects' bonuses and advantage folded in,
+    /// and its explanation so far (the d20 is added once rolled).
+    fn check_contestant(
+        &mut self,
+        i: usize,
+        skill: Skill,
+        mut vantage: VantageBreakdown,
+    ) -> (Contestant, RollExplanation) {
+        let label = format!("{:?}", skill);
+        let base = self.cs[i].skill_mod(skill);
+        let mut modifiers = Breakdown::new(label.as_str(), base);
+        modifiers
+            .terms
+            .extend(self.effect_terms(i, Stat::Check, &label, base));
+        vantage.extend(check_vantage_from_conditions(&self.cs[i].conditions));
+        vantage.extend(effects::vantage_sources(&self.cs[i].effects, Stat::Check));
+        let contestant = Contestant::new(modifiers.total(), vantage.net().into());
+        let mut explained =
+            RollExplanation::new(0, &self.cs[i].name, RollKind::Check, label, modifiers);
+        explained.vantage = vantage;
+        (contestant, explained)
+    }
+
+    /// Roll combatant `i`'s effect terms for one of its rolls, logging the breakdown
+    /// (`[EFFECT][X] attack: to-hit 5 + Bless 1d4 3 = 8`) when there are any.
+    fn effect_terms(&mut self, i: usize, stat: Stat, label: &str, base: i32) -> Vec<Term> {
+        let terms = roll_terms(self.dice, &self.cs[i].effects, stat);
+        if !terms.is_empty() {
+            let mut breakdown = Breakdown::new(label, base);
+            breakdown.terms.extend(terms.iter().cloned());
+            self.log.push(format!(
+                "[EFFECT][{}] {}: {}",
+                self.cs[i].name, stat, breakdown
+            ));
+        }
+        terms
+    }
+
+    /// Attach rolls to the log line just written.
+    fn record(&mut self, rolls: impl IntoIterator<Item = RollExplanation>) {
+        let index = self.log.len().saturating_sub(1);
+        for mut roll in rolls {
+            roll.log_index = index;
+            self.rolls.push(roll);
+        }
+    }
+
+    /// Record saves rolled since log line `from`, pairing each with its `[SAVE]` line.
+    fn record_saves(&mut self, from: usize, name: &str, saves: Vec<(SaveContext, SaveOutcome)>) {
+        let prefix = format!("[SAVE][{}]", name);
+        let lines: Vec<usize> = (from..self.log.len())
+            .filter(|&n| self.log[n].starts_with(&prefix))
+            .collect();
+        for ((ctx, outcome), index) in saves.into_iter().zip(lines) {
+            self.rolls.push(explain_save(index, name, &ctx, outcome));
+        }
+    }
+
+    /// A combatant's save profile plus the best Aura of Protection among conscious
+    /// allies (positions aren't tracked, so every ally counts as within 10 feet).
+    fn save_profile(&self, i: usize) -> SaveProfile {
+        let mut profile = self.cs[i].save_profile();
+        let aura = self
+            .cs
+            .iter()
+            .filter(|c| c.side == self.cs[i].side && c.is_standing())
+            .filter_map(|c| {
+                let kit = c.kit.as_ref()?;
+                Some(aura_of_protection(
+                    kit.class,
+                    kit.level,
+                    c.actor.ability_mod(Ability::Cha),
+                ))
+            })
+            .max()
+            .unwrap_or(0);
+        if aura > 0 {
+            profile.bonuses.push(Term::new("Aura of Protection", aura));
+        }
+        profile
+    }
+
+    /// One turn's worth of decisions: standing up or escaping a grapple, bonus-action
+    /// features, the Attack action, Action Surge.
+    fn act(&mut self, i: usize) {
+        let tactics = self.opts.tactics;
+        let mut bonus_action_free = true;
+
+        // Prone and pinned by a grapple: spend the action breaking free, then stand.
+        if self.cs[i].has_condition(ConditionKind::Prone) && self.cs[i].grappled_by.is_some() {
+            if self.escape(i) {
+                self.stand_up(i);
+            }
+            return;
+        }
+        // Restrained by something with an escape DC (a web, a net): break free first.
+        if let Some(dc) = self.cs[i].escape_dc(ConditionKind::Restrained) {
+            self.escape_condition(i, ConditionKind::Restrained, dc);
+            return;
+        }
+        self.stand_up(i);
+
+        if tactics.rage && self.cs[i].has_class(Class::Barbarian) && !self.cs[i].raging() {
+            let c = &mut self.cs[i];
+            let kit = c.kit.as_mut().expect("barbarian has a kit");
+            if kit.resources.spend(Resource::Rage) {
+                kit.rage_rounds = RAGE_ROUNDS;
+                bonus_action_free = false;
+                self.log.push(format!(
+                    "[FEATURE][{}] Rage (+{} melee damage, resists B/P/S; {} use(s) left)",
+                    c.name,
+                    rage_damage_bonus(kit.level),
+                    kit.resources.remaining(Resource::Rage)
+                ));
+            }
+        }
+
+        if bonus_action_free && self.cs[i].has_class(Class::Fighter) {
+            let c = &self.cs[i];
+            let hurt = c.health.hp * 100
+                <= c.health.effective_max_hp() * tactics.second_wind_below_pct as i32;
+            if hurt && self.spend(i, Resource::SecondWind) {
+                bonus_action_free = false;
+                let level = self.cs[i].kit.as_ref().map_or(1, |k| k.level) as i32;
+                let amount = self.dice.die(10) as i32 + level;
+                let c = &mut self.cs[i];
+                let log = &mut self.log;
+                log.push(format!("[FEATURE][{}] Second Wind: 1d10+{}", c.name, level));
+                heal(&c.name, &mut c.health, amount, |msg| log.push(msg));
+            }
+        }
+
+        let can_hide = self.cs[i]
+            .kit
+            .as_ref()
+            .is_some_and(|k| has_cunning_action(k.class, k.level));
+        if bonus_action_free && tactics.cunning_hide && can_hide && !self.cs[i].hidden {
+            self.try_hide(i, "Cunning Action");
+            bonus_action_free = false;
+        }
+        // Nimble Escape needs something to hide behind: only a creature with cover tries.
+        let c = &self.cs[i];
+        if bonus_action_free
+            && c.has_trait(Trait::NimbleEscape)
+            && c.cover != Cover::None
+            && !c.hidden
+        {
+            self.try_hide(i, Trait::NimbleEscape.name());
+            bonus_action_free = false;
+        }
+
+        match self.use_item(i, bonus_action_free) {
+            Some(ItemAction::Action) => {}
+            Some(ItemAction::BonusAction) | None => self.attack_action(i),
+        }
+
+        if tactics.action_surge
+            && self.cs[i].has_class(Class::Fighter)
+            && !self.over()
+            && self.spend(i, Resource::ActionSurge)
+        {
+            self.log.push(format!(
+                "[FEATURE][{}] Action Surge: takes another action",
+                self.cs[i].name
+            ));
+            self.attack_action(i);
+        }
+    }
+
+    /// Pick one consumable to use this turn: heal when low, a buff that counters an
+    /// enemy's attacks, or a throw that beats the weapon. Returns the action it cost.
+    fn use_item(&mut self, i: usize, bonus_action_free: bool) -> Option<ItemAction> {
+        let tactics = self.opts.tactics;
+        let c = &self.cs[i];
+        let usable = |s: &ItemStack| {
+            !s.is_empty() && (bonus_action_free || s.def.action == ItemAction::Action)
+        };
+        let hurt = c.health.hp * 100 <= c.health.effective_max_hp() * tactics.heal_below_pct as i32;
+        let enemy_attacks: Vec<&AttackProfile> = self
+            .cs
+            .iter()
+            .filter(|e| e.side != c.side && e.is_standing())
+            .flat_map(|e| e.attacks.iter())
+            .collect();
+        let resists = c.resistances_now();
+
+        let pick = c.inventory.iter().position(|s| {
+            usable(s)
+                && match s.def.effect {
+                    Some(ItemEffect::Heal { .. }) => hurt,
+                    _ => false,
+                }
+        });
+        let pick = pick.or_else(|| {
+            if !tactics.buff_items {
+                return None;
+            }
+            c.inventory.iter().position(|s| {
+                usable(s)
+                    && match s.def.effect {
+                        Some(ItemEffect::Resistance { damage_type }) => {
+                            !resists.contains(&damage_type)
+                                && !c.immunities.contains(&damage_type)
+                                && enemy_attacks.iter().any(|a| a.damage_type == damage_type)
+                        }
+                        Some(ItemEffect::SaveAdvantage { against }) => {
+                            !c.save_advantage.contains(&against)
+                                && enemy_attacks.iter().any(|a| {
+                                    a.apply_condition
+                                        .as_ref()
+                                        .is_some_and(|s| s.kind == against)
+                                })
+                        }
+                        _ => false,
+                    }
+            })
+        });
+        let pick = match pick {
+            Some(p) => Some((p, None)),
+            None => self.pick_throw(i, bonus_action_free),
+        }?;
+
+        let (slot, target) = pick;
+        let c = &mut self.cs[i];
+        let stack = &mut c.inventory[slot];
+        stack.use_one();
+        let def = stack.def.clone();
+        let left = stack.quantity;
+        match def.effect.expect("picked items have an effect") {
+            ItemEffect::Heal { dice, bonus } => {
+                let amount = roll_dice(self.dice, dice, None, false) + bonus;
+                self.log.push(format!(
+                    "[ITEM][{}] drinks {} ({} left): {}d{}{} = {}",
+                    c.name,
+                    def.name,
+                    left,
+                    dice.count,
+                    dice.sides,
+                    format_modifier(bonus),
+                    amount
+                ));
+                let log = &mut self.log;
+                heal(&c.name, &mut c.health, amount, |msg| log.push(msg));
+            }
+            ItemEffect::Resistance { damage_type } => {
+                c.item_resistances.insert(damage_type);
+                self.log.push(format!(
+                    "[ITEM][{}] drinks {} ({} left): resists {:?} until the next rest",
+                    c.name, def.name, left, damage_type
+                ));
+            }
+            ItemEffect::SaveAdvantage { against } => {
+                c.save_advantage.insert(against);
+                self.log.push(format!(
+                    "[ITEM][{}] drinks {} ({} left): advantage on saves vs {:?} until the next rest",
+                    c.name, def.name, left, against
+                ));
+            }
+            ItemEffect::Thrown { dice, damage_type } => {
+                let t = target.expect("throws have a target");
+                let profile = AttackProfile::thrown_item(&c.actor, &def.name, dice, damage_type);
+                self.log.push(format!(
+                    "[ITEM][{}] throws {} at {} ({} left)",
+                    self.cs[i].name, def.name, self.cs[t].name, left
+                ));
+                self.attack(i, t, &profile);
+            }
+        }
+        Some(def.action)
+    }
+
+    /// A thrown item whose average damage against the current target beats one
+    /// weapon attack. Only for single-attack turns; Extra Attack always wins.
+    fn pick_throw(&mut self, i: usize, bonus_action_free: bool) -> Option<(usize, Option<usize>)> {
+        let c = &self.cs[i];
+        let single_attack = c
+            .kit
+            .as_ref()
+            .map_or(1, |k| attacks_per_action(k.class, k.level))
+            == 1;
+        if !self.opts.tactics.throw_items || !single_attack {
+            return None;
+        }
+        let has_throw = c.inventory.iter().any(|s| {
+            !s.is_empty()
+                && (bonus_action_free || s.def.action == ItemAction::Action)
+                && matches!(s.def.effect, Some(ItemEffect::Thrown { .. }))
+        });
+        if !has_throw {
+            return None;
+        }
+        let t = self.choose_target(i)?;
+        let c = &self.cs[i];
+        let target = &self.cs[t];
+        let resists = target.resistances_now();
+        let expected = |dice: DamageDice, modifier: i32, dt: DamageType, source: AttackSource| {
+            let doubled = i32::from(dice.count) * (i32::from(dice.sides) + 1) + 2 * modifier;
+            adjusted_damage(target, &resists, doubled, dt, Some(source)).0
+        };
+        // Sneak Attack only rides on weapons, so count it against the throw.
+        let sneak = c
+            .kit
+            .as_ref()
+            .map_or(0, |k| sneak_attack_dice(k.class, k.level));
+        let weapon = c.attacks.first().map_or(0, |a| {
+            let sneak = if a.finesse || a.ranged { sneak } else { 0 };
+            expected(a.dice, a.damage_mod, a.damage_type, a.source)
+                + expected(DamageDice::new(sneak, 6), 0, a.damage_type, a.source)
+        });
+        c.inventory
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| {
+                !s.is_empty() && (bonus_action_free || s.def.action == ItemAction::Action)
+            })
+            .filter_map(|(slot, s)| match s.def.effect {
+                Some(ItemEffect::Thrown { dice, damage_type }) => Some((
+                    slot,
+                    expected(dice, 0, damage_type, AttackSource::default()),
+                )),
+                _ => None,
+            })
+            .filter(|&(_, avg)| avg > weapon)
+            .max_by_key(|&(slot, avg)| (avg, std::cmp::Reverse(slot)))
+            .map(|(slot, _)| (slot, Some(t)))
+    }
+
+    fn spend(&mut self, i: usize, res: Resource) -> bool {
+        self.cs[i]
+            .kit
+            .as_mut()
+            .is_some_and(|k| k.resources.spend(res))
+    }
+
+    /// Hide as a bonus action (Cunning Action, Nimble Escape). Stealth vs the best
+    /// passive Perception among standing foes.
+    fn try_hide(&mut self, i: usize, feature: &str) {
+        let side = self.cs[i].side;
+        let sunlight = self.opts.sunlight;
+        let Some(passive) = self
+            .cs
+            .iter()
+            .filter(|c| c.side != side && c.is_standing())
+            .map(|c| c.passive_perception_in(sunlight))
+            .max()
+        else {
+            return;
+        };
+        let (stealth, pending) =
+            self.check_contestant(i, Skill::Stealth, VantageBreakdown::default());
+        let roll = self.dice.d20(stealth.mode) as i32;
+        let total = roll + stealth.modifier;
+        let c = &mut self.cs[i];
+        c.hidden = total >= passive;
+        self.log.push(format!(
+            "[FEATURE][{}] {}: Hide (Stealth {} vs passive Perception {}) → {}",
+            c.name,
+            feature,
+            total,
+            passive,
+            if c.hidden { "hidden" } else { "spotted" }
+        ));
+        let hidden = c.hidden;
+        self.record([rolled_check(pending, roll, Some(passive), Some(hidden))]);
+    }
+
+    fn attack_action(&mut self, i: usize) {
+        let count = self.cs[i]
+            .kit
+            .as_ref()
+            .map_or(1, |k| attacks_per_action(k.class, k.level));
+        if self.cs[i].attacks.is_empty() {
+            self.log
+                .push(format!("[TURN][{}] has no attacks", self.cs[i].name));
+            return;
+        }
+        for n in 0..count {
+            let Some(t) = self.choose_target(i) else {
+                break;
+            };
+            if n > 0 {
+                self.log.push(format!(
+                    "[FEATURE][{}] Extra Attack ({}/{})",
+                    self.cs[i].name,
+                    n + 1,
+                    count
+                ));
+            }
+            let profile = self.cs[i].attacks[0].clone();
+            if self.opts.tactics.grapple_shove
+                && self.cs[i].kit.is_some()
+                && !profile.ranged
+                && self.maneuver(i, t, &profile)
+            {
+                continue;
+            }
+            self.attack(i, t, &profile);
+        }
+    }
+
+    /// Grapple or shove in place of an attack: push a foe off a grappled ally, shove a
+    /// creature we hold prone, or grab a new one. Returns false to attack instead.
+    fn maneuver(&mut self, i: usize, t: usize, profile: &AttackProfile) -> bool {
+        let side = self.cs[i].side;
+        let target = &self.cs[t];
+        let holds_ally = self
+            .cs
+            .iter()
+            .enumerate()
+            .any(|(j, c)| j != i && c.side == side && c.grappled_by == Some(t));
+        if holds_ally {
+            self.shove(i, t, ShoveEffect::Push);
+            return true;
+        }
+        // Not worth a turn against something one hit will drop.
+        let average =
+            profile.dice.count as i32 * (profile.dice.sides as i32 + 1) / 2 + profile.damage_mod;
+        if target.health.hp <= average {
+            return false;
+        }
+        if target.grappled_by == Some(i) {
+            if target.has_condition(ConditionKind::Prone)
+                || target.condition_immunities.contains(&ConditionKind::Prone)
+            {
+                return false;
+            }
+            self.shove(i, t, ShoveEffect::Prone);
+            return true;
+        }
+        let attacker = &self.cs[i];
+        let defense = grapple_defense(|s| target.skill_mod(s));
+        if target.grappled_by.is_some()
+            || target
+                .condition_immunities
+                .contains(&ConditionKind::Grappled)
+            || attacker.skill_mod(Skill::Athletics) < target.skill_mod(defense)
+        {
+            return false;
+        }
+        self.grapple(i, t);
+        true
+    }
+
+    /// Athletics contest against the target's Athletics or Acrobatics; Rage gives
+    /// advantage on the Strength check.
+    fn contest_athletics(
+        &mut self,
+        i: usize,
+        t: usize,
+    ) -> (Skill, ContestResult, [RollExplanation; 2]) {
+        let mut rage = VantageBreakdown::default();
+        if self.cs[i].raging() {
+            rage.add("Rage", Vantage::Advantage);
+        }
+        let attacker = self.check_contestant(i, Skill::Athletics, rage);
+        let defense = grapple_defense(|s| self.cs[t].skill_mod(s));
+        let defender = self.check_contestant(t, defense, VantageBreakdown::default());
+        let (res, rolls) = self.contest(attacker, defender);
+        (defense, res, rolls)
+    }
+
+    /// Roll a contest between two explained checks.
+    fn contest(
+        &mut self,
+        (initiator, mine): (Contestant, RollExplanation),
+        (defender, theirs): (Contestant, RollExplanation),
+    ) -> (ContestResult, [RollExplanation; 2]) {
+        let res = contest(self.dice, initiator, defender);
+        let wins = res.initiator_wins;
+        (
+            res,
+            [
+                rolled_check(mine, res.initiator_roll, None, Some(wins)),
+                rolled_check(theirs, res.defender_roll, None, Some(!wins)),
+            ],
+        )
+    }
+
+    fn grapple(&mut self, i: usize, t: usize) {
+        let (defense, res, rolls) = self.contest_athletics(i, t);
+        let won = res.initiator_wins;
+        let (name, target) = (self.cs[i].name.clone(), self.cs[t].name.clone());
+        self.log.push(format!(
+            "[GRAPPLE][{}] grapples {}: Athletics {} vs {:?} {} → {}",
+            name,
+            target,
+            res.initiator_total,
+            defense,
+            res.defender_total,
+            if won { "grappled" } else { "slips free" }
+        ));
+        self.record(rolls);
+        if won {
+            self.grab(i, t);
+        }
+    }
+
+    fn grab(&mut self, i: usize, t: usize) {
+        let source = ConditionSource::new(i, self.cs[i].name.clone(), "grapple");
+        if self.impose(t, ConditionKind::Grappled, source) != Applied::Immune {
+            self.cs[t].grappled_by = Some(i);
+        }
+    }
+
+    /// Apply an indefinite condition to combatant `t`, without stacking.
+    fn impose(&mut self, t: usize, kind: ConditionKind, source: ConditionSource) -> Applied {
+        let c = &mut self.cs[t];
+        let log = &mut self.log;
+        apply_condition(
+            &c.name,
+            &mut c.conditions,
+            &c.condition_immunities,
+            ActiveCondition::indefinite(kind).with_source(source),
+            |msg| log.push(msg),
+        )
+    }
+
+    fn shove(&mut self, i: usize, t: usize, effect: ShoveEffect) {
+        let (defense, res, rolls) = self.contest_athletics(i, t);
+        let won = res.initiator_wins;
+        let (name, target) = (self.cs[i].name.clone(), self.cs[t].name.clone());
+        let what = match effect {
+            ShoveEffect::Prone => "prone",
+            ShoveEffect::Push => "away",
+        };
+        self.log.push(format!(
+            "[SHOVE][{}] shoves {} {}: Athletics {} vs {:?} {} → {}",
+            name,
+            target,
+            what,
+            res.initiator_total,
+            defense,
+            res.defender_total,
+            if won { "success" } else { "holds its ground" }
+        ));
+        self.record(rolls);
+        if !won {
+            return;
+        }
+        match effect {
+            ShoveEffect::Prone => {
+                let source = ConditionSource::new(i, self.cs[i].name.clone(), "shove");
+                self.impose(t, ConditionKind::Prone, source);
+            }
+            ShoveEffect::Push => {
+                for j in 0..self.cs.len() {
+                    if self.cs[j].grappled_by == Some(t) {
+                        self.release(j, "pushed out of reach");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Use the action to escape: Athletics or Acrobatics against the grappler's Athletics,
+    /// or against the grab's escape DC when it has one.
+    fn escape(&mut self, i: usize) -> bool {
+        let Some(g) = self.cs[i].grappled_by else {
+            return false;
+        };
+        if let Some(dc) = self.cs[i].escape_dc(ConditionKind::Grappled) {
+            return self.escape_condition(i, ConditionKind::Grappled, dc);
+        }
+        let skill = grapple_defense(|s| self.cs[i].skill_mod(s));
+        let mine = self.check_contestant(i, skill, VantageBreakdown::default());
+        let theirs = self.check_contestant(g, Skill::Athletics, VantageBreakdown::default());
+        let (res, rolls) = self.contest(mine, theirs);
+        let me = &self.cs[i];
+        self.log.push(format!(
+            "[GRAPPLE][{}] tries to escape {}: {:?} {} vs Athletics {} → {}",
+            me.name,
+            self.cs[g].name,
+            skill,
+            res.initiator_total,
+            res.defender_total,
+            if res.initiator_wins {
+                "escapes"
+            } else {
+                "still grappled"
+            }
+        ));
+        self.record(rolls);
+        if res.initiator_wins {
+            self.release(i, "escapes");
+        }
+        res.initiator_wins
+    }
+
+    /// Use the action to end a condition with an escape DC: Athletics or Acrobatics,
+    /// whichever is better, against the DC.
+    fn escape_condition(&mut self, i: usize, kind: ConditionKind, dc: i32) -> bool {
+        let skill = grapple_defense(|s| self.cs[i].skill_mod(s));
+        let (check, pending) = self.check_contestant(i, skill, VantageBreakdown::default());
+        let roll = self.dice.d20(check.mode) as i32;
+        let total = roll + check.modifier;
+        let escaped = total >= dc;
+        self.log.push(format!(
+            "[COND][{}] tries to escape {:?}: {:?} {} vs DC {} → {}",
+            self.cs[i].name,
+            kind,
+            skill,
+            total,
+            dc,
+            if escaped { "escapes" } else { "still held" }
+        ));
+        self.record([rolled_check(pending, roll, Some(dc), Some(escaped))]);
+        if !escaped {
+            return false;
+        }
+        if kind == ConditionKind::Grappled {
+            self.release(i, "escapes");
+        } else {
+            let c = &mut self.cs[i];
+            c.conditions.retain(|k| k.kind != kind);
+            self.log
+                .push(format!("[COND][{}] is no longer {:?}", c.name, kind));
+        }
+        true
+    }
+
+    /// A grapple ends when the grappler can no longer act.
+    fn check_grapple(&mut self, i: usize) {
+        if let Some(g) = self.cs[i].grappled_by {
+            if !self.cs[g].is_standing() {
+                self.release(i, "grappler is down");
+            } else if let Some(kind) = self.cs[g].incapacitated() {
+                let why = format!("grappler is {}", format!("{:?}", kind).to_lowercase());
+                self.release(i, &why);
+            }
+        }
+    }
+
+    fn release(&mut self, i: usize, why: &str) {
+        let c = &mut self.cs[i];
+        c.grappled_by = None;
+        let before = c.conditions.len();
+        c.conditions.retain(|k| k.kind != ConditionKind::Grappled);
+        if c.conditions.len() < before {
+            self.log.push(format!(
+                "[COND][{}] is no longer Grappled ({})",
+                c.name, why
+            ));
+        }
+    }
+
+    /// Grapples don't outlast the fight.
+    fn end_grapples(&mut self) {
+        for c in &mut self.cs {
+            if c.grappled_by.take().is_some() {
+                c.conditions.retain(|k| k.kind != ConditionKind::Grappled);
+            }
+        }
+    }
+
+    /// Standing costs half the creature's movement; a grappled creature has none.
+    fn stand_up(&mut self, i: usize) {
+        let c = &mut self.cs[i];
+        if !c.has_condition(ConditionKind::Prone) || c.has_condition(ConditionKind::Grappled) {
+            return;
+        }
+        c.conditions.retain(|k| k.kind != ConditionKind::Prone);
+        self.log.push(format!("[COND][{}] stands up", c.name));
+    }
+
+    fn choose_target(&mut self, i: usize) -> Option<usize> {
+        let side = self.cs[i].side;
+        if side == Side::Enemies && self.opts.downed == DownedPolicy::Finish {
+            let downed =
+                (0..self.cs.len()).find(|&j| self.cs[j].side != side && self.cs[j].is_down());
+            if let Some(j) = downed {
+                self.log.push(format!(
+                    "[AI][{}] goes after the downed {}",
+                    self.cs[i].name, self.cs[j].name
+                ));
+                return Some(j);
+            }
+        }
+        let alive: Vec<usize> = (0..self.cs.len())
+            .filter(|&j| self.cs[j].side != side && self.cs[j].is_standing())
+            .collect();
+        if alive.is_empty() {
+            // Only a downed foe that may regenerate is left: keep hitting it.
+            return (0..self.cs.len()).find(|&j| {
+                self.cs[j].side != side && self.cs[j].is_down() && self.cs[j].regenerates()
+            });
+        }
+        let focus = if side == Side::Party {
+            self.opts.focus
+        } else {
+            Focus::First
+        };
+        match focus {
+            Focus::First => alive.first().copied(),
+            Focus::Lowest => alive
+                .iter()
+                .copied()
+                .min_by_key(|&j| (self.cs[j].health.hp, j)),
+            Focus::Random => {
+                let sides = alive.len().min(u8::MAX as usize) as u8;
+                let roll = self.dice.die(sides) as usize - 1;
+                Some(alive[roll.min(alive.len() - 1)])
+            }
+        }
+    }
+
+    fn attack(&mut self, i: usize, t: usize, profile: &AttackProfile) {
+        let side = self.cs[i].side;
+        let ally_ready = self.cs.iter().enumerate().any(|(j, c)| {
+            j != i && c.side == side && c.is_standing() && c.incapacitated().is_none()
+        });
+        let sunlight = self.opts.sunlight;
+        let tactics = self.opts.tactics;
+        let nonlethal = tactics.nonlethal && side == Side::Party && !profile.ranged;
+        let crit_rule = self.opts.crit_rule;
+        let mut to_hit = Breakdown::new("attack bonus", profile.to_hit);
+        if self.cs[i].style() == Some(FightingStyle::Archery) && profile.ranged && profile.weapon {
+            to_hit.push("Archery", 2);
+        }
+        let terms = self.effect_terms(i, Stat::Attack, "to-hit", to_hit.total());
+        to_hit.terms.extend(terms);
+        let mut target_saves = (profile.apply_condition.is_some()
+            || profile.drain.is_some()
+            || self.cs[t].has_trait(Trait::UndeadFortitude))
+        .then(|| self.save_profile(t));
+        let mut rolled_saves = Vec::new();
+        let log = &mut self.log;
+        let rolls = &mut self.rolls;
+        let dice = &mut *self.dice;
+        let (attacker, target) = pair_mut(&mut self.cs, i, t);
+
+        let mut vantage = VantageBreakdown::default();
+        vantage.add("attack setting", attacker.attack_vantage);
+        vantage.extend(vantage_sources_from_conditions(
+            &attacker.conditions,
+            &target.conditions,
+            profile.style(),
+        ));
+        vantage.extend(effects::vantage_sources(&attacker.effects, Stat::Attack));
+        if ally_ready && attacker.has_trait(Trait::PackTactics) {
+            vantage.add(Trait::PackTactics.name(), Vantage::Advantage);
+        }
+        if sunlight && attacker.has_trait(Trait::SunlightSensitivity) {
+            vantage.add(Trait::SunlightSensitivity.name(), Vantage::Disadvantage);
+        }
+        if attacker.hidden {
+            attacker.hidden = false;
+            vantage.add("attacking from hiding", Vantage::Advantage);
+            log.push(format!(
+                "[FEATURE][{}] attacks from hiding (advantage)",
+                attacker.name
+            ));
+        }
+        let mode: AdMode = vantage.net().into();
+        let style = attacker.style();
+
+        let base_ac = target.armor_class();
+        log_defense(log, &target.name, base_ac, target.cover);
+        let atk = crate::attack_with_crit_range(
+            dice,
+            mode,
+            to_hit.total(),
+            base_ac + target.cover.ac_bonus(),
+            attacker.crit_on,
+        );
+        log_attack(log, &format!("{} ({})", attacker.name, profile.name), &atk);
+        let mut explained = explain_attack(
+            log.len() - 1,
+            &attacker.name,
+            format!("{} vs {}", profile.name, target.name),
+            to_hit,
+            vantage,
+            &atk,
+            target.cover,
+        );
+        let melee = !profile.ranged;
+        let helpless = target
+            .helpless()
+            .filter(|_| atk.hit && !atk.is_crit && melee);
+        if let Some(why) = helpless {
+            log.push(format!(
+                "[ATTACK][{}] hits a {} target within 5 ft → CRIT!",
+                attacker.name, why
+            ));
+            explained.add_note(format!("{} target: critical hit", why));
+        }
+        let mut crit = atk.is_crit || helpless.is_some();
+        if crit && target.crit_immune {
+            crit = false;
+            log.push(format!(
+                "[DEF][{}] critical hit becomes a normal hit",
+                target.name
+            ));
+            explained.add_note("crit immune: normal hit");
+        }
+        rolls.push(explained);
+        if !atk.hit {
+            log.push(format!("[HP][{}] {} HP", target.name, target.health.hp));
+            return;
+        }
+        let crit = crit.then_some(crit_rule);
+
+        let great_weapon =
+            style == Some(FightingStyle::GreatWeaponFighting) && melee && profile.two_handed;
+        let rolled = roll_dice(dice, profile.dice, crit, great_weapon);
+        let mut damage = Breakdown::new(dice_label(profile.dice, crit), rolled);
+        damage.push(
+            profile
+                .ability
+                .map_or("damage bonus".to_string(), |a| format!("{:?}", a)),
+            profile.damage_mod,
+        );
+        if style == Some(FightingStyle::Dueling) && melee && !profile.two_handed {
+            damage.push("Dueling", 2);
+        }
+        let kit_level = attacker.kit.as_ref().map_or(0, |k| k.level);
+        if attacker.raging() && melee && profile.ability == Some(Ability::Str) {
+            damage.push("Rage", rage_damage_bonus(kit_level));
+        }
+        let mut weapon_total = damage.total();
+        let damage_line = log.len();
+        log_damage(
+            log,
+            &attacker.name,
+            profile.dice,
+            weapon_total - rolled,
+            crit,
+            weapon_total,
+            Some(profile.damage_type),
+        );
+        let terms = roll_terms(dice, &attacker.effects, Stat::Damage);
+        damage.terms.extend(terms.iter().cloned());
+        weapon_total = add_terms(
+            log,
+            &attacker.name,
+            Stat::Damage,
+            "damage",
+            weapon_total,
+            terms,
+        );
+
+        if attacker.has_trait(Trait::Brute) && melee && profile.weapon {
+            let die = DamageDice::new(1, profile.dice.sides);
+            let extra = roll_dice(dice, die, crit, false);
+            weapon_total += extra;
+            let label = format!("Brute {}", dice_label(die, crit));
+            log.push(format!(
+                "[FEATURE][{}] {} = {}",
+                attacker.name, label, extra
+            ));
+            damage.push(label, extra);
+        }
+
+        let sneak = attacker
+            .kit
+            .as_ref()
+            .map_or(0, |k| sneak_attack_dice(k.class, k.level));
+        let sneak_ok = mode == AdMode::Advantage || (ally_ready && mode != AdMode::Disadvantage);
+        let smite_on_crit = crit.is_some();
+        if sneak > 0
+            && !attacker.sneak_attack_used
+            && profile.weapon
+            && (profile.finesse || profile.ranged)
+            && sneak_ok
+        {
+            attacker.sneak_attack_used = true;
+            let sneak_dice = DamageDice::new(sneak, 6);
+            let extra = roll_dice(dice, sneak_dice, crit, false);
+            weapon_total += extra;
+            let label = format!("Sneak Attack {}", dice_label(sneak_dice, crit));
+            log.push(format!(
+                "[FEATURE][{}] {} = {}",
+                attacker.name, label, extra
+            ));
+            damage.push(label, extra);
+        }
+
+        let mut radiant = 0;
+        let can_smite = attacker
+            .kit
+            .as_ref()
+            .is_some_and(|k| has_divine_smite(k.class, k.level));
+        let wants_smite = match tactics.smite {
+            SmitePolicy::Never => false,
+            SmitePolicy::OnCrit => smite_on_crit,
+            SmitePolicy::Always => true,
+        };
+        if can_smite && melee && wants_smite {
+            let kit = attacker.kit.as_mut().expect("smite needs a kit");
+            if let Some(slot) = kit.resources.lowest_slot() {
+                kit.resources.spend(slot);
+                let slot_level = slot.spell_slot_level().unwrap_or(1);
+                let d8s = DamageDice::new((1 + slot_level).min(5), 8);
+                radiant = roll_dice(dice, d8s, crit, false);
+                let label = dice_label(d8s, crit);
+                log.push(format!(
+                    "[FEATURE][{}] Divine Smite (level {} slot) {} = {} [Radiant]",
+                    attacker.name, slot_level, label, radiant
+                ));
+                damage.push(format!("Divine Smite {}", label), radiant);
+            }
+        }
+
+        if crit == Some(CritRule::DoubleTotal) {
+            let before = weapon_total + radiant;
+            weapon_total = CritRule::DoubleTotal.total(weapon_total);
+            radiant = CritRule::DoubleTotal.total(radiant);
+            damage.push("critical: total doubled", weapon_total + radiant - before);
+            log.push(format!(
+                "[DMG][{}] crit: total doubled {} → {}",
+                attacker.name,
+                before,
+                weapon_total + radiant
+            ));
+        }
+
+        let resist = target.resistances_now();
+        // Divine Smite's radiant damage is magical whatever the weapon.
+        let smite = AttackSource {
+            magical: true,
+            ..profile.source
+        };
+        let mut dealt_parts = [0; 2];
+        for (n, (amount, dtype, source)) in [
+            (weapon_total, profile.damage_type, profile.source),
+            (radiant, DamageType::Radiant, smite),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let (after, why) = adjusted_damage(target, &resist, amount, dtype, Some(source));
+            if after != amount.max(0) {
+                log.push(format!(
+                    "[DEF][{}] {}: {} → {}",
+                    target.name, why, amount, after
+                ));
+            }
+            damage.push(why, after - amount);
+            dealt_parts[n] = after;
+        }
+        let [weapon_dealt, radiant_dealt] = dealt_parts;
+        let mut dealt = weapon_dealt + radiant_dealt;
+        for (dtype, amount) in [
+            (profile.damage_type, weapon_dealt),
+            (DamageType::Radiant, radiant_dealt),
+        ] {
+            if amount > 0 {
+                target.damage_taken.insert(dtype);
+            }
+        }
+        rolls.push(RollExplanation::new(
+            damage_line,
+            &attacker.name,
+            RollKind::Damage,
+            format!("{} damage to {}", profile.name, target.name),
+            damage,
+        ));
+        let save_line = log.len();
+        let radiant_hit = radiant_dealt > 0 || profile.damage_type == DamageType::Radiant;
+        if let Some(saves) = target_saves
+            .as_mut()
+            .filter(|_| !radiant_hit && crit.is_none())
+        {
+            dealt = undead_fortitude(
+                target,
+                dealt,
+                |ctx| roll_save(saves, dice, &target.actor, ctx, &mut rolled_saves),
+                log,
+            );
+        }
+        damage_combatant(target, dealt, crit.is_some(), nonlethal, log);
+
+        if let (Some(drain), Some(saves)) = (profile.drain, target_saves.as_mut()) {
+            if dealt > 0 && !target.is_dead() {
+                let ctx = SaveContext::new(drain.ability, drain.dc);
+                let outcome = roll_save(saves, dice, &target.actor, &ctx, &mut rolled_saves);
+                target.legendary_resistance = saves.legendary_resistance;
+                log.push(format!(
+                    "[SAVE][{}] makes a {:?} save DC {} vs {}: roll={} total={} → {}",
+                    target.name,
+                    drain.ability,
+                    drain.dc,
+                    profile.name,
+                    outcome.roll,
+                    outcome.total,
+                    if outcome.success { "SUCCESS" } else { "FAIL" }
+                ));
+                if !outcome.success {
+                    reduce_max_hp(&target.name, &mut target.health, dealt, |msg| log.push(msg));
+                }
+            }
+        }
+
+        if let (Some(spec), Some(saves)) = (profile.apply_condition.as_ref(), target_saves.as_mut())
+        {
+            let grabs = spec.kind == ConditionKind::Grappled;
+            let already_held = grabs && target.grappled_by.is_some();
+            if !target.is_dead() && !already_held {
+                let actor = &target.actor;
+                maybe_apply_on_hit_condition(
+                    &target.name,
+                    &mut target.conditions,
+                    &target.condition_immunities,
+                    spec,
+                    Some(ConditionSource::new(
+                        i,
+                        attacker.name.clone(),
+                        profile.name.clone(),
+                    )),
+                    |ctx| roll_save(saves, dice, actor, ctx, &mut rolled_saves),
+                    |msg| log.push(msg),
+                );
+                target.legendary_resistance = saves.legendary_resistance;
+                // A grab from a stat block (`apply_condition: grappled`) is held by the attacker.
+                if grabs && target.has_condition(ConditionKind::Grappled) {
+                    target.grappled_by = Some(i);
+                }
+            }
+        }
+        let name = self.cs[t].name.clone();
+        self.record_saves(save_line, &name, rolled_saves);
+    }
+
+    fn tick_rage(&mut self, i: usize) {
+        let c = &mut self.cs[i];
+        let down = !c.is_standing();
+        if let Some(kit) = c.kit.as_mut() {
+            if kit.rage_rounds > 0 {
+                kit.rage_rounds = if down { 0 } else { kit.rage_rounds - 1 };
+                if kit.rage_rounds == 0 {
+                    self.log.push(format!("[FEATURE][{}] Rage ends", c.name));
+                }
+            }
+        }
+    }
+}
+
+/// Apply damage: characters go through death-save rules, monsters die at 0 HP. A
+/// `crit` on a character at 0 HP counts as two failed death saves, and a `nonlethal`
+/// hit knocks out whatever it drops to 0 HP.
+fn damage_combatant(
+    c: &mut Combatant,
+    amount: i32,
+    crit: bool,
+    nonlethal: bool,
+    log: &mut Vec<String>,
+) {
+    if nonlethal && c.health.hp > 0 {
+        apply_nonlethal_damage(&c.name, &mut c.health, &mut c.conditions, amount, |msg| {
+            log.push(msg)
+        });
+        log.push(format!("[HP][{}] {} HP", c.name, c.health.hp));
+    } else if c.death_saves {
+        apply_damage(
+            &c.name,
+            &mut c.health,
+            &mut c.conditions,
+            amount,
+            crit,
+            |msg| log.push(msg),
+        );
+        log.push(format!("[HP][{}] {} HP", c.name, c.health.hp));
+    } else {
+        let (amount, soaked) = soak_damage(&mut c.health, amount);
+        let before = c.health.hp;
+        c.health.hp = (before - amount).max(0);
+        match soaked {
+            Some(note) => log.push(format!(
+                "[HP][{}] {} → {} ({})",
+                c.name, before, c.health.hp, note
+            )),
+            None => log.push(format!("[HP][{}] {} → {}", c.name, before, c.health.hp)),
+        }
+        if c.health.hp == 0 && c.regenerates() {
+            // A regenerating creature only dies if it starts a turn at 0 HP and can't heal.
+            if before > 0 {
+                c.health.state = LifeState::Unconscious { stable: true };
+                log.push(format!("[ENEMY] {} drops, but may regenerate", c.name));
+            }
+        } else if c.health.hp == 0 && (before > 0 || amount > 0) && !c.is_dead() {
+            // Any damage finishes a creature already down, such as one knocked out.
+            c.health.state = LifeState::Dead;
+            log.push(format!("[ENEMY] {} defeated", c.name));
+        }
+    }
+}
+
+/// Undead Fortitude: damage that would drop `target` to 0 HP leaves it at 1 on a Con
+/// save (DC 5 + the damage). Returns the damage to deal.
+fn undead_fortitude(
+    target: &Combatant,
+    dealt: i32,
+    save: impl FnOnce(&SaveContext) -> SaveOutcome,
+    log: &mut Vec<String>,
+) -> i32 {
+    let hp = target.health.hp;
+    if !target.has_trait(Trait::UndeadFortitude) || hp <= 0 || dealt < hp {
+        return dealt;
+    }
+    let dc = undead_fortitude_dc(dealt);
+    let outcome = save(&SaveContext::new(Ability::Con, dc));
+    log.push(format!(
+        "[SAVE][{}] makes a Con save DC {} vs Undead Fortitude: roll={} total={} → {}",
+        target.name,
+        dc,
+        outcome.roll,
+        outcome.total,
+        if outcome.success { "SUCCESS" } else { "FAIL" }
+    ));
+    if !outcome.success {
+        return dealt;
+    }
+    log.push(format!(
+        "[FEATURE][{}] Undead Fortitude: drops to 1 HP instead",
+        target.name
+    ));
+    hp - 1
+}
+
+/// `amount` of `dtype` damage to `target` after its defenses, and why it changed:
+/// immunity, resistance or vulnerability, with the qualifiers of any defense that
+/// depends on the attack. `source` is None for damage that isn't from an attack, which
+/// qualified defenses ignore.
+fn adjusted_damage(
+    target: &Combatant,
+    resist: &HashSet<DamageType>,
+    amount: i32,
+    dtype: DamageType,
+    source: Option<AttackSource>,
+) -> (i32, String) {
+    let mut resist = resist.clone();
+    let mut vuln = target.vulnerabilities.clone();
+    let mut immune = target.immunities.clone();
+    let mut qualifiers = Vec::new();
+    for q in &target.qualified_defenses {
+        if !source.is_some_and(|s| q.applies(dtype, s)) {
+            continue;
+        }
+        match q.defense {
+            Defense::Resistance => resist.insert(dtype),
+            Defense::Vulnerability => vuln.insert(dtype),
+            Defense::Immunity => immune.insert(dtype),
+        };
+        qualifiers.push(q.describe());
+    }
+    let after = crate::adjust_damage_by_type(amount.max(0), dtype, &resist, &vuln, &immune);
+    let mut why = if immune.contains(&dtype) {
+        format!("{:?} immunity", dtype)
+    } else if resist.contains(&dtype) && !vuln.contains(&dtype) {
+        format!("{:?} resistance", dtype)
+    } else if vuln.contains(&dtype) && !resist.contains(&dtype) {
+        format!("{:?} vulnerability", dtype)
+    } else {
+        return (after, "no negative damage".to_string());
+    };
+    if !qualifiers.is_empty() {
+        why = format!("{} ({})", why, qualifiers.join("; "));
+    }
+    (after, why)
+}
+
+/// Roll damage dice, as the crit rule says on a critical hit; Great Weapon Fighting
+/// rerolls 1s and 2s once.
+fn roll_dice(dice: &mut Dice, spec: DamageDice, crit: Option<CritRule>, reroll_low: bool) -> i32 {
+    let (n, mut sum) = match crit {
+        Some(CritRule::DoubleDice) => (spec.count.saturating_mul(2), 0),
+        Some(CritRule::MaxPlusRoll) => (spec.count, spec.count as i32 * spec.sides as i32),
+        Some(CritRule::DoubleTotal) | None => (spec.count, 0),
+    };
+    for _ in 0..n {
+        let mut r = dice.die(spec.sides);
+        if reroll_low && r <= 2 {
+            r = dice.die(spec.sides);
+        }
+        sum += r as i32;
+    }
+    sum
+}
+
+/// `base` plus the effect terms; logs `[EFFECT][X] attack: to-hit 5 + Bless 1d4 3 = 8`
+/// when there are any.
+fn add_terms(
+    log: &mut Vec<String>,
+    name: &str,
+    stat: Stat,
+    label: &str,
+    base: i32,
+    terms: Vec<Term>,
+) -> i32 {
+    if terms.is_empty() {
+        return base;
+    }
+    let mut breakdown = Breakdown::new(label, base);
+    breakdown.terms.extend(terms);
+    log.push(format!("[EFFECT][{}] {}: {}", name, stat, breakdown));
+    breakdown.total()
+}
+
+/// Roll a save and keep a copy for [`Combat::record_saves`].
+fn roll_save(
+    profile: &mut SaveProfile,
+    dice: &mut Dice,
+    actor: &Actor,
+    ctx: &SaveContext,
+    rolled: &mut Vec<(SaveContext, SaveOutcome)>,
+) -> SaveOutcome {
+    let outcome = profile.roll(dice, actor, ctx);
+    rolled.push((*ctx, outcome.clone()));
+    outcome
+}
+
+fn explain_save(
+    log_index: usize,
+    name: &str,
+    ctx: &SaveContext,
+    outcome: SaveOutcome,
+) -> RollExplanation {
+    let what = match ctx.against {
+        Some(kind) => format!("{:?} save vs {:?}", ctx.ability, kind),
+        None => format!("{:?} save", ctx.ability),
+    };
+    let mut save = RollExplanation::new(log_index, name, RollKind::Save, what, outcome.breakdown);
+    save.total = outcome.total;
+    save.vantage = outcome.vantage;
+    save.against = Some(ctx.dc);
+    save.success = Some(outcome.success);
+    save.note = outcome.note;
+    save
+}
+
+fn explain_attack(
+    log_index: usize,
+    name: &str,
+    what: String,
+    to_hit: Breakdown,
+    vantage: VantageBreakdown,
+    atk: &AttackResult,
+    cover: Cover,
+) -> RollExplanation {
+    let mut modifiers = Breakdown::new("d20", atk.roll);
+    modifiers.terms.extend(to_hit.terms);
+    let mut attack = RollExplanation::new(log_index, name, RollKind::Attack, what, modifiers);
+    attack.rolls = atk.raw_rolls.clone();
+    attack.vantage = vantage;
+    attack.against = Some(atk.ac);
+    attack.success = Some(atk.hit);
+    let mut notes = Vec::new();
+    if atk.nat20 {
+        notes.push("natural 20 hits".to_string());
+    } else if atk.nat1 {
+        notes.push("natural 1 misses".to_string());
+    }
+    if cover != Cover::None {
+        notes.push(format!("{:?} cover +{} AC", cover, cover.ac_bonus()));
+    }
+    attack.note = (!notes.is_empty()).then(|| notes.join(", "));
+    attack
+}
+
+/// A check explained by [`Combat::check_contestant`], completed with its d20.
+fn rolled_check(
+    mut pending: RollExplanation,
+    roll: i32,
+    against: Option<i32>,
+    success: Option<bool>,
+) -> RollExplanation {
+    pending.modifiers.terms.insert(0, Term::new("d20", roll));
+    pending.total = pending.modifiers.total();
+    pending.against = against;
+    pending.success = success;
+    pending
+}
+
+fn pair_mut<T>(v: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
+    assert_ne!(a, b, "a combatant cannot target itself");
+    if a < b {
+        let (left, right) = v.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = v.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+fn format_d20_sequence(raw: &[u8], kept: i32) -> String {
+    match raw {
+        [] => format!("d20=? (keep={})", kept),
+        [only] => format!("d20={} (keep={})", only, kept),
+        [first, second] => format!("d20={} vs d20={} (keep={})", first, second, kept),
+        _ => {
+            let joined = raw
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("d20s=[{}] (keep={})", joined, kept)
+        }
+    }
+}
+
+fn format_modifier(modifier: i32) -> String {
+    if modifier >= 0 {
+        format!("+{}", modifier)
+    } else {
+        format!("-{}", modifier.abs())
+    }
+}
+
+fn log_attack(logs: &mut Vec<String>, name: &str, atk: &AttackResult) {
+    let rolls = format_d20_sequence(&atk.raw_rolls, atk.roll);
+    let outcome = if atk.is_crit {
+        "CRIT!"
+    } else if atk.hit {
+        "HIT"
+    } else if atk.nat1 {
+        "MISS (NAT1)"
+    } else {
+        "MISS"
+    };
+    let mark = if atk.hit { "✔" } else { "✖" };
+    logs.push(format!(
+        "[ATTACK][{}] {} → {} to-hit={} vs AC={} {}",
+        name, rolls, outcome, atk.total, atk.ac, mark
+    ));
+}
+
+/// How damage dice read under a crit rule: `1d8`, `2×1d8`, `8+1d8`.
+fn dice_label(spec: DamageDice, crit: Option<CritRule>) -> String {
+    let dice = format!("{}d{}", spec.count, spec.sides);
+    match crit {
+        Some(CritRule::DoubleDice) => format!("2×{}", dice),
+        Some(CritRule::MaxPlusRoll) => {
+            format!("{}+{}", spec.count as i32 * spec.sides as i32, dice)
+        }
+        Some(CritRule::DoubleTotal) | None => dice,
+    }
+}
+
+fn log_damage(
+    logs: &mut Vec<String>,
+    name: &str,
+    dice: DamageDice,
+    modifier: i32,
+    crit: Option<CritRule>,
+    total: i32,
+    dtype: Option<DamageType>,
+) {
+    let dice_expr = match crit {
+        Some(CritRule::DoubleDice) => format!("2×({}d{})", dice.count, dice.sides),
+        _ => dice_label(dice, crit),
+    };
+    let prefix = if crit.is_some() { "crit: " } else { "" };
+    match dtype {
+        Some(dt) => logs.push(format!(
+            "[DMG][{}] {}rolled {} {} = {} [{:?}]",
+            name,
+            prefix,
+            dice_expr,
+            format_modifier(modifier),
+            total,
+            dt
+        )),
+        None => logs.push(format!(
+            "[DMG][{}] {}rolled {} {} = {}",
+            name,
+            prefix,
+            dice_expr,
+            format_modifier(modifier),
+            total
+        )),
+    }
+}
+
+fn log_defense(logs: &mut Vec<String>, name: &str, base_ac: i32, cover: Cover) {
+    let bonus = cover.ac_bonus();
+    logs.push(format!(
+        "[DEF][{}] AC {} + cover({:+}) = {}",
+        name,
+        base_ac,
+        bonus,
+        base_ac + bonus
+    ));
+}
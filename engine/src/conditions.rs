@@ -1,12 +1,53 @@
-use crate::{Ability, SavingThrow};
+use crate::explain::VantageBreakdown;
+use crate::features::RestKind;
+use crate::saves::{SaveContext, SaveOutcome};
+use crate::{DamageDice, DamageType, SavingThrow};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ConditionKind {
     Poisoned,
     Prone,
     Restrained,
+    /// Speed 0: can't stand up from Prone. Ends when the grappler is incapacitated or
+    /// the creature escapes.
+    Grappled,
+    /// Incapacitated: loses its turns, automatically fails Str and Dex saves, and
+    /// attacks against it have advantage.
+    Stunned,
+    /// As Stunned, and any hit from within 5 feet is a critical hit.
+    Paralyzed,
+}
+
+impl ConditionKind {
+    /// The creature can't take actions or reactions.
+    pub fn incapacitates(self) -> bool {
+        matches!(self, ConditionKind::Stunned | ConditionKind::Paralyzed)
+    }
+}
+
+impl FromStr for ConditionKind {
+    type Err = String;
+
+    /// Parse a condition name as written in JSON or on the command line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "poisoned" => Ok(ConditionKind::Poisoned),
+            "prone" => Ok(ConditionKind::Prone),
+            "restrained" => Ok(ConditionKind::Restrained),
+            "grappled" => Ok(ConditionKind::Grappled),
+            "stunned" => Ok(ConditionKind::Stunned),
+            "paralyzed" => Ok(ConditionKind::Paralyzed),
+            other => Err(format!(
+                "unknown condition '{}' (valid: poisoned, prone, restrained, grappled, stunned, paralyzed)",
+                other
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,15 +58,104 @@ pub enum DurationPhase {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct ConditionDuration {
     /// If Some, the condition expires automatically on the affected creature's next occurrence of this phase.
     pub until: Option<DurationPhase>,
     /// If true, the affected creature attempts a saving throw at the end of each of its turns to end the condition.
     #[serde(default)]
     pub save_ends_each_turn: bool,
+    /// Ends after this many of the affected creature's turns, counted at the end of each.
+    #[serde(default)]
+    pub rounds: Option<u32>,
+    /// As `rounds`, in minutes of 10 rounds (Hold Person: 1).
+    #[serde(default)]
+    pub minutes: Option<u32>,
+    /// Ends at this phase of the source's next turn ("until the end of your next turn").
+    #[serde(default)]
+    pub until_source: Option<DurationPhase>,
+    /// Lasts until a rest of this kind; a short rest ends it at either kind of rest.
+    #[serde(default)]
+    pub until_rest: Option<RestKind>,
+}
+
+pub const ROUNDS_PER_MINUTE: u32 = 10;
+
+impl ConditionDuration {
+    /// `rounds`, or `minutes` counted in rounds.
+    pub fn total_rounds(&self) -> Option<u32> {
+        self.rounds
+            .or(self.minutes.map(|m| m.saturating_mul(ROUNDS_PER_MINUTE)))
+    }
+}
+
+/// The counted parts of a duration as they run down on a creature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Countdown {
+    /// Turns of the affected creature left.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounds_left: Option<u32>,
+    /// Ends at this phase of the source's next turn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_end: Option<DurationPhase>,
+    /// Internal flag: the source's next turn has started.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub source_turn_started: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until_rest: Option<RestKind>,
+}
+
+impl Countdown {
+    pub fn from_duration(duration: &ConditionDuration) -> Self {
+        Self {
+            rounds_left: duration.total_rounds(),
+            source_end: duration.until_source,
+            source_turn_started: false,
+            until_rest: duration.until_rest,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Countdown::default()
+    }
+
+    /// The end of one of the affected creature's turns; true once no rounds are left.
+    pub fn tick(&mut self) -> bool {
+        match self.rounds_left.as_mut() {
+            Some(left) => {
+                *left = left.saturating_sub(1);
+                *left == 0
+            }
+            None => false,
+        }
+    }
+
+    /// A boundary of the source's turn; true when the duration ends there.
+    pub fn source_boundary(&mut self, boundary: TurnBoundary) -> bool {
+        match (self.source_end, boundary) {
+            (Some(DurationPhase::StartOfTurn), TurnBoundary::StartOfTurn) => true,
+            (Some(DurationPhase::EndOfTurn), TurnBoundary::StartOfTurn) => {
+                self.source_turn_started = true;
+                false
+            }
+            (Some(DurationPhase::EndOfTurn), TurnBoundary::EndOfTurn) => self.source_turn_started,
+            _ => false,
+        }
+    }
+
+    /// Whether a rest of this kind ends it. Counted durations are shorter than any rest.
+    pub fn ends_at_rest(&self, kind: RestKind) -> bool {
+        match self.until_rest {
+            Some(RestKind::Long) => kind == RestKind::Long,
+            Some(RestKind::Short) => true,
+            None => self.rounds_left.is_some() || self.source_end.is_some(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ConditionSpec {
     pub kind: ConditionKind,
     /// On application: if present, target makes this save to resist application.
@@ -33,6 +163,95 @@ pub struct ConditionSpec {
     /// How the condition lasts.
     #[serde(default)]
     pub duration: ConditionDuration,
+    /// Comes from a spell or other magic, so Magic Resistance applies to its saves.
+    #[serde(default)]
+    pub magical: bool,
+    /// The affected creature can use its action on an Athletics or Acrobatics check
+    /// against this DC to end the condition (a mimic's adhesive: 13).
+    #[serde(default)]
+    pub escape_dc: Option<i32>,
+    /// Damage or healing at a boundary of the affected creature's turns while it lasts.
+    #[serde(default)]
+    pub ongoing: Vec<TurnTrigger>,
+}
+
+/// Something that happens to a creature at the start or end of each of its turns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TurnTrigger {
+    /// Ongoing damage ("takes 1d6 fire damage at the start of each of its turns"),
+    /// adjusted by the creature's resistances.
+    Damage {
+        at: DurationPhase,
+        dice: DamageDice,
+        #[serde(default)]
+        bonus: i32,
+        damage_type: DamageType,
+    },
+    /// Regaining hit points while above 0 (a troll's Regeneration: 10). Skipped if the
+    /// creature took damage of a `suppressed_by` type since its last turn started.
+    Heal {
+        at: DurationPhase,
+        amount: i32,
+        #[serde(default)]
+        suppressed_by: Vec<DamageType>,
+    },
+}
+
+impl TurnTrigger {
+    pub fn at(&self) -> DurationPhase {
+        match self {
+            TurnTrigger::Damage { at, .. } | TurnTrigger::Heal { at, .. } => *at,
+        }
+    }
+
+    /// Whether it fires at this boundary.
+    pub fn fires_at(&self, boundary: TurnBoundary) -> bool {
+        matches!(
+            (self.at(), boundary),
+            (DurationPhase::StartOfTurn, TurnBoundary::StartOfTurn)
+                | (DurationPhase::EndOfTurn, TurnBoundary::EndOfTurn)
+        )
+    }
+}
+
+/// A trigger with the name the log gives it: the condition, effect or trait it
+/// belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedTrigger {
+    pub name: String,
+    pub trigger: TurnTrigger,
+}
+
+/// Who imposed a condition, and with what.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConditionSource {
+    /// Index of the combatant that applied it, so two creatures with the same name
+    /// stay apart.
+    #[serde(default)]
+    pub combatant: usize,
+    /// Its name, for the log.
+    pub creature: String,
+    /// The attack, action or effect it came from (`Bite`, `shove`).
+    pub effect: String,
+}
+
+impl ConditionSource {
+    pub fn new(combatant: usize, creature: impl Into<String>, effect: impl Into<String>) -> Self {
+        Self {
+            combatant,
+            creature: creature.into(),
+            effect: effect.into(),
+        }
+    }
+}
+
+/// `Goblin's Bite`.
+impl fmt::Display for ConditionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}'s {}", self.creature, self.effect)
+    }
 }
 
 /// A condition that is currently active on an actor.
@@ -46,6 +265,20 @@ pub struct ActiveCondition {
     pub end_save: Option<SavingThrow>,
     /// Internal flag so a one-turn duration expires exactly once.
     pub pending_one_turn: bool,
+    /// Applied by magic; kept for the saves that end it.
+    #[serde(default)]
+    pub magical: bool,
+    /// Who applied it; None for conditions a creature starts with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<ConditionSource>,
+    /// Rounds left, the source's turn it ends on, and the rest that ends it.
+    #[serde(default, skip_serializing_if = "Countdown::is_empty")]
+    pub countdown: Countdown,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escape_dc: Option<i32>,
+    /// Ongoing damage or healing while it lasts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ongoing: Vec<TurnTrigger>,
 }
 
 impl ActiveCondition {
@@ -56,12 +289,106 @@ impl ActiveCondition {
             end_phase: spec.duration.until,
             end_save: spec.save,
             pending_one_turn: spec.duration.until.is_some(),
+            magical: spec.magical,
+            source: None,
+            countdown: Countdown::from_duration(&spec.duration),
+            escape_dc: spec.escape_dc,
+            ongoing: spec.ongoing.clone(),
         }
     }
+
+    /// A condition with no end phase or save (e.g. one a creature starts combat with).
+    pub fn indefinite(kind: ConditionKind) -> Self {
+        Self {
+            kind,
+            save_ends_each_turn: false,
+            end_phase: None,
+            end_save: None,
+            pending_one_turn: false,
+            magical: false,
+            source: None,
+            countdown: Countdown::default(),
+            escape_dc: None,
+            ongoing: Vec::new(),
+        }
+    }
+
+    pub fn with_source(mut self, source: ConditionSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Lasts until removed: no end phase, save or countdown ends it (an escape DC may).
+    pub fn is_indefinite(&self) -> bool {
+        self.end_phase.is_none() && !self.save_ends_each_turn && self.countdown.is_empty()
+    }
 }
 
-/// Net vantage result for attack rolls.
+/// What applying a condition did.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applied {
+    Gained,
+    /// The creature already had it; the new duration and source replace the old.
+    Refreshed,
+    /// The creature already had it with no end; nothing changes.
+    Kept,
+    Immune,
+    /// The creature made its save against it.
+    Resisted,
+}
+
+/// Apply a condition without stacking: a creature either has a condition or it
+/// doesn't. Reapplying one it has refreshes the duration and source, unless the
+/// current one lasts indefinitely. Immune creatures are unaffected.
+pub fn apply_condition(
+    target_name: &str,
+    conds: &mut Vec<ActiveCondition>,
+    immunities: &HashSet<ConditionKind>,
+    new: ActiveCondition,
+    mut log: impl FnMut(String),
+) -> Applied {
+    let from = new
+        .source
+        .as_ref()
+        .map(|s| format!(" ({})", s))
+        .unwrap_or_default();
+    if immunities.contains(&new.kind) {
+        log(format!(
+            "[COND][{}] is immune to {:?}{}",
+            target_name, new.kind, from
+        ));
+        return Applied::Immune;
+    }
+    match conds.iter_mut().find(|c| c.kind == new.kind) {
+        Some(current) if current.is_indefinite() => {
+            log(format!(
+                "[COND][{}] is already {:?}{}",
+                target_name, new.kind, from
+            ));
+            Applied::Kept
+        }
+        Some(current) => {
+            log(format!(
+                "[COND][{}] {:?} refreshed{}",
+                target_name, new.kind, from
+            ));
+            *current = new;
+            Applied::Refreshed
+        }
+        None => {
+            log(format!(
+                "[COND][{}] gains {:?}{}",
+                target_name, new.kind, from
+            ));
+            conds.push(new);
+            Applied::Gained
+        }
+    }
+}
+
+/// Net vantage result for attack rolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Vantage {
     Normal,
     Advantage,
@@ -94,32 +421,52 @@ pub fn vantage_from_conditions(
     target_conds: &[ActiveCondition],
     style: AttackStyle,
 ) -> Vantage {
+    vantage_sources_from_conditions(attacker_conds, target_conds, style).net()
+}
+
+/// Each condition that gives the attack advantage or disadvantage, as a named source
+/// (`attacker Poisoned`, `target Prone (ranged)`).
+pub fn vantage_sources_from_conditions(
+    attacker_conds: &[ActiveCondition],
+    target_conds: &[ActiveCondition],
+    style: AttackStyle,
+) -> VantageBreakdown {
     use ConditionKind::*;
     use Vantage::*;
 
-    let mut net = Normal;
-
-    if attacker_conds
-        .iter()
-        .any(|c| matches!(c.kind, Poisoned | Restrained))
-    {
-        net = net.combine(Disadvantage);
+    let mut sources = VantageBreakdown::default();
+    for c in attacker_conds {
+        if matches!(c.kind, Poisoned | Restrained | Prone) {
+            sources.add(format!("attacker {:?}", c.kind), Disadvantage);
+        }
     }
 
     for c in target_conds {
         match c.kind {
-            Restrained => {
-                net = net.combine(Advantage);
+            Restrained | Stunned | Paralyzed => {
+                sources.add(format!("target {:?}", c.kind), Advantage)
             }
             Prone => match style {
-                AttackStyle::Melee => net = net.combine(Advantage),
-                AttackStyle::Ranged => net = net.combine(Disadvantage),
+                AttackStyle::Melee => sources.add("target Prone (melee)", Advantage),
+                AttackStyle::Ranged => sources.add("target Prone (ranged)", Disadvantage),
             },
-            Poisoned => {}
+            Poisoned | Grappled => {}
         }
     }
 
-    net
+    sources
+}
+
+/// Each condition that gives a creature's ability checks (skill checks and initiative
+/// included) advantage or disadvantage. Poisoned: disadvantage on every check.
+pub fn check_vantage_from_conditions(conds: &[ActiveCondition]) -> VantageBreakdown {
+    let mut sources = VantageBreakdown::default();
+    for c in conds {
+        if c.kind == ConditionKind::Poisoned {
+            sources.add("Poisoned", Vantage::Disadvantage);
+        }
+    }
+    sources
 }
 
 /// Lifecycle hooks to expire or allow saves at turn boundaries.
@@ -129,33 +476,54 @@ pub enum TurnBoundary {
     EndOfTurn,
 }
 
+/// Expire conditions and roll their end-of-turn saves. Returns the ongoing damage and
+/// healing due at this boundary, for the caller to resolve against the creature.
 pub fn process_turn_boundary(
     boundary: TurnBoundary,
     actor_name: &str,
     actor_conds: &mut Vec<ActiveCondition>,
-    mut saving_throw_fn: impl FnMut(Ability, i32) -> (i32, i32),
+    mut saving_throw_fn: impl FnMut(&SaveContext) -> SaveOutcome,
     mut log: impl FnMut(String),
-) {
+) -> Vec<NamedTrigger> {
     use TurnBoundary::*;
 
+    // Ongoing damage and healing fire before a save or expiry can end the condition.
+    let due = actor_conds
+        .iter()
+        .flat_map(|c| {
+            c.ongoing
+                .iter()
+                .filter(|t| t.fires_at(boundary))
+                .map(|t| NamedTrigger {
+                    name: format!("{:?}", c.kind),
+                    trigger: t.clone(),
+                })
+        })
+        .collect();
+
     if matches!(boundary, EndOfTurn) {
         let mut to_remove = vec![];
         for (idx, c) in actor_conds.iter().enumerate() {
             if c.save_ends_each_turn {
                 if let Some(SavingThrow { ability, dc }) = c.end_save {
-                    let (roll, total) = saving_throw_fn(ability, dc);
-                    let success = total >= dc;
+                    let outcome = saving_throw_fn(&SaveContext {
+                        ability,
+                        dc,
+                        against: Some(c.kind),
+                        magical: c.magical,
+                    });
                     log(format!(
-                        "[SAVE][{}] makes a {:?} save DC {} vs {:?}: roll={} total={} → {}",
+                        "[SAVE][{}] makes a {:?} save DC {} vs {:?}: roll={} total={} → {}{}",
                         actor_name,
                         ability,
                         dc,
                         c.kind,
-                        roll,
-                        total,
-                        if success { "SUCCESS" } else { "FAIL" }
+                        outcome.roll,
+                        outcome.total,
+                        if outcome.success { "SUCCESS" } else { "FAIL" },
+                        note_suffix(&outcome)
                     ));
-                    if success {
+                    if outcome.success {
                         to_remove.push(idx);
                     }
                 }
@@ -189,34 +557,110 @@ pub fn process_turn_boundary(
             actor_name, removed.kind, phase
         ));
     }
+
+    if boundary == EndOfTurn {
+        actor_conds.retain_mut(|c| {
+            if !c.countdown.tick() {
+                return true;
+            }
+            log(format!("[COND][{}] {:?} runs out", actor_name, c.kind));
+            false
+        });
+    }
+    due
 }
 
+/// A boundary of combatant `source`'s turn: end conditions it imposed "until the start
+/// (or end) of its next turn". Sources are matched by combatant index; `source_name`
+/// is only logged.
+pub fn process_source_boundary(
+    boundary: TurnBoundary,
+    source: usize,
+    source_name: &str,
+    holder_name: &str,
+    conds: &mut Vec<ActiveCondition>,
+    mut log: impl FnMut(String),
+) {
+    conds.retain_mut(|c| {
+        let from_source = c.source.as_ref().is_some_and(|s| s.combatant == source);
+        if !from_source || !c.countdown.source_boundary(boundary) {
+            return true;
+        }
+        log(format!(
+            "[COND][{}] {:?} ends at {}'s {:?}",
+            holder_name, c.kind, source_name, boundary
+        ));
+        false
+    });
+}
+
+/// Remove the conditions a rest outlasts: counted durations, and those lasting until
+/// a rest of this kind.
+pub fn end_conditions_at_rest(
+    name: &str,
+    conds: &mut Vec<ActiveCondition>,
+    kind: RestKind,
+    mut log: impl FnMut(String),
+) {
+    conds.retain(|c| {
+        if !c.countdown.ends_at_rest(kind) {
+            return true;
+        }
+        log(format!("[COND][{}] {:?} ends with the rest", name, c.kind));
+        false
+    });
+}
+
+/// Offer the target its save against an on-hit condition, then apply it if the save
+/// fails. Immune targets don't roll.
 pub fn maybe_apply_on_hit_condition(
     target_name: &str,
     target_conditions: &mut Vec<ActiveCondition>,
+    immunities: &HashSet<ConditionKind>,
     spec: &ConditionSpec,
-    mut saving_throw_fn: impl FnMut(Ability, i32) -> (i32, i32),
+    source: Option<ConditionSource>,
+    mut saving_throw_fn: impl FnMut(&SaveContext) -> SaveOutcome,
     mut log: impl FnMut(String),
-) {
+) -> Applied {
+    let mut active = ActiveCondition::from_spec_for_application(spec);
+    active.source = source;
+    if immunities.contains(&spec.kind) {
+        return apply_condition(target_name, target_conditions, immunities, active, log);
+    }
     if let Some(save) = spec.save {
-        let (roll, total) = saving_throw_fn(save.ability, save.dc);
-        let success = total >= save.dc;
+        let outcome = saving_throw_fn(&SaveContext {
+            ability: save.ability,
+            dc: save.dc,
+            against: Some(spec.kind),
+            magical: spec.magical,
+        });
         log(format!(
-            "[SAVE][{}] resists {:?}? {:?} save DC {}: roll={} total={} → {}",
+            "[SAVE][{}] resists {:?}? {:?} save DC {}: roll={} total={} → {}{}",
             target_name,
             spec.kind,
             save.ability,
             save.dc,
-            roll,
-            total,
-            if success { "RESISTED" } else { "FAILED" }
+            outcome.roll,
+            outcome.total,
+            if outcome.success {
+                "RESISTED"
+            } else {
+                "FAILED"
+            },
+            note_suffix(&outcome)
         ));
-        if success {
-            return;
+        if outcome.success {
+            return Applied::Resisted;
         }
     }
 
-    let active = ActiveCondition::from_spec_for_application(spec);
-    target_conditions.push(active);
-    log(format!("[COND][{}] gains {:?}", target_name, spec.kind));
+    apply_condition(target_name, target_conditions, immunities, active, log)
+}
+
+fn note_suffix(outcome: &SaveOutcome) -> String {
+    outcome
+        .note
+        .as_ref()
+        .map(|n| format!(" ({})", n))
+        .unwrap_or_default()
 }
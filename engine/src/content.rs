@@ -1,4 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::Side;
+use crate::conditions::{ConditionKind, ConditionSpec, DurationPhase, NamedTrigger, TurnTrigger};
+use crate::legendary::{LairAction, LegendaryActions};
+use crate::traits::Trait;
+use crate::{
+    Ability, AbilityScores, Cover, DamageDice, DamageType, QualifiedDefense, SavingThrow, Skill,
+};
 
 pub fn builtin_targets() -> HashMap<&'static str, &'static str> {
     HashMap::from([(
@@ -11,9 +22,285 @@ pub fn builtin_weapons() -> HashMap<&'static str, &'static str> {
     HashMap::from([("basic", include_str!("../content/weapons/basic.json"))])
 }
 
+pub fn builtin_items() -> HashMap<&'static str, &'static str> {
+    HashMap::from([("basic", include_str!("../content/items/basic.json"))])
+}
+
+pub fn builtin_effects() -> HashMap<&'static str, &'static str> {
+    HashMap::from([("basic", include_str!("../content/effects/basic.json"))])
+}
+
 pub fn builtin_encounters() -> HashMap<&'static str, &'static str> {
+    HashMap::from([
+        (
+            "goblin_ambush",
+            include_str!("../content/encounters/goblin_ambush.json"),
+        ),
+        (
+            "goblin_den",
+            include_str!("../content/encounters/goblin_den.json"),
+        ),
+    ])
+}
+
+pub fn builtin_campaigns() -> HashMap<&'static str, &'static str> {
     HashMap::from([(
-        "goblin_ambush",
-        include_str!("../content/encounters/goblin_ambush.json"),
+        "goblin_warrens",
+        include_str!("../content/campaigns/goblin_warrens.json"),
     )])
 }
+
+/* ---------------- typed content ---------------- */
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetAttack {
+    pub name: String,
+    pub to_hit: i32,
+    pub dice: DamageDice,
+    #[serde(default)]
+    pub damage_type: Option<DamageType>,
+    #[serde(default)]
+    pub ranged: bool,
+    /// Counts as magical against resistance to nonmagical attacks.
+    #[serde(default)]
+    pub magical: bool,
+    #[serde(default)]
+    pub apply_condition: Option<ConditionSpec>,
+    /// Unless the target makes this save, its hit point maximum drops by the damage
+    /// dealt until a long rest (a wraith's Life Drain).
+    #[serde(default)]
+    pub drain: Option<SavingThrow>,
+}
+
+/// A monster/NPC stat block as stored in `content/targets/*.json` (and inline in encounters).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetData {
+    pub name: String,
+    pub ac: i32,
+    pub hp: i32,
+    #[serde(default)]
+    pub dex_mod: i32,
+    #[serde(default)]
+    pub abilities: Option<AbilityScores>,
+    #[serde(default)]
+    pub attacks: Vec<TargetAttack>,
+    /// Total skill bonuses from the stat block (goblin: `{ "stealth": 6 }`); other
+    /// skills use the ability modifier.
+    #[serde(default)]
+    pub skills: HashMap<Skill, i32>,
+    #[serde(default)]
+    pub resistances: HashSet<DamageType>,
+    #[serde(default)]
+    pub vulnerabilities: HashSet<DamageType>,
+    #[serde(default)]
+    pub immunities: HashSet<DamageType>,
+    /// Defenses that depend on the attack (werewolf: immune to nonmagical weapons that
+    /// aren't silvered).
+    #[serde(default)]
+    pub qualified_defenses: Vec<QualifiedDefense>,
+    /// Conditions that can't be applied to it (skeletons: Poisoned).
+    #[serde(default)]
+    pub condition_immunities: HashSet<ConditionKind>,
+    #[serde(default)]
+    pub conditions: Vec<ConditionKind>,
+    #[serde(default)]
+    pub cover: Cover,
+    /// Advantage on saves against spells and other magical effects.
+    #[serde(default)]
+    pub magic_resistance: bool,
+    /// Legendary Resistance uses per day: a failed save can succeed instead.
+    #[serde(default)]
+    pub legendary_resistance: u8,
+    /// Critical hits against it become normal hits (adamantine armor).
+    #[serde(default)]
+    pub crit_immune: bool,
+    /// Rolls death saves at 0 HP like a character instead of dying (important NPCs).
+    #[serde(default)]
+    pub death_saves: bool,
+    /// Damage threshold (objects, vehicles): hits dealing less damage deal none.
+    #[serde(default)]
+    pub damage_threshold: i32,
+    /// Hit points regained at the start of each of its turns (troll: 10, stopped by
+    /// acid or fire).
+    #[serde(default)]
+    pub regeneration: Option<Regeneration>,
+    /// Monster traits such as Pack Tactics and Nimble Escape.
+    #[serde(default)]
+    pub traits: HashSet<Trait>,
+    /// Actions taken at the end of other creatures' turns.
+    #[serde(default)]
+    pub legendary_actions: Option<LegendaryActions>,
+    /// Actions taken on initiative count 20; a stat block with them fights in its lair.
+    #[serde(default)]
+    pub lair_actions: Vec<LairAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Regeneration {
+    pub amount: i32,
+    /// Damage types that stop it at the start of the creature's next turn.
+    #[serde(default)]
+    pub suppressed_by: Vec<DamageType>,
+}
+
+impl Regeneration {
+    /// As a start-of-turn trigger named for the log.
+    pub fn trigger(&self) -> NamedTrigger {
+        NamedTrigger {
+            name: "Regeneration".to_string(),
+            trigger: TurnTrigger::Heal {
+                at: DurationPhase::StartOfTurn,
+                amount: self.amount,
+                suppressed_by: self.suppressed_by.clone(),
+            },
+        }
+    }
+}
+
+impl TargetData {
+    pub fn dexterity_mod(&self) -> i32 {
+        self.ability_mod(Ability::Dex)
+    }
+
+    /// Ability modifier from full scores if present; otherwise only `dex_mod` is known.
+    pub fn ability_mod(&self, ability: Ability) -> i32 {
+        if let Some(scores) = &self.abilities {
+            scores.mod_of(ability)
+        } else if ability == Ability::Dex {
+            self.dex_mod
+        } else {
+            0
+        }
+    }
+}
+
+/// How the actor picks among several living enemies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Focus {
+    #[default]
+    First,
+    Lowest,
+    Random,
+}
+
+/// Whether enemies attack party members who are down at 0 HP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownedPolicy {
+    /// Only fight creatures still standing.
+    #[default]
+    Ignore,
+    /// Attack a downed foe before any standing one, to finish it off.
+    Finish,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncounterData {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub focus: Focus,
+    /// Whether the enemies attack party members who are down.
+    #[serde(default)]
+    pub downed: DownedPolicy,
+    /// The side lying in wait; its Stealth against the other side's passive Perception
+    /// decides who is surprised.
+    #[serde(default)]
+    pub ambush: Option<Side>,
+    /// Fought in sunlight (Sunlight Sensitivity).
+    #[serde(default)]
+    pub sunlight: bool,
+    pub enemies: Vec<TargetData>,
+}
+
+/* ---------------- strict parsing ---------------- */
+
+/// A content/config document that failed to deserialize, with the offending field.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid {what} at `{field}`: {message}")]
+pub struct ContentError {
+    /// What was being parsed ("target", "encounter", "weapons", "duel config", ...).
+    pub what: &'static str,
+    /// Dotted path to the field, e.g. `attacks[0].damage_type` (`.` for the root).
+    pub field: String,
+    /// The underlying serde message (includes line/column).
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Deserialize JSON strictly, reporting which field was wrong on failure.
+/// A leading UTF-8 BOM is tolerated.
+pub fn parse_content<T: DeserializeOwned>(
+    what: &'static str,
+    text: &str,
+) -> Result<T, ContentError> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut de = serde_json::Deserializer::from_str(text);
+    let value = serde_path_to_error::deserialize(&mut de).map_err(|e| {
+        let field = e.path().to_string();
+        let inner = e.into_inner();
+        ContentError {
+            what,
+            field,
+            line: inner.line(),
+            column: inner.column(),
+            message: inner.to_string(),
+        }
+    })?;
+    de.end().map_err(|e| ContentError {
+        what,
+        field: ".".to_string(),
+        line: e.line(),
+        column: e.column(),
+        message: e.to_string(),
+    })?;
+    Ok(value)
+}
+
+/// As `parse_content`, from JSON already parsed into a value. There is no text to
+/// point into, so `line` and `column` are 0 and only `field` locates the error.
+pub fn parse_content_value<T: DeserializeOwned>(
+    what: &'static str,
+    value: serde_json::Value,
+) -> Result<T, ContentError> {
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let field = e.path().to_string();
+        ContentError {
+            what,
+            field,
+            line: 0,
+            column: 0,
+            message: e.into_inner().to_string(),
+        }
+    })
+}
+
+pub fn parse_target(text: &str) -> Result<TargetData, ContentError> {
+    parse_content("target", text)
+}
+
+pub fn parse_encounter(text: &str) -> Result<EncounterData, ContentError> {
+    parse_content("encounter", text)
+}
+
+pub fn parse_weapons(text: &str) -> Result<Vec<crate::Weapon>, ContentError> {
+    parse_content("weapons", text)
+}
+
+pub fn parse_campaign(text: &str) -> Result<crate::campaign::Campaign, ContentError> {
+    parse_content("campaign", text)
+}
+
+pub fn parse_items(text: &str) -> Result<Vec<crate::items::ItemDef>, ContentError> {
+    parse_content("items", text)
+}
+
+pub fn parse_effects(text: &str) -> Result<Vec<crate::effects::EffectDef>, ContentError> {
+    parse_content("effects", text)
+}
@@ -0,0 +1,334 @@
+//! Active effects: buffs and debuffs from spells, items and features.
+//!
+//! Effect definitions live in content (`content/effects/*.json`). A combatant carries
+//! the effects currently on it; each adds typed modifiers to its rolls, AC and
+//! resistances until its duration runs out or the next rest.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::conditions::{
+    ConditionDuration, Countdown, DurationPhase, NamedTrigger, TurnBoundary, TurnTrigger, Vantage,
+};
+use crate::error::{EngineError, Result};
+pub use crate::explain::{Breakdown, Term};
+use crate::explain::{DiceTerm, VantageBreakdown};
+use crate::features::RestKind;
+use crate::saves::{SaveContext, SaveOutcome};
+use crate::{DamageDice, DamageType, Dice, SavingThrow};
+
+/// What a modifier applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    Attack,
+    Damage,
+    Save,
+    Check,
+    Ac,
+    /// Feet of movement. Recorded for front-ends; the combat loop has no positions.
+    Speed,
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stat::Attack => "attack",
+            Stat::Damage => "damage",
+            Stat::Save => "save",
+            Stat::Check => "check",
+            Stat::Ac => "AC",
+            Stat::Speed => "speed",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Modifier {
+    /// A flat bonus, or a penalty when negative (Shield of Faith: +2 AC).
+    Bonus {
+        to: Stat,
+        value: i32,
+    },
+    /// Rolled each time and added, or subtracted (Bless: +1d4, Bane: −1d4).
+    Dice {
+        to: Stat,
+        dice: DamageDice,
+        #[serde(default)]
+        subtract: bool,
+    },
+    Advantage {
+        on: Stat,
+    },
+    Disadvantage {
+        on: Stat,
+    },
+    Resistance {
+        damage_type: DamageType,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EffectDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    /// Same rules as conditions: expire at a turn phase and/or end on a save each turn.
+    #[serde(default)]
+    pub duration: ConditionDuration,
+    /// The save rolled at the end of each turn when `duration.save_ends_each_turn`.
+    #[serde(default)]
+    pub end_save: Option<SavingThrow>,
+    /// A spell or other magic, so Magic Resistance applies to its saves.
+    #[serde(default)]
+    pub magical: bool,
+    /// Damage or healing at the start or end of each of the creature's turns.
+    #[serde(default)]
+    pub triggers: Vec<TurnTrigger>,
+}
+
+/// An effect on a creature, with where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveEffect {
+    pub def: EffectDef,
+    /// Who or what applied it (a caster, an item).
+    pub source: String,
+    /// Internal flag so a one-turn duration expires exactly once.
+    pub pending_one_turn: bool,
+    /// Rounds left and the source- and rest-based parts of the duration.
+    pub countdown: Countdown,
+}
+
+impl ActiveEffect {
+    pub fn new(def: EffectDef, source: impl Into<String>) -> Self {
+        let pending_one_turn = def.duration.until.is_some();
+        let countdown = Countdown::from_duration(&def.duration);
+        Self {
+            def,
+            source: source.into(),
+            pending_one_turn,
+            countdown,
+        }
+    }
+
+    /// Whether a rest of this kind ends it. Effects without a rest in their duration
+    /// last an hour at most, so any rest outlasts them.
+    pub fn ends_at_rest(&self, kind: RestKind) -> bool {
+        self.countdown.until_rest != Some(RestKind::Long) || kind == RestKind::Long
+    }
+}
+
+/// Roll every bonus and dice modifier to `stat`, one term per modifier.
+pub fn roll_terms(dice: &mut Dice, effects: &[ActiveEffect], stat: Stat) -> Vec<Term> {
+    let mut terms = Vec::new();
+    for e in effects {
+        for m in &e.def.modifiers {
+            match *m {
+                Modifier::Bonus { to, value } if to == stat => {
+                    terms.push(Term::new(e.def.name.clone(), value))
+                }
+                Modifier::Dice {
+                    to,
+                    dice: d,
+                    subtract,
+                } if to == stat => terms.push(
+                    DiceTerm {
+                        subtract,
+                        ..DiceTerm::new(e.def.name.clone(), d)
+                    }
+                    .roll(dice),
+                ),
+                _ => {}
+            }
+        }
+    }
+    terms
+}
+
+/// Flat bonuses to `stat` (AC, speed); dice modifiers are ignored.
+pub fn flat_bonus(effects: &[ActiveEffect], stat: Stat) -> i32 {
+    flat_terms(effects, stat).iter().map(|t| t.value).sum()
+}
+
+/// Flat bonuses to `stat`, one term per effect.
+pub fn flat_terms(effects: &[ActiveEffect], stat: Stat) -> Vec<Term> {
+    let mut terms = Vec::new();
+    for e in effects {
+        for m in &e.def.modifiers {
+            if let Modifier::Bonus { to, value } = *m {
+                if to == stat {
+                    terms.push(Term::new(e.def.name.clone(), value));
+                }
+            }
+        }
+    }
+    terms
+}
+
+/// Dice added to and subtracted from `stat`, for rolls made elsewhere (saves).
+pub fn dice_for(effects: &[ActiveEffect], stat: Stat) -> Vec<DiceTerm> {
+    let mut terms = Vec::new();
+    for e in effects {
+        for m in &e.def.modifiers {
+            if let Modifier::Dice { to, dice, subtract } = *m {
+                if to == stat {
+                    terms.push(DiceTerm {
+                        subtract,
+                        ..DiceTerm::new(e.def.name.clone(), dice)
+                    });
+                }
+            }
+        }
+    }
+    terms
+}
+
+/// Net advantage or disadvantage the effects give on `stat`.
+pub fn vantage(effects: &[ActiveEffect], stat: Stat) -> Vantage {
+    vantage_sources(effects, stat).net()
+}
+
+/// Each effect giving advantage or disadvantage on `stat`, by name.
+pub fn vantage_sources(effects: &[ActiveEffect], stat: Stat) -> VantageBreakdown {
+    let mut sources = VantageBreakdown::default();
+    for e in effects {
+        for m in &e.def.modifiers {
+            match *m {
+                Modifier::Advantage { on } if on == stat => {
+                    sources.add(e.def.name.clone(), Vantage::Advantage)
+                }
+                Modifier::Disadvantage { on } if on == stat => {
+                    sources.add(e.def.name.clone(), Vantage::Disadvantage)
+                }
+                _ => {}
+            }
+        }
+    }
+    sources
+}
+
+pub fn resistances(effects: &[ActiveEffect]) -> HashSet<DamageType> {
+    modifiers(effects)
+        .filter_map(|m| match *m {
+            Modifier::Resistance { damage_type } => Some(damage_type),
+            _ => None,
+        })
+        .collect()
+}
+
+fn modifiers(effects: &[ActiveEffect]) -> impl Iterator<Item = &Modifier> {
+    effects.iter().flat_map(|e| e.def.modifiers.iter())
+}
+
+/// Expire effects and roll their end-of-turn saves, as `process_turn_boundary` does
+/// for conditions, and return the triggers due at this boundary.
+pub fn process_effect_boundary(
+    boundary: TurnBoundary,
+    actor_name: &str,
+    effects: &mut Vec<ActiveEffect>,
+    mut saving_throw_fn: impl FnMut(&SaveContext) -> SaveOutcome,
+    mut log: impl FnMut(String),
+) -> Vec<NamedTrigger> {
+    let due = effects
+        .iter()
+        .flat_map(|e| {
+            e.def
+                .triggers
+                .iter()
+                .filter(|t| t.fires_at(boundary))
+                .map(|t| NamedTrigger {
+                    name: e.def.name.clone(),
+                    trigger: t.clone(),
+                })
+        })
+        .collect();
+    let phase = match boundary {
+        TurnBoundary::StartOfTurn => DurationPhase::StartOfTurn,
+        TurnBoundary::EndOfTurn => DurationPhase::EndOfTurn,
+    };
+    effects.retain_mut(|e| {
+        if boundary == TurnBoundary::EndOfTurn && e.def.duration.save_ends_each_turn {
+            if let Some(SavingThrow { ability, dc }) = e.def.end_save {
+                let outcome = saving_throw_fn(&SaveContext {
+                    magical: e.def.magical,
+                    ..SaveContext::new(ability, dc)
+                });
+                log(format!(
+                    "[SAVE][{}] makes a {:?} save DC {} vs {}: roll={} total={} → {}",
+                    actor_name,
+                    ability,
+                    dc,
+                    e.def.name,
+                    outcome.roll,
+                    outcome.total,
+                    if outcome.success { "SUCCESS" } else { "FAIL" }
+                ));
+                if outcome.success {
+                    log(format!("[EFFECT][{}] {} ends", actor_name, e.def.name));
+                    return false;
+                }
+            }
+        }
+        if e.pending_one_turn && e.def.duration.until == Some(phase) {
+            log(format!(
+                "[EFFECT][{}] {} ends at {:?}",
+                actor_name, e.def.name, phase
+            ));
+            return false;
+        }
+        if boundary == TurnBoundary::EndOfTurn && e.countdown.tick() {
+            log(format!("[EFFECT][{}] {} runs out", actor_name, e.def.name));
+            return false;
+        }
+        true
+    });
+    due
+}
+
+/// A boundary of `source_name`'s turn: end effects it applied "until the start (or
+/// end) of its next turn", as `process_source_boundary` does for conditions.
+pub fn process_effect_source_boundary(
+    boundary: TurnBoundary,
+    source_name: &str,
+    holder_name: &str,
+    effects: &mut Vec<ActiveEffect>,
+    mut log: impl FnMut(String),
+) {
+    effects.retain_mut(|e| {
+        if e.source != source_name || !e.countdown.source_boundary(boundary) {
+            return true;
+        }
+        log(format!(
+            "[EFFECT][{}] {} ends at {}'s {:?}",
+            holder_name, e.def.name, source_name, boundary
+        ));
+        false
+    });
+}
+
+pub fn find_effect<'a>(catalog: &'a [EffectDef], id: &str) -> Option<&'a EffectDef> {
+    catalog.iter().find(|d| d.id.eq_ignore_ascii_case(id))
+}
+
+/// Look up effect ids in the catalog; unknown ids are an error. The same effect
+/// doesn't stack with itself, so a repeated id is kept once.
+pub fn resolve_effects(
+    ids: &[String],
+    catalog: &[EffectDef],
+    source: &str,
+) -> Result<Vec<ActiveEffect>> {
+    let mut effects: Vec<ActiveEffect> = Vec::new();
+    for id in ids {
+        let def = find_effect(catalog, id)
+            .ok_or_else(|| EngineError::not_found("effect", id.as_str(), "not in effect list"))?;
+        if !effects.iter().any(|e| e.def.id == def.id) {
+            effects.push(ActiveEffect::new(def.clone(), source));
+        }
+    }
+    Ok(effects)
+}
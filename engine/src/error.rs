@@ -0,0 +1,48 @@
+use crate::content::ContentError;
+
+/// Errors returned by the public engine API. Each variant maps to a stable
+/// machine-readable [`code`](EngineError::code) so front-ends can branch on the kind.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EngineError {
+    /// A built-in id, file path or named item (e.g. a weapon) could not be found.
+    #[error("{what} not found: {reference} ({reason})")]
+    ContentNotFound {
+        what: &'static str,
+        reference: String,
+        reason: String,
+    },
+    /// A content or config document failed to deserialize; carries field and line/column.
+    #[error(transparent)]
+    Parse(#[from] ContentError),
+    /// The request was well-formed JSON but cannot be run as given.
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
+    /// The request exceeds a hard limit (e.g. too many simulation samples).
+    #[error("simulation limit: {0}")]
+    SimulationLimit(String),
+}
+
+impl EngineError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            EngineError::ContentNotFound { .. } => "content_not_found",
+            EngineError::Parse(_) => "parse_error",
+            EngineError::InvalidConfig(_) => "invalid_config",
+            EngineError::SimulationLimit(_) => "simulation_limit",
+        }
+    }
+
+    pub(crate) fn not_found(
+        what: &'static str,
+        reference: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        EngineError::ContentNotFound {
+            what,
+            reference: reference.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, EngineError>;
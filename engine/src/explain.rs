@@ -0,0 +1,244 @@
+//! Why a roll came out the way it did: every modifier with its source, and every
+//! advantage and disadvantage source with how they cancelled. Combat records one
+//! [`RollExplanation`] per attack, damage roll, save and check so a front-end can show
+//! it next to the log line.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::conditions::Vantage;
+use crate::{DamageDice, Dice};
+
+/// One labelled contribution to a total.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Term {
+    pub label: String,
+    pub value: i32,
+}
+
+impl Term {
+    pub fn new(label: impl Into<String>, value: i32) -> Self {
+        Self {
+            label: label.into(),
+            value,
+        }
+    }
+}
+
+/// How a total was reached, term by term: `to-hit 5 + Bless 1d4 3 = 8`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Breakdown {
+    pub terms: Vec<Term>,
+}
+
+impl Breakdown {
+    pub fn new(label: impl Into<String>, value: i32) -> Self {
+        Self {
+            terms: vec![Term::new(label, value)],
+        }
+    }
+
+    /// Add a term unless it is zero.
+    pub fn push(&mut self, label: impl Into<String>, value: i32) {
+        if value != 0 {
+            self.terms.push(Term::new(label, value));
+        }
+    }
+
+    pub fn total(&self) -> i32 {
+        self.terms.iter().map(|t| t.value).sum()
+    }
+}
+
+impl fmt::Display for Breakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, t) in self.terms.iter().enumerate() {
+            match (i, t.value < 0) {
+                (0, _) => write!(f, "{} {}", t.label, t.value)?,
+                (_, false) => write!(f, " + {} {}", t.label, t.value)?,
+                (_, true) => write!(f, " − {} {}", t.label, -t.value)?,
+            }
+        }
+        write!(f, " = {}", self.total())
+    }
+}
+
+/// Dice rolled and added to (or subtracted from) a roll, with where they come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceTerm {
+    pub label: String,
+    pub dice: DamageDice,
+    pub subtract: bool,
+}
+
+impl DiceTerm {
+    pub fn new(label: impl Into<String>, dice: DamageDice) -> Self {
+        Self {
+            label: label.into(),
+            dice,
+            subtract: false,
+        }
+    }
+
+    /// Roll the dice: `Bless 1d4 3`, negative when subtracted.
+    pub fn roll(&self, dice: &mut Dice) -> Term {
+        let rolled = self.dice.roll_total(dice, false);
+        Term::new(
+            format!("{} {}d{}", self.label, self.dice.count, self.dice.sides),
+            if self.subtract { -rolled } else { rolled },
+        )
+    }
+}
+
+/// One reason a roll has advantage or disadvantage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VantageSource {
+    pub source: String,
+    pub vantage: Vantage,
+}
+
+/// Every advantage and disadvantage source on a roll. Any advantage and any
+/// disadvantage cancel, however many of each there are.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VantageBreakdown {
+    pub sources: Vec<VantageSource>,
+}
+
+impl VantageBreakdown {
+    /// Record a source; `Normal` adds nothing.
+    pub fn add(&mut self, source: impl Into<String>, vantage: Vantage) {
+        if vantage != Vantage::Normal {
+            self.sources.push(VantageSource {
+                source: source.into(),
+                vantage,
+            });
+        }
+    }
+
+    pub fn extend(&mut self, other: VantageBreakdown) {
+        self.sources.extend(other.sources);
+    }
+
+    pub fn net(&self) -> Vantage {
+        let has = |v| self.sources.iter().any(|s| s.vantage == v);
+        match (has(Vantage::Advantage), has(Vantage::Disadvantage)) {
+            (true, false) => Vantage::Advantage,
+            (false, true) => Vantage::Disadvantage,
+            _ => Vantage::Normal,
+        }
+    }
+
+    fn list(&self, vantage: Vantage) -> Option<String> {
+        let names: Vec<&str> = self
+            .sources
+            .iter()
+            .filter(|s| s.vantage == vantage)
+            .map(|s| s.source.as_str())
+            .collect();
+        (!names.is_empty()).then(|| names.join(", "))
+    }
+}
+
+/// `advantage (hidden)`, or `advantage (hidden) and disadvantage (attacker Prone) cancel`.
+impl fmt::Display for VantageBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (
+            self.list(Vantage::Advantage),
+            self.list(Vantage::Disadvantage),
+        ) {
+            (Some(a), Some(d)) => write!(f, "advantage ({a}) and disadvantage ({d}) cancel"),
+            (Some(a), None) => write!(f, "advantage ({a})"),
+            (None, Some(d)) => write!(f, "disadvantage ({d})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollKind {
+    Attack,
+    Damage,
+    Save,
+    Check,
+}
+
+/// One roll made during combat, explained.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RollExplanation {
+    /// Index of the log line that reports this roll.
+    pub log_index: usize,
+    pub roller: String,
+    pub kind: RollKind,
+    /// What was rolled: `Longsword vs Goblin`, `Con save vs Poisoned`, `Athletics`.
+    pub what: String,
+    /// Every d20 behind an attack roll (two with advantage or disadvantage); empty for
+    /// other rolls, whose breakdown holds the kept die.
+    pub rolls: Vec<u8>,
+    /// The kept d20 (or damage dice) first, then each modifier with its source.
+    pub modifiers: Breakdown,
+    pub vantage: VantageBreakdown,
+    pub total: i32,
+    /// AC or DC the total was compared to; None for damage and opposed checks.
+    pub against: Option<i32>,
+    pub success: Option<bool>,
+    /// Anything the numbers don't show: a natural 20, cover, Legendary Resistance.
+    pub note: Option<String>,
+}
+
+impl RollExplanation {
+    pub fn new(
+        log_index: usize,
+        roller: impl Into<String>,
+        kind: RollKind,
+        what: impl Into<String>,
+        modifiers: Breakdown,
+    ) -> Self {
+        Self {
+            log_index,
+            roller: roller.into(),
+            kind,
+            what: what.into(),
+            rolls: Vec::new(),
+            total: modifiers.total(),
+            modifiers,
+            vantage: VantageBreakdown::default(),
+            against: None,
+            success: None,
+            note: None,
+        }
+    }
+
+    /// Add to the note, after anything already there.
+    pub fn add_note(&mut self, note: impl Into<String>) {
+        let note = note.into();
+        self.note = Some(match self.note.take() {
+            Some(old) => format!("{}; {}", old, note),
+            None => note,
+        });
+    }
+}
+
+/// `Hero Longsword vs Goblin: d20 7 + attack bonus 5 = 12 vs 13 → fail; advantage (hidden)`.
+impl fmt::Display for RollExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.roller, self.what, self.modifiers)?;
+        if self.total != self.modifiers.total() {
+            write!(f, " → {}", self.total)?;
+        }
+        if let Some(against) = self.against {
+            write!(f, " vs {}", against)?;
+        }
+        if let Some(success) = self.success {
+            write!(f, " → {}", if success { "success" } else { "fail" })?;
+        }
+        if !self.vantage.sources.is_empty() {
+            write!(f, "; {}", self.vantage)?;
+        }
+        if let Some(note) = &self.note {
+            write!(f, " ({})", note)?;
+        }
+        Ok(())
+    }
+}
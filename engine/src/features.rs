@@ -0,0 +1,357 @@
+//! Class features with limited uses (SRD 5.1): Fighter, Rogue, Barbarian and Paladin.
+//!
+//! Per-character state is a [`Resources`] map of use pools; everything else (how many
+//! attacks, sneak attack dice, rage damage) is derived from class and level.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::character::Class;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FightingStyle {
+    Archery,
+    Defense,
+    Dueling,
+    GreatWeaponFighting,
+}
+
+impl std::str::FromStr for FightingStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::parse_name("fighting style", s)
+    }
+}
+
+/// Level at which a class picks a fighting style, and which styles it may pick.
+pub fn fighting_styles(class: Class) -> Option<(u8, &'static [FightingStyle])> {
+    use FightingStyle::*;
+    match class {
+        Class::Fighter => Some((1, &[Archery, Defense, Dueling, GreatWeaponFighting])),
+        Class::Paladin => Some((2, &[Defense, Dueling, GreatWeaponFighting])),
+        Class::Ranger => Some((2, &[Archery, Defense, Dueling])),
+        _ => None,
+    }
+}
+
+/// Subclasses with features the combat loop models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subclass {
+    /// Fighter: Improved Critical (3rd) and Superior Critical (15th).
+    Champion,
+}
+
+impl std::str::FromStr for Subclass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::parse_name("subclass", s)
+    }
+}
+
+impl Subclass {
+    /// The class a subclass belongs to and the level it takes effect.
+    pub fn class_and_level(self) -> (Class, u8) {
+        match self {
+            Subclass::Champion => (Class::Fighter, 3),
+        }
+    }
+}
+
+/// Lowest d20 roll that scores a critical hit: 19 with Improved Critical, 18 with
+/// Superior Critical, otherwise 20.
+pub fn crit_threshold(subclass: Option<Subclass>, level: u8) -> u8 {
+    match subclass {
+        Some(Subclass::Champion) if level >= 15 => 18,
+        Some(Subclass::Champion) if level >= 3 => 19,
+        _ => 20,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestKind {
+    Short,
+    Long,
+}
+
+/// A limited-use feature or spell slot tracked per character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    SecondWind,
+    ActionSurge,
+    Rage,
+    #[serde(rename = "spell_slot_1")]
+    SpellSlot1,
+    #[serde(rename = "spell_slot_2")]
+    SpellSlot2,
+    #[serde(rename = "spell_slot_3")]
+    SpellSlot3,
+    #[serde(rename = "spell_slot_4")]
+    SpellSlot4,
+    #[serde(rename = "spell_slot_5")]
+    SpellSlot5,
+}
+
+impl Resource {
+    pub const SPELL_SLOTS: [Resource; 5] = [
+        Resource::SpellSlot1,
+        Resource::SpellSlot2,
+        Resource::SpellSlot3,
+        Resource::SpellSlot4,
+        Resource::SpellSlot5,
+    ];
+
+    /// The shortest rest that restores this resource.
+    pub fn refresh(self) -> RestKind {
+        match self {
+            Resource::SecondWind | Resource::ActionSurge => RestKind::Short,
+            _ => RestKind::Long,
+        }
+    }
+
+    pub fn spell_slot_level(self) -> Option<u8> {
+        Resource::SPELL_SLOTS
+            .iter()
+            .position(|&r| r == self)
+            .map(|i| i as u8 + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pool {
+    pub remaining: u8,
+    pub max: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Resources(pub BTreeMap<Resource, Pool>);
+
+impl Resources {
+    /// Full pools for a class at a level.
+    pub fn for_class(class: Class, level: u8) -> Self {
+        let mut r = Resources::default();
+        r.sync(class, level);
+        r
+    }
+
+    /// Recompute maximums (e.g. after leveling), keeping uses already spent.
+    pub fn sync(&mut self, class: Class, level: u8) {
+        let mut next = BTreeMap::new();
+        for (res, max) in resource_max(class, level) {
+            let spent = self.0.get(&res).map(|p| p.max.saturating_sub(p.remaining));
+            let remaining = max.saturating_sub(spent.unwrap_or(0));
+            next.insert(res, Pool { remaining, max });
+        }
+        self.0 = next;
+    }
+
+    pub fn remaining(&self, res: Resource) -> u8 {
+        self.0.get(&res).map_or(0, |p| p.remaining)
+    }
+
+    /// Spend one use; false if none are left.
+    pub fn spend(&mut self, res: Resource) -> bool {
+        match self.0.get_mut(&res) {
+            Some(p) if p.remaining > 0 => {
+                p.remaining -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Restore everything a rest of this kind refreshes (a long rest restores all).
+    pub fn refresh(&mut self, rest: RestKind) {
+        for (res, pool) in self.0.iter_mut() {
+            if rest == RestKind::Long || res.refresh() == RestKind::Short {
+                pool.remaining = pool.max;
+            }
+        }
+    }
+
+    /// Lowest spell slot with a use left.
+    pub fn lowest_slot(&self) -> Option<Resource> {
+        Resource::SPELL_SLOTS
+            .into_iter()
+            .find(|&r| self.remaining(r) > 0)
+    }
+}
+
+fn resource_max(class: Class, level: u8) -> Vec<(Resource, u8)> {
+    let mut out = Vec::new();
+    match class {
+        Class::Fighter => {
+            out.push((Resource::SecondWind, 1));
+            if level >= 2 {
+                out.push((Resource::ActionSurge, if level >= 17 { 2 } else { 1 }));
+            }
+        }
+        Class::Barbarian => {
+            let rages = match level {
+                0..=2 => 2,
+                3..=5 => 3,
+                6..=11 => 4,
+                12..=16 => 5,
+                17..=19 => 6,
+                _ => u8::MAX, // unlimited at 20th level
+            };
+            out.push((Resource::Rage, rages));
+        }
+        Class::Paladin => {
+            for (res, n) in Resource::SPELL_SLOTS
+                .into_iter()
+                .zip(half_caster_slots(level))
+            {
+                if n > 0 {
+                    out.push((res, n));
+                }
+            }
+        }
+        _ => {}
+    }
+    out
+}
+
+/// Paladin/Ranger spell slots by level (1st..5th).
+pub fn half_caster_slots(level: u8) -> [u8; 5] {
+    match level {
+        0 | 1 => [0, 0, 0, 0, 0],
+        2 => [2, 0, 0, 0, 0],
+        3 | 4 => [3, 0, 0, 0, 0],
+        5 | 6 => [4, 2, 0, 0, 0],
+        7 | 8 => [4, 3, 0, 0, 0],
+        9 | 10 => [4, 3, 2, 0, 0],
+        11 | 12 => [4, 3, 3, 0, 0],
+        13 | 14 => [4, 3, 3, 1, 0],
+        15 | 16 => [4, 3, 3, 2, 0],
+        17 | 18 => [4, 3, 3, 3, 1],
+        _ => [4, 3, 3, 3, 2],
+    }
+}
+
+/// Attacks per Attack action (Extra Attack).
+pub fn attacks_per_action(class: Class, level: u8) -> u8 {
+    match class {
+        Class::Fighter => match level {
+            0..=4 => 1,
+            5..=10 => 2,
+            11..=19 => 3,
+            _ => 4,
+        },
+        Class::Barbarian | Class::Monk | Class::Paladin | Class::Ranger if level >= 5 => 2,
+        _ => 1,
+    }
+}
+
+/// Sneak Attack d6s: 1 at 1st level, +1 every odd level.
+pub fn sneak_attack_dice(class: Class, level: u8) -> u8 {
+    if class == Class::Rogue {
+        level.div_ceil(2)
+    } else {
+        0
+    }
+}
+
+pub fn rage_damage_bonus(level: u8) -> i32 {
+    match level {
+        0..=8 => 2,
+        9..=15 => 3,
+        _ => 4,
+    }
+}
+
+pub fn has_cunning_action(class: Class, level: u8) -> bool {
+    class == Class::Rogue && level >= 2
+}
+
+pub fn has_divine_smite(class: Class, level: u8) -> bool {
+    class == Class::Paladin && level >= 2
+}
+
+/// Skills with Expertise: Rogues get two at 1st and 6th level, Bards at 3rd and 10th.
+pub fn expertise_count(class: Class, level: u8) -> usize {
+    let (first, second) = match class {
+        Class::Rogue => (1, 6),
+        Class::Bard => (3, 10),
+        _ => return 0,
+    };
+    2 * (usize::from(level >= first) + usize::from(level >= second))
+}
+
+/// Feral Instinct: advantage on initiative rolls.
+pub fn has_feral_instinct(class: Class, level: u8) -> bool {
+    class == Class::Barbarian && level >= 7
+}
+
+/// Aura of Protection: from 6th level a paladin and its allies add the paladin's
+/// Charisma modifier (minimum +1) to saving throws.
+pub fn aura_of_protection(class: Class, level: u8, cha_mod: i32) -> i32 {
+    if class == Class::Paladin && level >= 6 {
+        cha_mod.max(1)
+    } else {
+        0
+    }
+}
+
+/// Rage lasts 1 minute.
+pub const RAGE_ROUNDS: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmitePolicy {
+    Never,
+    /// Only on critical hits, where the extra dice are doubled.
+    OnCrit,
+    #[default]
+    Always,
+}
+
+/// How the AI spends class features and consumables during its turn.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tactics {
+    /// Use Second Wind once HP is at or below this percentage of max.
+    pub second_wind_below_pct: u8,
+    pub action_surge: bool,
+    pub rage: bool,
+    pub smite: SmitePolicy,
+    /// Hide with Cunning Action to gain advantage (and Sneak Attack) on the next attack.
+    pub cunning_hide: bool,
+    /// Drink a healing item once HP is at or below this percentage of max.
+    pub heal_below_pct: u8,
+    /// Drink resistance or save potions when an enemy's attacks make them useful.
+    pub buff_items: bool,
+    /// Throw items (alchemist's fire, acid) when they out-damage the weapon attack.
+    pub throw_items: bool,
+    /// Characters attacking in melee grapple, then shove the grappled foe prone so it
+    /// can't stand.
+    pub grapple_shove: bool,
+    /// Characters' melee weapon hits knock creatures out at 0 HP instead of killing
+    /// them, so they can be captured.
+    pub nonlethal: bool,
+}
+
+impl Default for Tactics {
+    fn default() -> Self {
+        Self {
+            second_wind_below_pct: 50,
+            action_surge: true,
+            rage: true,
+            smite: SmitePolicy::Always,
+            cunning_hide: true,
+            heal_below_pct: 30,
+            buff_items: true,
+            throw_items: true,
+            grapple_shove: false,
+            nonlethal: false,
+        }
+    }
+}
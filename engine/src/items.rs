@@ -0,0 +1,154 @@
+//! Inventory and consumable items.
+//!
+//! Item definitions live in content (`content/items/*.json`); characters carry an
+//! inventory of `{ item, quantity, charges }` entries that refer to them by id.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conditions::ConditionKind;
+use crate::error::{EngineError, Result};
+use crate::{DamageDice, DamageType};
+
+/// What using an item takes in combat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemAction {
+    #[default]
+    Action,
+    BonusAction,
+}
+
+/// What an item does when used. Effects from potions last an hour: the rest of the
+/// fight and any fights before the next rest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ItemEffect {
+    /// Regain `dice` + `bonus` HP (potion of healing: 2d4+2).
+    Heal {
+        dice: DamageDice,
+        #[serde(default)]
+        bonus: i32,
+    },
+    /// Resistance to one damage type (potion of resistance).
+    Resistance { damage_type: DamageType },
+    /// Advantage on saves against a condition (antitoxin vs Poisoned).
+    SaveAdvantage { against: ConditionKind },
+    /// A ranged attack with an improvised weapon (alchemist's fire, acid vial).
+    Thrown {
+        dice: DamageDice,
+        damage_type: DamageType,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDef {
+    pub id: String,
+    pub name: String,
+    /// Pounds per item.
+    #[serde(default)]
+    pub weight: f32,
+    #[serde(default)]
+    pub action: ItemAction,
+    /// Uses per item before it is used up (wands, kits); None means single use.
+    #[serde(default)]
+    pub charges: Option<u8>,
+    /// None for gear with no combat use (rope, rations).
+    #[serde(default)]
+    pub effect: Option<ItemEffect>,
+}
+
+/// One line of a character's inventory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InventoryEntry {
+    pub item: String,
+    #[serde(default = "one")]
+    pub quantity: u32,
+    /// Charges left on the item in use; defaults to the item's full charges.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charges: Option<u8>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+/// An inventory entry resolved against the item catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    pub def: ItemDef,
+    pub quantity: u32,
+    pub charges: Option<u8>,
+}
+
+impl ItemStack {
+    pub fn new(def: ItemDef, quantity: u32) -> Self {
+        let charges = def.charges;
+        Self {
+            def,
+            quantity,
+            charges,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quantity == 0
+    }
+
+    /// Spend one use: a charge if the item has them, otherwise the item itself.
+    /// Returns false if nothing is left.
+    pub fn use_one(&mut self) -> bool {
+        if self.quantity == 0 {
+            return false;
+        }
+        match self.charges.as_mut() {
+            Some(c) if *c > 1 => *c -= 1,
+            _ => {
+                self.quantity -= 1;
+                self.charges = self.def.charges;
+            }
+        }
+        true
+    }
+
+    pub fn to_entry(&self) -> InventoryEntry {
+        InventoryEntry {
+            item: self.def.id.clone(),
+            quantity: self.quantity,
+            charges: self.charges.filter(|_| self.def.charges.is_some()),
+        }
+    }
+}
+
+pub fn find_item<'a>(catalog: &'a [ItemDef], id: &str) -> Option<&'a ItemDef> {
+    catalog.iter().find(|d| d.id.eq_ignore_ascii_case(id))
+}
+
+/// Look up every entry in the catalog; unknown ids are an error.
+pub fn resolve_inventory(
+    entries: &[InventoryEntry],
+    catalog: &[ItemDef],
+) -> Result<Vec<ItemStack>> {
+    entries
+        .iter()
+        .map(|e| {
+            let def = find_item(catalog, &e.item).ok_or_else(|| {
+                EngineError::not_found("item", e.item.as_str(), "not in item list")
+            })?;
+            let mut stack = ItemStack::new(def.clone(), e.quantity);
+            if let (Some(max), Some(left)) = (def.charges, e.charges) {
+                stack.charges = Some(left.min(max));
+            }
+            Ok(stack)
+        })
+        .collect()
+}
+
+/// Total weight in pounds.
+pub fn carried_weight(stacks: &[ItemStack]) -> f32 {
+    stacks
+        .iter()
+        .map(|s| s.def.weight * s.quantity as f32)
+        .sum()
+}
@@ -0,0 +1,89 @@
+//! Legendary and lair actions (SRD 5.1) for boss stat blocks.
+//!
+//! A legendary creature spends a pool of actions, one at a time, at the end of other
+//! creatures' turns; the pool refills at the start of its own turn. Lair actions
+//! happen on initiative count 20, losing ties. Positions aren't tracked, so an area
+//! effect reaches every foe.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conditions::ConditionSpec;
+use crate::{DamageDice, DamageType, SavingThrow};
+
+fn default_per_round() -> u32 {
+    3
+}
+
+fn default_cost() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LegendaryActions {
+    /// Actions it can spend each round (usually 3).
+    #[serde(default = "default_per_round")]
+    pub per_round: u32,
+    pub actions: Vec<LegendaryAction>,
+}
+
+impl LegendaryActions {
+    /// The costliest action `left` actions pay for, the first listed on ties.
+    pub fn choose(&self, left: u32) -> Option<&LegendaryAction> {
+        self.actions
+            .iter()
+            .filter(|a| a.cost <= left)
+            .rev()
+            .max_by_key(|a| a.cost)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LegendaryAction {
+    pub name: String,
+    /// Actions from the pool it costs (Wing Attack: 2).
+    #[serde(default = "default_cost")]
+    pub cost: u32,
+    pub effect: MonsterAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LairAction {
+    pub name: String,
+    pub effect: MonsterAction,
+}
+
+/// What a legendary or lair action does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MonsterAction {
+    /// One of the creature's own attacks, by name.
+    Attack { attack: String },
+    /// Every foe makes a save. A failure takes the damage and the condition; a success
+    /// takes half the damage if `half_on_success`, otherwise nothing.
+    Save {
+        save: SavingThrow,
+        #[serde(default)]
+        dice: Option<DamageDice>,
+        #[serde(default)]
+        bonus: i32,
+        #[serde(default)]
+        damage_type: Option<DamageType>,
+        #[serde(default)]
+        half_on_success: bool,
+        #[serde(default)]
+        condition: Option<ConditionSpec>,
+    },
+}
+
+/// The lair action after `last` (last round's) out of `count`. None repeats two rounds
+/// running, so a lair with one action skips every other round.
+pub fn next_lair_action(count: usize, last: Option<usize>) -> Option<usize> {
+    match (count, last) {
+        (0, _) => None,
+        (_, None) => Some(0),
+        (_, Some(n)) => Some((n + 1) % count).filter(|&next| next != n),
+    }
+}
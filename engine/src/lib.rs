@@ -2,11 +2,25 @@ use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::str::FromStr;
 
 pub mod api;
+pub mod campaign;
+pub mod character;
+pub mod checks;
+pub mod combat;
 pub mod conditions;
 pub mod content;
+pub mod effects;
+pub mod error;
+pub mod explain;
+pub mod features;
+pub mod items;
+pub mod legendary;
 pub mod life;
+pub mod saves;
+pub mod traits;
+pub mod validate;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -156,7 +170,34 @@ pub enum Ability {
     Cha,
 }
 
+impl Ability {
+    pub const ALL: [Ability; 6] = [
+        Ability::Str,
+        Ability::Dex,
+        Ability::Con,
+        Ability::Int,
+        Ability::Wis,
+        Ability::Cha,
+    ];
+}
+
+/// Parse a name as written in JSON (e.g. `sleight_of_hand`); `-` is accepted for `_`.
+pub(crate) fn parse_name<T: serde::de::DeserializeOwned>(what: &str, s: &str) -> Result<T, String> {
+    let key = s.trim().to_lowercase().replace('-', "_");
+    serde_json::from_value(serde_json::Value::String(key))
+        .map_err(|_| format!("unknown {} '{}'", what, s.trim()))
+}
+
+impl FromStr for Ability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_name("ability", s)
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SavingThrow {
     pub ability: Ability,
     pub dc: i32,
@@ -185,7 +226,36 @@ pub enum Skill {
     Persuasion, // CHA
 }
 
+impl FromStr for Skill {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_name("skill", s)
+    }
+}
+
 impl Skill {
+    pub const ALL: [Skill; 18] = [
+        Skill::Athletics,
+        Skill::Acrobatics,
+        Skill::SleightOfHand,
+        Skill::Stealth,
+        Skill::Arcana,
+        Skill::History,
+        Skill::Investigation,
+        Skill::Nature,
+        Skill::Religion,
+        Skill::AnimalHandling,
+        Skill::Insight,
+        Skill::Medicine,
+        Skill::Perception,
+        Skill::Survival,
+        Skill::Deception,
+        Skill::Intimidation,
+        Skill::Performance,
+        Skill::Persuasion,
+    ];
+
     pub fn key_ability(&self) -> Ability {
         use Ability::*;
         match self {
@@ -233,6 +303,16 @@ impl AbilityScores {
             Ability::Cha => self.cha,
         }
     }
+    pub fn set(&mut self, a: Ability, value: i32) {
+        match a {
+            Ability::Str => self.str_ = value,
+            Ability::Dex => self.dex = value,
+            Ability::Con => self.con = value,
+            Ability::Int => self.int_ = value,
+            Ability::Wis => self.wis = value,
+            Ability::Cha => self.cha = value,
+        }
+    }
     pub fn mod_of(&self, a: Ability) -> i32 {
         ability_mod(self.get(a))
     }
@@ -244,6 +324,9 @@ pub struct Actor {
     pub proficiency_bonus: i32,
     pub save_proficiencies: HashSet<Ability>,
     pub skill_proficiencies: HashSet<Skill>,
+    /// Proficient skills that add double the proficiency bonus.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub expertise: HashSet<Skill>,
 }
 
 impl Actor {
@@ -263,10 +346,12 @@ impl Actor {
 
     pub fn skill_mod(&self, s: Skill) -> i32 {
         let base = self.ability_mod(s.key_ability());
-        let prof = if self.skill_proficiencies.contains(&s) {
-            self.proficiency_bonus
-        } else {
+        let prof = if !self.skill_proficiencies.contains(&s) {
             0
+        } else if self.expertise.contains(&s) {
+            self.proficiency_bonus * 2
+        } else {
+            self.proficiency_bonus
         };
         base + prof
     }
@@ -319,7 +404,8 @@ impl Actor {
 
 /* ---------------- attacks & damage ---------------- */
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DamageDice {
     pub count: u8,
     pub sides: u8,
@@ -344,6 +430,49 @@ impl DamageDice {
     }
 }
 
+/// How a critical hit's damage is rolled: the rule as written or a table variant.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CritRule {
+    /// Roll the damage dice twice (5e).
+    #[default]
+    DoubleDice,
+    /// Take the dice's maximum, then roll them once more.
+    MaxPlusRoll,
+    /// Roll normally and double the total, modifiers included.
+    DoubleTotal,
+}
+
+impl CritRule {
+    /// The dice part of a critical hit; `DoubleTotal` rolls them once and is doubled
+    /// by [`CritRule::total`].
+    pub fn roll_dice(self, dice: &mut Dice, spec: DamageDice) -> i32 {
+        match self {
+            CritRule::DoubleDice => spec.roll_total(dice, true),
+            CritRule::MaxPlusRoll => {
+                spec.count as i32 * spec.sides as i32 + spec.roll_total(dice, false)
+            }
+            CritRule::DoubleTotal => spec.roll_total(dice, false),
+        }
+    }
+
+    /// The full damage of a critical hit, given what its dice and modifiers came to.
+    pub fn total(self, damage: i32) -> i32 {
+        match self {
+            CritRule::DoubleTotal => damage * 2,
+            _ => damage,
+        }
+    }
+}
+
+impl std::str::FromStr for CritRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_name("crit rule", s)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AttackResult {
     pub roll: i32,
@@ -359,6 +488,18 @@ pub struct AttackResult {
 
 /// 5e: nat20 always hits, nat1 always misses; otherwise total >= AC.
 pub fn attack(dice: &mut Dice, mode: AdMode, bonus: i32, ac: i32) -> AttackResult {
+    attack_with_crit_range(dice, mode, bonus, ac, 20)
+}
+
+/// As [`attack`], but a kept roll of `crit_on` or higher is a critical hit, and so a
+/// hit (Improved Critical: 19).
+pub fn attack_with_crit_range(
+    dice: &mut Dice,
+    mode: AdMode,
+    bonus: i32,
+    ac: i32,
+    crit_on: u8,
+) -> AttackResult {
     let mut raw_rolls = Vec::new();
     let kept = match mode {
         AdMode::Normal => {
@@ -383,8 +524,9 @@ pub fn attack(dice: &mut Dice, mode: AdMode, bonus: i32, ac: i32) -> AttackResul
     } as i32;
     let nat20 = kept == 20;
     let nat1 = kept == 1;
+    let is_crit = kept >= (crit_on.clamp(2, 20) as i32);
     let total = kept + bonus;
-    let hit = if nat20 {
+    let hit = if is_crit {
         true
     } else if nat1 {
         false
@@ -399,14 +541,32 @@ pub fn attack(dice: &mut Dice, mode: AdMode, bonus: i32, ac: i32) -> AttackResul
         nat20,
         nat1,
         hit,
-        is_crit: nat20,
+        is_crit,
         raw_rolls,
     }
 }
 
 /// On crit, double dice (modifier once).
 pub fn damage(dice: &mut Dice, dice_spec: DamageDice, modifier: i32, crit: bool) -> i32 {
-    dice_spec.roll_total(dice, crit) + modifier
+    damage_with_rule(
+        dice,
+        dice_spec,
+        modifier,
+        crit.then_some(CritRule::DoubleDice),
+    )
+}
+
+/// Damage for a hit; `crit` names the rule when it is a critical hit.
+pub fn damage_with_rule(
+    dice: &mut Dice,
+    dice_spec: DamageDice,
+    modifier: i32,
+    crit: Option<CritRule>,
+) -> i32 {
+    match crit {
+        Some(rule) => rule.total(rule.roll_dice(dice, dice_spec) + modifier),
+        None => dice_spec.roll_total(dice, false) + modifier,
+    }
 }
 
 pub fn adjust_damage_by_type(
@@ -433,7 +593,74 @@ pub fn adjust_damage_by_type(
     }
 }
 
+/// A special material that gets past some defenses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Material {
+    Silvered,
+    Adamantine,
+}
+
+/// What an attack is made with, for defenses that depend on it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttackSource {
+    pub magical: bool,
+    pub material: Option<Material>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Defense {
+    Resistance,
+    Vulnerability,
+    Immunity,
+}
+
+/// A resistance, vulnerability or immunity that only some attacks meet: "bludgeoning,
+/// piercing and slashing from nonmagical attacks that aren't silvered".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QualifiedDefense {
+    pub defense: Defense,
+    pub types: Vec<DamageType>,
+    /// Only against nonmagical attacks.
+    #[serde(default)]
+    pub nonmagical: bool,
+    /// Attacks made of this material get past it.
+    #[serde(default)]
+    pub except: Option<Material>,
+    /// Only against attacks made of this material (vulnerable to silvered weapons).
+    #[serde(default)]
+    pub material: Option<Material>,
+}
+
+impl QualifiedDefense {
+    /// Whether it applies to `dtype` damage from an attack made with `source`.
+    pub fn applies(&self, dtype: DamageType, source: AttackSource) -> bool {
+        self.types.contains(&dtype)
+            && !(self.nonmagical && source.magical)
+            && self.except.is_none_or(|m| source.material != Some(m))
+            && self.material.is_none_or(|m| source.material == Some(m))
+    }
+
+    /// The qualifiers in words: `nonmagical, not silvered`.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.nonmagical {
+            parts.push("nonmagical".to_string());
+        }
+        if let Some(m) = self.except {
+            parts.push(format!("not {:?}", m).to_lowercase());
+        }
+        if let Some(m) = self.material {
+            parts.push(format!("{:?}", m).to_lowercase());
+        }
+        parts.join(", ")
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Weapon {
     pub name: String,
     pub dice: DamageDice,
@@ -446,6 +673,23 @@ pub struct Weapon {
     pub versatile: Option<DamageDice>,
     #[serde(default)]
     pub damage_type: Option<DamageType>,
+    /// Requires two hands (Great Weapon Fighting applies; Dueling does not).
+    #[serde(default)]
+    pub two_handed: bool,
+    /// A magic weapon gets past resistance to nonmagical attacks.
+    #[serde(default)]
+    pub magical: bool,
+    #[serde(default)]
+    pub material: Option<Material>,
+}
+
+impl Weapon {
+    pub fn source(&self) -> AttackSource {
+        AttackSource {
+            magical: self.magical,
+            material: self.material,
+        }
+    }
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
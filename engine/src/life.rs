@@ -21,6 +21,15 @@ pub struct Health {
     pub max_hp: i32,
     pub state: LifeState,
     pub death: DeathSaves,
+    /// Temporary hit points, lost before `hp` and gone after a long rest.
+    #[serde(default)]
+    pub temp_hp: i32,
+    /// Taken off `max_hp` (a wraith's Life Drain) until the next long rest.
+    #[serde(default)]
+    pub max_hp_reduction: i32,
+    /// Damage threshold (objects, vehicles): smaller hits deal no damage.
+    #[serde(default)]
+    pub threshold: i32,
 }
 
 impl Health {
@@ -30,48 +39,255 @@ impl Health {
             max_hp,
             state: LifeState::Conscious,
             death: DeathSaves::default(),
+            temp_hp: 0,
+            max_hp_reduction: 0,
+            threshold: 0,
         }
     }
+
+    /// The hit point maximum after reductions.
+    pub fn effective_max_hp(&self) -> i32 {
+        (self.max_hp - self.max_hp_reduction).max(0)
+    }
 }
 
-/// Apply damage and handle drop-to-0 transitions. Returns true if the creature dropped to 0 this call.
+/// Damage left for `hp` after the damage threshold and temporary hit points, with a
+/// note for the log when either took some (`temp HP absorbs 3`).
+pub fn soak_damage(health: &mut Health, dmg: i32) -> (i32, Option<String>) {
+    if dmg > 0 && dmg < health.threshold {
+        return (
+            0,
+            Some(format!("below damage threshold {}", health.threshold)),
+        );
+    }
+    let absorbed = dmg.clamp(0, health.temp_hp);
+    if absorbed == 0 {
+        return (dmg, None);
+    }
+    health.temp_hp -= absorbed;
+    (
+        dmg - absorbed,
+        Some(format!(
+            "temp HP absorbs {}, {} left",
+            absorbed, health.temp_hp
+        )),
+    )
+}
+
+/// Grant temporary hit points. They don't stack: the creature keeps the higher amount.
+pub fn grant_temp_hp(name: &str, health: &mut Health, amount: i32, mut log: impl FnMut(String)) {
+    if matches!(health.state, LifeState::Dead) || amount <= health.temp_hp {
+        return;
+    }
+    log(format!(
+        "[TEMPHP][{}] {} → {} temporary HP",
+        name, health.temp_hp, amount
+    ));
+    health.temp_hp = amount;
+}
+
+/// Reduce the hit point maximum until the next long rest, lowering HP to match. A
+/// creature whose maximum reaches 0 dies. Returns true if it died.
+pub fn reduce_max_hp(
+    name: &str,
+    health: &mut Health,
+    amount: i32,
+    mut log: impl FnMut(String),
+) -> bool {
+    if matches!(health.state, LifeState::Dead) || amount <= 0 {
+        return false;
+    }
+    let before = health.effective_max_hp();
+    health.max_hp_reduction = (health.max_hp_reduction + amount).min(health.max_hp);
+    health.hp = health.hp.min(health.effective_max_hp());
+    log(format!(
+        "[MAXHP][{}] max HP {} → {} until a long rest",
+        name,
+        before,
+        health.effective_max_hp()
+    ));
+    if health.effective_max_hp() == 0 {
+        health.state = LifeState::Dead;
+        log(format!("[STATE][{}] max HP reduced to 0 → DEAD", name));
+        return true;
+    }
+    false
+}
+
+/// Apply damage and handle drop-to-0 transitions: temporary HP soak it up first, and
+/// damage left over after dropping to 0 that equals the hit point maximum kills
+/// outright. Damage at 0 HP is a failed death save (two on a `crit`). Returns true if
+/// the creature dropped to 0 this call.
 pub fn apply_damage(
     name: &str,
     health: &mut Health,
     conditions: &mut Vec<ActiveCondition>,
     dmg: i32,
+    crit: bool,
     mut log: impl FnMut(String),
 ) -> bool {
     if matches!(health.state, LifeState::Dead) {
         return false;
     }
 
+    let (dmg, soaked) = soak_damage(health, dmg);
+    if health.hp == 0 {
+        damage_at_zero(name, health, dmg, crit, soaked, &mut log);
+        return false;
+    }
     let before = health.hp;
     health.hp = (health.hp - dmg).max(0);
-    log(format!(
-        "[DMG][{}] {} → {} (−{})",
-        name, before, health.hp, dmg
-    ));
+    match soaked {
+        Some(note) => log(format!(
+            "[DMG][{}] {} → {} (−{}; {})",
+            name, before, health.hp, dmg, note
+        )),
+        None => log(format!(
+            "[DMG][{}] {} → {} (−{})",
+            name, before, health.hp, dmg
+        )),
+    }
 
+    if before > 0 && health.hp == 0 && dmg - before >= health.effective_max_hp() {
+        health.state = LifeState::Dead;
+        log(format!(
+            "[STATE][{}] massive damage: {} left over ≥ max HP {} → DEAD",
+            name,
+            dmg - before,
+            health.effective_max_hp()
+        ));
+        return true;
+    }
     if before > 0 && health.hp == 0 {
-        // Transition to Unconscious (not stable). Apply Prone once for flavor.
-        health.state = LifeState::Unconscious { stable: false };
-        if !conditions.iter().any(|c| c.kind == ConditionKind::Prone) {
-            conditions.push(ActiveCondition {
-                kind: ConditionKind::Prone,
-                save_ends_each_turn: false,
-                end_phase: None,
-                end_save: None,
-                pending_one_turn: false,
-            });
-            log(format!("[COND][{}] gains Prone (unconscious)", name));
-        }
+        // Transition to Unconscious (not stable).
+        fall_unconscious(name, health, conditions, false, &mut log);
         log(format!("[STATE][{}] drops to 0 HP → Unconscious", name));
         return true;
     }
     false
 }
 
+/// Apply a nonlethal melee hit: as [`apply_damage`], but a creature it drops to 0 HP
+/// is knocked out (unconscious and stable) instead of dying, whatever the damage left
+/// over. Returns true if the creature was knocked out.
+pub fn apply_nonlethal_damage(
+    name: &str,
+    health: &mut Health,
+    conditions: &mut Vec<ActiveCondition>,
+    dmg: i32,
+    mut log: impl FnMut(String),
+) -> bool {
+    if health.hp == 0 || matches!(health.state, LifeState::Dead) {
+        return apply_damage(name, health, conditions, dmg, false, log);
+    }
+    let (dmg, soaked) = soak_damage(health, dmg);
+    let before = health.hp;
+    health.hp = (health.hp - dmg).max(0);
+    match soaked {
+        Some(note) => log(format!(
+            "[DMG][{}] {} → {} (−{}; {}, nonlethal)",
+            name, before, health.hp, dmg, note
+        )),
+        None => log(format!(
+            "[DMG][{}] {} → {} (−{}, nonlethal)",
+            name, before, health.hp, dmg
+        )),
+    }
+    if health.hp > 0 {
+        return false;
+    }
+    fall_unconscious(name, health, conditions, true, &mut log);
+    log(format!(
+        "[STATE][{}] knocked out at 0 HP → Unconscious (stable)",
+        name
+    ));
+    true
+}
+
+/// Drop to unconscious at 0 HP. Apply Prone once for flavor.
+fn fall_unconscious(
+    name: &str,
+    health: &mut Health,
+    conditions: &mut Vec<ActiveCondition>,
+    stable: bool,
+    log: &mut impl FnMut(String),
+) {
+    health.state = LifeState::Unconscious { stable };
+    if !conditions.iter().any(|c| c.kind == ConditionKind::Prone) {
+        conditions.push(ActiveCondition::indefinite(ConditionKind::Prone));
+        log(format!("[COND][{}] gains Prone (unconscious)", name));
+    }
+}
+
+/// Damage to a creature already at 0 HP: one failed death save, two on a critical
+/// hit, and death if it is at least the hit point maximum. A stable creature starts
+/// dying again.
+fn damage_at_zero(
+    name: &str,
+    health: &mut Health,
+    dmg: i32,
+    crit: bool,
+    soaked: Option<String>,
+    log: &mut impl FnMut(String),
+) {
+    if dmg <= 0 {
+        if let Some(note) = soaked {
+            log(format!("[DMG][{}] 0 → 0 (−0; {})", name, note));
+        }
+        return;
+    }
+    if dmg >= health.effective_max_hp() {
+        health.state = LifeState::Dead;
+        log(format!(
+            "[STATE][{}] takes {} at 0 HP ≥ max HP {} → DEAD",
+            name,
+            dmg,
+            health.effective_max_hp()
+        ));
+        return;
+    }
+    let failed = if crit { 2 } else { 1 };
+    health.death.failures = (health.death.failures + failed).min(3);
+    health.state = LifeState::Unconscious { stable: false };
+    log(format!(
+        "[DEATHSAVE][{}] takes {} at 0 HP{} → {} failure{} (S={}, F={})",
+        name,
+        dmg,
+        if crit { " from a critical hit" } else { "" },
+        failed,
+        if failed == 1 { "" } else { "s" },
+        health.death.successes,
+        health.death.failures
+    ));
+    if health.death.failures >= 3 {
+        health.state = LifeState::Dead;
+        log(format!("[STATE][{}] three failed death saves → DEAD", name));
+    }
+}
+
+/// A stable creature at 0 HP regains 1 HP after `hours` (1d4) and wakes. Returns true
+/// if it did.
+pub fn recover_from_stable(
+    name: &str,
+    health: &mut Health,
+    hours: u32,
+    mut log: impl FnMut(String),
+) -> bool {
+    if health.state != (LifeState::Unconscious { stable: true }) || health.hp > 0 {
+        return false;
+    }
+    health.hp = 1.min(health.effective_max_hp());
+    health.state = LifeState::Conscious;
+    health.death = DeathSaves::default();
+    log(format!(
+        "[STATE][{}] stable for {} hour{} → regains 1 HP and wakes",
+        name,
+        hours,
+        if hours == 1 { "" } else { "s" }
+    ));
+    true
+}
+
 /// Healing; if at 0/unconscious, wakes and resets death saves.
 pub fn heal(name: &str, health: &mut Health, amount: i32, mut log: impl FnMut(String)) {
     if amount <= 0 {
@@ -79,7 +295,7 @@ pub fn heal(name: &str, health: &mut Health, amount: i32, mut log: impl FnMut(St
     }
     let before = health.hp;
     let was_uncon = matches!(health.state, LifeState::Unconscious { .. });
-    health.hp = (health.hp + amount).min(health.max_hp);
+    health.hp = (health.hp + amount).min(health.effective_max_hp());
     if was_uncon && health.hp > 0 {
         health.state = LifeState::Conscious;
         health.death = DeathSaves::default();
@@ -159,3 +375,120 @@ pub fn process_death_save_start_of_turn(
         _ => None,
     }
 }
+
+/* ---------------- rests ---------------- */
+
+/// Hit dice a character can spend on short rests (one per level).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HitDice {
+    pub sides: u8,
+    pub remaining: u8,
+    pub max: u8,
+}
+
+/// How many hit dice to spend on a short rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShortRestPolicy {
+    /// Keep spending dice until HP reaches this percentage of max.
+    pub heal_to_pct: u8,
+    /// Never spend below this many remaining dice (saved for later rests).
+    pub reserve: u8,
+}
+
+impl Default for ShortRestPolicy {
+    fn default() -> Self {
+        Self {
+            heal_to_pct: 100,
+            reserve: 0,
+        }
+    }
+}
+
+/// Exhaustion level 6 is death.
+pub const MAX_EXHAUSTION: u8 = 6;
+
+/// Short rest: spend hit dice per `policy`, each healing die + Con mod (minimum 0).
+/// `roll_die(sides)` rolls one hit die. Returns the HP regained.
+pub fn short_rest(
+    name: &str,
+    health: &mut Health,
+    hit_dice: &mut HitDice,
+    con_mod: i32,
+    policy: &ShortRestPolicy,
+    mut roll_die: impl FnMut(u8) -> i32,
+    mut log: impl FnMut(String),
+) -> i32 {
+    if matches!(health.state, LifeState::Dead) {
+        return 0;
+    }
+    log(format!("[REST][{}] short rest", name));
+    let target = health.effective_max_hp() * policy.heal_to_pct.min(100) as i32 / 100;
+    let mut regained = 0;
+    while health.hp < target && hit_dice.remaining > policy.reserve {
+        hit_dice.remaining -= 1;
+        let roll = roll_die(hit_dice.sides);
+        let amount = (roll + con_mod).max(0);
+        log(format!(
+            "[REST][{}] spends a hit die: d{}={} {:+} = {} ({} left)",
+            name, hit_dice.sides, roll, con_mod, amount, hit_dice.remaining
+        ));
+        let before = health.hp;
+        heal(name, health, amount, &mut log);
+        regained += health.hp - before;
+    }
+    regained
+}
+
+/// Long rest: full HP and maximum, no temporary HP, back half the total hit dice
+/// (minimum one), one less level of exhaustion. A creature must have at least 1 HP to
+/// benefit. Returns whether it did.
+pub fn long_rest(
+    name: &str,
+    health: &mut Health,
+    hit_dice: Option<&mut HitDice>,
+    exhaustion: &mut u8,
+    mut log: impl FnMut(String),
+) -> bool {
+    if matches!(health.state, LifeState::Dead) {
+        return false;
+    }
+    if health.hp <= 0 {
+        log(format!(
+            "[REST][{}] long rest: needs at least 1 HP to benefit",
+            name
+        ));
+        return false;
+    }
+    let before = health.hp;
+    if health.max_hp_reduction > 0 {
+        log(format!(
+            "[REST][{}] max HP restored to {}",
+            name, health.max_hp
+        ));
+    }
+    health.max_hp_reduction = 0;
+    health.temp_hp = 0;
+    health.hp = health.max_hp;
+    health.death = DeathSaves::default();
+    log(format!(
+        "[REST][{}] long rest: HP {} → {}",
+        name, before, health.hp
+    ));
+    if let Some(hd) = hit_dice {
+        let regain = (hd.max / 2).max(1);
+        hd.remaining = (hd.remaining + regain).min(hd.max);
+        log(format!(
+            "[REST][{}] hit dice {}/{}",
+            name, hd.remaining, hd.max
+        ));
+    }
+    if *exhaustion > 0 {
+        *exhaustion -= 1;
+        log(format!(
+            "[REST][{}] exhaustion → level {}",
+            name, exhaustion
+        ));
+    }
+    true
+}
@@ -0,0 +1,226 @@
+//! Saving throws with everything that bends them: advantage and disadvantage from
+//! conditions and features, flat and dice bonuses, automatic failure and Legendary
+//! Resistance.
+
+use crate::conditions::{ActiveCondition, ConditionKind, Vantage};
+use crate::explain::{Breakdown, DiceTerm, Term, VantageBreakdown, VantageSource};
+use crate::{Ability, Actor, AdMode, Dice};
+
+/// What a save is against, for features that only apply to some saves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveContext {
+    pub ability: Ability,
+    pub dc: i32,
+    /// The condition a failed save would impose or keep in place.
+    pub against: Option<ConditionKind>,
+    /// A spell or other magical effect (Magic Resistance applies).
+    pub magical: bool,
+}
+
+impl SaveContext {
+    pub fn new(ability: Ability, dc: i32) -> Self {
+        Self {
+            ability,
+            dc,
+            against: None,
+            magical: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveOutcome {
+    /// The kept d20.
+    pub roll: i32,
+    pub total: i32,
+    pub success: bool,
+    pub mode: AdMode,
+    /// Why the result isn't simply `total >= dc` (automatic failure, Legendary
+    /// Resistance), for the log.
+    pub note: Option<String>,
+    /// The d20 and each modifier with its source.
+    pub breakdown: Breakdown,
+    /// Where the advantage or disadvantage came from.
+    pub vantage: VantageBreakdown,
+}
+
+impl SaveOutcome {
+    /// A plain roll: success if the total meets the DC.
+    pub fn new(roll: i32, total: i32, dc: i32) -> Self {
+        let mut breakdown = Breakdown::new("d20", roll);
+        breakdown.push("modifier", total - roll);
+        Self {
+            roll,
+            total,
+            success: total >= dc,
+            mode: AdMode::Normal,
+            note: None,
+            breakdown,
+            vantage: VantageBreakdown::default(),
+        }
+    }
+}
+
+/// Which saves an advantage or disadvantage source applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveScope {
+    All,
+    Ability(Ability),
+    /// Saves against one condition (Dwarven Resilience: Poisoned).
+    Against(ConditionKind),
+    /// Saves against spells and other magical effects (Magic Resistance).
+    Magical,
+}
+
+impl SaveScope {
+    pub fn applies(self, ctx: &SaveContext) -> bool {
+        match self {
+            SaveScope::All => true,
+            SaveScope::Ability(a) => a == ctx.ability,
+            SaveScope::Against(k) => ctx.against == Some(k),
+            SaveScope::Magical => ctx.magical,
+        }
+    }
+}
+
+/// Everything about a creature that adjusts its saving throws, gathered before the roll,
+/// each with its source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveProfile {
+    /// Advantage and disadvantage sources and the saves they apply to (Rage: Str,
+    /// exhaustion level 3 and up: every save).
+    pub vantage: Vec<(SaveScope, VantageSource)>,
+    /// Abilities whose saves fail without a roll, with the cause (Stunned or Paralyzed:
+    /// Str and Dex).
+    pub auto_fail: Vec<(Ability, String)>,
+    /// Added to every save (Aura of Protection).
+    pub bonuses: Vec<Term>,
+    /// Rolled and added to or subtracted from every save (Bless, Bane).
+    pub dice: Vec<DiceTerm>,
+    /// Failed saves this creature can still choose to succeed (Legendary Resistance).
+    pub legendary_resistance: u8,
+}
+
+impl SaveProfile {
+    /// What a creature's conditions do to its saves: Restrained gives disadvantage on
+    /// Dex saves, Stunned and Paralyzed fail Str and Dex saves.
+    pub fn from_conditions(conds: &[ActiveCondition]) -> Self {
+        let mut profile = SaveProfile {
+            auto_fail: auto_fail_from_conditions(conds),
+            ..SaveProfile::default()
+        };
+        for c in conds {
+            if c.kind == ConditionKind::Restrained {
+                profile.grant(
+                    SaveScope::Ability(Ability::Dex),
+                    "Restrained",
+                    Vantage::Disadvantage,
+                );
+            }
+        }
+        profile
+    }
+
+    /// Add an advantage or disadvantage source for the saves in `scope`; `Normal` adds
+    /// nothing.
+    pub fn grant(&mut self, scope: SaveScope, source: impl Into<String>, vantage: Vantage) {
+        if vantage == Vantage::Normal {
+            return;
+        }
+        self.vantage.push((
+            scope,
+            VantageSource {
+                source: source.into(),
+                vantage,
+            },
+        ));
+    }
+
+    /// The sources that apply to this save.
+    pub fn vantage(&self, ctx: &SaveContext) -> VantageBreakdown {
+        VantageBreakdown {
+            sources: self
+                .vantage
+                .iter()
+                .filter(|(scope, _)| scope.applies(ctx))
+                .map(|(_, source)| source.clone())
+                .collect(),
+        }
+    }
+
+    pub fn mode(&self, ctx: &SaveContext) -> AdMode {
+        self.vantage(ctx).net().into()
+    }
+
+    /// Roll the save. A failure spends a Legendary Resistance use if any are left.
+    pub fn roll(&mut self, dice: &mut Dice, actor: &Actor, ctx: &SaveContext) -> SaveOutcome {
+        let vantage = self.vantage(ctx);
+        let mode: AdMode = vantage.net().into();
+        let auto_fail = self.auto_fail.iter().find(|(a, _)| *a == ctx.ability);
+        let mut outcome = if let Some((_, cause)) = auto_fail {
+            SaveOutcome {
+                roll: 0,
+                total: 0,
+                success: false,
+                mode,
+                note: Some("automatic failure".into()),
+                breakdown: Breakdown::new(cause.clone(), 0),
+                vantage,
+            }
+        } else {
+            let roll = dice.d20(mode) as i32;
+            let mut breakdown = Breakdown::new("d20", roll);
+            breakdown.push(
+                format!("{:?} save", ctx.ability),
+                actor.save_mod(ctx.ability),
+            );
+            breakdown.terms.extend(self.bonuses.iter().cloned());
+            for d in &self.dice {
+                breakdown.terms.push(d.roll(dice));
+            }
+            SaveOutcome {
+                mode,
+                breakdown: breakdown.clone(),
+                vantage,
+                ..SaveOutcome::new(roll, breakdown.total(), ctx.dc)
+            }
+        };
+        if !outcome.success && self.legendary_resistance > 0 {
+            self.legendary_resistance -= 1;
+            outcome.success = true;
+            outcome.note = Some(format!(
+                "Legendary Resistance, {} left",
+                self.legendary_resistance
+            ));
+        }
+        outcome
+    }
+}
+
+impl Actor {
+    /// A saving throw with the creature's conditions applied; `mode` is any advantage or
+    /// disadvantage the caller knows of besides them.
+    pub fn saving_throw_with_conditions(
+        &self,
+        dice: &mut Dice,
+        ability: Ability,
+        conds: &[ActiveCondition],
+        mode: AdMode,
+        dc: i32,
+    ) -> SaveOutcome {
+        let mut profile = SaveProfile::from_conditions(conds);
+        profile.grant(SaveScope::All, "situational", mode.into());
+        profile.roll(dice, self, &SaveContext::new(ability, dc))
+    }
+}
+
+/// Abilities whose saves a creature fails automatically because of its conditions.
+pub fn auto_fail_from_conditions(conds: &[ActiveCondition]) -> Vec<(Ability, String)> {
+    match conds.iter().find(|c| c.kind.incapacitates()) {
+        Some(c) => vec![
+            (Ability::Str, format!("{:?}", c.kind)),
+            (Ability::Dex, format!("{:?}", c.kind)),
+        ],
+        None => Vec::new(),
+    }
+}
@@ -0,0 +1,64 @@
+//! Monster traits from stat blocks (SRD 5.1): the common ones that change how a
+//! creature fights.
+//!
+//! Combat doesn't track positions, so "an ally within 5 feet of the target" is any ally
+//! that can act, as for Sneak Attack, and movement is recorded but does nothing.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trait {
+    /// Advantage on attacks while an ally that can act is next to the target (kobold,
+    /// wolf).
+    PackTactics,
+    /// Disengage or Hide as a bonus action (goblin). Hiding gives advantage on the next
+    /// attack.
+    NimbleEscape,
+    /// Damage that would drop it to 0 HP leaves it at 1 on a Con save (DC 5 + the
+    /// damage), unless the damage is radiant or from a critical hit (zombie).
+    UndeadFortitude,
+    /// In sunlight: disadvantage on attacks and on Perception (kobold).
+    SunlightSensitivity,
+    /// Advantage on Perception from keen sight, hearing or smell (wolf).
+    KeenSenses,
+    /// Moves toward a foe as a bonus action (orc). Recorded for front-ends only.
+    Aggressive,
+    /// Melee weapon hits deal one extra die of the weapon's damage (bugbear).
+    Brute,
+}
+
+impl Trait {
+    pub fn name(self) -> &'static str {
+        match self {
+            Trait::PackTactics => "Pack Tactics",
+            Trait::NimbleEscape => "Nimble Escape",
+            Trait::UndeadFortitude => "Undead Fortitude",
+            Trait::SunlightSensitivity => "Sunlight Sensitivity",
+            Trait::KeenSenses => "Keen Senses",
+            Trait::Aggressive => "Aggressive",
+            Trait::Brute => "Brute",
+        }
+    }
+}
+
+impl fmt::Display for Trait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Trait {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::parse_name("trait", s)
+    }
+}
+
+/// The Con save DC for Undead Fortitude against `damage`.
+pub fn undead_fortitude_dc(damage: i32) -> i32 {
+    5 + damage
+}
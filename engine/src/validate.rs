@@ -0,0 +1,1112 @@
+//! Content validation: JSON Schema checks plus semantic rules the schemas can't express.
+//!
+//! Every issue carries the file it came from and a JSON pointer into the document, so
+//! `content/targets/goblin.json: /attacks/0/dice/sides: d7 is not a real die` is enough to
+//! find the problem without re-reading the whole file.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{EngineError, Result};
+
+const ACTOR_SCHEMA: &str = include_str!("../../schema/actor.schema.json");
+const TARGET_SCHEMA: &str = include_str!("../../schema/target.schema.json");
+const ENCOUNTER_SCHEMA: &str = include_str!("../../schema/encounter.schema.json");
+const WEAPONS_SCHEMA: &str = include_str!("../../schema/weapons.schema.json");
+const ITEMS_SCHEMA: &str = include_str!("../../schema/items.schema.json");
+const EFFECTS_SCHEMA: &str = include_str!("../../schema/effects.schema.json");
+const CAMPAIGN_SCHEMA: &str = include_str!("../../schema/campaign.schema.json");
+
+const DAMAGE_TYPES: &[&str] = &[
+    "bludgeoning",
+    "piercing",
+    "slashing",
+    "fire",
+    "cold",
+    "lightning",
+    "acid",
+    "poison",
+    "psychic",
+    "radiant",
+    "necrotic",
+    "thunder",
+    "force",
+];
+
+const SKILLS: &[&str] = &[
+    "athletics",
+    "acrobatics",
+    "sleight_of_hand",
+    "stealth",
+    "arcana",
+    "history",
+    "investigation",
+    "nature",
+    "religion",
+    "animal_handling",
+    "insight",
+    "medicine",
+    "perception",
+    "survival",
+    "deception",
+    "intimidation",
+    "performance",
+    "persuasion",
+];
+
+/// Die sizes that exist on a real table.
+const REAL_DICE: &[u64] = &[2, 3, 4, 6, 8, 10, 12, 20, 100];
+
+/// Which schema (and rule set) a content file is checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    Actor,
+    Target,
+    Encounter,
+    Weapons,
+    Items,
+    Effects,
+    Campaign,
+}
+
+impl ContentKind {
+    /// Infer the kind from the content folder a file lives in (`content/targets/x.json` → Target).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let dir = path.parent()?.file_name()?.to_str()?;
+        Self::from_dir_name(dir)
+    }
+
+    fn from_dir_name(dir: &str) -> Option<Self> {
+        match dir {
+            "characters" => Some(ContentKind::Actor),
+            "targets" => Some(ContentKind::Target),
+            "encounters" => Some(ContentKind::Encounter),
+            "weapons" => Some(ContentKind::Weapons),
+            "items" => Some(ContentKind::Items),
+            "effects" => Some(ContentKind::Effects),
+            "campaigns" => Some(ContentKind::Campaign),
+            _ => None,
+        }
+    }
+
+    fn schema_file(self) -> &'static str {
+        match self {
+            ContentKind::Actor => "actor.schema.json",
+            ContentKind::Target => "target.schema.json",
+            ContentKind::Encounter => "encounter.schema.json",
+            ContentKind::Weapons => "weapons.schema.json",
+            ContentKind::Items => "items.schema.json",
+            ContentKind::Effects => "effects.schema.json",
+            ContentKind::Campaign => "campaign.schema.json",
+        }
+    }
+}
+
+/// A single problem found in a content file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    pub file: String,
+    /// JSON pointer to the offending value (`""` is the document root).
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}: {}", self.file, path, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub files_checked: usize,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Validate one document held in memory. `file` is only used to label issues.
+pub fn validate_str(kind: ContentKind, file: &str, text: &str) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let value: Value = match serde_json::from_str(text.trim_start_matches('\u{feff}')) {
+        Ok(v) => v,
+        Err(e) => {
+            issues.push(ValidationIssue {
+                file: file.to_string(),
+                path: String::new(),
+                message: format!("invalid JSON: {}", e),
+            });
+            return issues;
+        }
+    };
+
+    let schemas = SchemaSet::load();
+    let mut ctx = Ctx {
+        file,
+        issues: &mut issues,
+    };
+    let doc = kind.schema_file();
+    schemas.check(&mut ctx, doc, schemas.root(doc), &value, "");
+
+    // Semantic rules assume the document has the right shape.
+    if issues.is_empty() {
+        let mut ctx = Ctx {
+            file,
+            issues: &mut issues,
+        };
+        match kind {
+            ContentKind::Actor => check_actor(&mut ctx, &value),
+            ContentKind::Target => check_stat_block(&mut ctx, &value, ""),
+            ContentKind::Encounter => check_encounter(&mut ctx, &value),
+            ContentKind::Weapons => check_weapons(&mut ctx, &value),
+            ContentKind::Items => check_items(&mut ctx, &value),
+            ContentKind::Effects => check_effects(&mut ctx, &value),
+            ContentKind::Campaign => check_campaign(&mut ctx, &value),
+        }
+    }
+    issues
+}
+
+/// Validate a single file; the kind is inferred from its folder unless given.
+pub fn validate_file(path: &Path, kind: Option<ContentKind>) -> Result<Vec<ValidationIssue>> {
+    let kind = match kind.or_else(|| ContentKind::from_path(path)) {
+        Some(k) => k,
+        None => {
+            return Err(EngineError::InvalidConfig(format!(
+                "cannot infer content kind for {} (expected it under characters/, targets/, encounters/, weapons/, items/, effects/ or campaigns/)",
+                path.display()
+            )))
+        }
+    };
+    let text = fs::read_to_string(path).map_err(|e| {
+        EngineError::not_found("content file", path.display().to_string(), e.to_string())
+    })?;
+    Ok(validate_str(kind, &path.display().to_string(), &text))
+}
+
+/// Validate every `*.json` file under the known sub-folders of a content root,
+/// plus rules that span files (e.g. two targets sharing a name).
+pub fn validate_content_dir(root: &Path) -> Result<ValidationReport> {
+    if !root.is_dir() {
+        return Err(EngineError::not_found(
+            "content directory",
+            root.display().to_string(),
+            "not a directory",
+        ));
+    }
+    let mut report = ValidationReport::default();
+    let mut target_names: HashMap<String, String> = HashMap::new();
+
+    let mut dirs: Vec<_> = fs::read_dir(root)
+        .map_err(|e| {
+            EngineError::not_found(
+                "content directory",
+                root.display().to_string(),
+                e.to_string(),
+            )
+        })?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+
+    for dir in dirs {
+        let Some(kind) = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(ContentKind::from_dir_name)
+        else {
+            continue;
+        };
+        let mut files: Vec<_> = fs::read_dir(&dir)
+            .map_err(|e| {
+                EngineError::not_found(
+                    "content directory",
+                    dir.display().to_string(),
+                    e.to_string(),
+                )
+            })?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+
+        for file in files {
+            let label = file.display().to_string();
+            let text = match fs::read_to_string(&file) {
+                Ok(t) => t,
+                Err(e) => {
+                    report.issues.push(ValidationIssue {
+                        file: label,
+                        path: String::new(),
+                        message: format!("unreadable: {}", e),
+                    });
+                    continue;
+                }
+            };
+            report.files_checked += 1;
+            let issues = validate_str(kind, &label, &text);
+            let clean = issues.is_empty();
+            report.issues.extend(issues);
+
+            if clean && kind == ContentKind::Target {
+                let name = serde_json::from_str::<Value>(text.trim_start_matches('\u{feff}'))
+                    .ok()
+                    .and_then(|v| v.get("name").and_then(Value::as_str).map(str::to_string));
+                if let Some(name) = name {
+                    let key = name.to_lowercase();
+                    if let Some(first) = target_names.get(&key) {
+                        report.issues.push(ValidationIssue {
+                            file: label,
+                            path: "/name".to_string(),
+                            message: format!(
+                                "duplicate target name `{}` (also in {})",
+                                name, first
+                            ),
+                        });
+                    } else {
+                        target_names.insert(key, label);
+                    }
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/* ---------------- JSON Schema subset ---------------- */
+
+struct Ctx<'a> {
+    file: &'a str,
+    issues: &'a mut Vec<ValidationIssue>,
+}
+
+impl Ctx<'_> {
+    fn push(&mut self, path: &str, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            file: self.file.to_string(),
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+}
+
+/// The bundled schemas, keyed by file name so cross-file `$ref`s resolve.
+struct SchemaSet {
+    docs: HashMap<&'static str, Value>,
+}
+
+impl SchemaSet {
+    fn load() -> Self {
+        let mut docs = HashMap::new();
+        for (name, text) in [
+            ("actor.schema.json", ACTOR_SCHEMA),
+            ("target.schema.json", TARGET_SCHEMA),
+            ("encounter.schema.json", ENCOUNTER_SCHEMA),
+            ("weapons.schema.json", WEAPONS_SCHEMA),
+            ("items.schema.json", ITEMS_SCHEMA),
+            ("effects.schema.json", EFFECTS_SCHEMA),
+            ("campaign.schema.json", CAMPAIGN_SCHEMA),
+        ] {
+            let value = serde_json::from_str(text).expect("bundled schema is valid JSON");
+            docs.insert(name, value);
+        }
+        Self { docs }
+    }
+
+    fn root(&self, doc: &str) -> &Value {
+        &self.docs[doc]
+    }
+
+    /// Resolve `other.schema.json#/definitions/x` or `#/$defs/x` relative to `doc`.
+    fn resolve<'s>(&'s self, doc: &'s str, reference: &str) -> Option<(&'s str, &'s Value)> {
+        let (file, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+        let doc = if file.is_empty() {
+            doc
+        } else {
+            let name = file.rsplit('/').next().unwrap_or(file);
+            let name = name.split('?').next().unwrap_or(name);
+            self.docs.get_key_value(name).map(|(k, _)| *k)?
+        };
+        self.docs[doc].pointer(pointer).map(|v| (doc, v))
+    }
+
+    fn check(&self, ctx: &mut Ctx, doc: &str, schema: &Value, value: &Value, path: &str) {
+        let Some(schema) = schema.as_object() else {
+            return;
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(doc, reference) {
+                Some((ref_doc, target)) => self.check(ctx, ref_doc, target, value, path),
+                None => ctx.push(
+                    path,
+                    format!("schema error: unresolved $ref `{}`", reference),
+                ),
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let ok = match expected {
+                Value::String(t) => type_matches(t, value),
+                Value::Array(ts) => ts
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|t| type_matches(t, value)),
+                _ => true,
+            };
+            if !ok {
+                ctx.push(
+                    path,
+                    format!(
+                        "expected {}, found {}",
+                        type_label(expected),
+                        json_type(value)
+                    ),
+                );
+                return;
+            }
+        }
+
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            if !options.contains(value) {
+                let allowed: Vec<_> = options.iter().map(Value::to_string).collect();
+                ctx.push(
+                    path,
+                    format!("{} is not one of [{}]", value, allowed.join(", ")),
+                );
+            }
+        }
+
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                ctx.push(path, format!("expected {}, found {}", expected, value));
+            }
+        }
+
+        if let Some(n) = value.as_f64() {
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    ctx.push(path, format!("{} is below minimum {}", value, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    ctx.push(path, format!("{} is above maximum {}", value, max));
+                }
+            }
+        }
+
+        if let Some(s) = value.as_str() {
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if (s.chars().count() as u64) < min {
+                    ctx.push(path, format!("string is shorter than {} characters", min));
+                }
+            }
+        }
+
+        if let Some(items) = value.as_array() {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    ctx.push(path, format!("expected at least {} item(s)", min));
+                }
+            }
+            if schema.get("uniqueItems").and_then(Value::as_bool) == Some(true) {
+                for (i, item) in items.iter().enumerate() {
+                    if items[..i].contains(item) {
+                        ctx.push(
+                            &format!("{}/{}", path, i),
+                            format!("duplicate item {}", item),
+                        );
+                    }
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    self.check(ctx, doc, item_schema, item, &format!("{}/{}", path, i));
+                }
+            }
+        }
+
+        if let Some(obj) = value.as_object() {
+            let props = schema.get("properties").and_then(Value::as_object);
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !obj.contains_key(key) {
+                        ctx.push(path, format!("missing required property `{}`", key));
+                    }
+                }
+            }
+            for (key, child) in obj {
+                let child_path = format!("{}/{}", path, escape_pointer(key));
+                match props.and_then(|p| p.get(key)) {
+                    Some(child_schema) => self.check(ctx, doc, child_schema, child, &child_path),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            ctx.push(&child_path, format!("unknown property `{}`", key))
+                        }
+                        Some(extra @ Value::Object(_)) => {
+                            self.check(ctx, doc, extra, child, &child_path)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn type_matches(t: &str, value: &Value) -> bool {
+    match t {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_label(expected: &Value) -> String {
+    match expected {
+        Value::String(s) => s.clone(),
+        Value::Array(ts) => ts
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.to_string(),
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/* ---------------- semantic rules ---------------- */
+
+fn check_actor(ctx: &mut Ctx, actor: &Value) {
+    let class = actor.get("class").and_then(Value::as_str);
+    if let (Some(sub), Some(class)) = (actor.get("subclass").and_then(Value::as_str), class) {
+        let parent = match sub {
+            "champion" => "fighter",
+            _ => class,
+        };
+        if parent != class {
+            ctx.push(
+                "/subclass",
+                format!("`{}` is a {} subclass, not {}", sub, parent, class),
+            );
+        }
+    }
+
+    let Some(expertise) = actor.get("expertise").and_then(Value::as_array) else {
+        return;
+    };
+    let proficient: Vec<&str> = actor
+        .get("skill_proficiencies")
+        .and_then(Value::as_array)
+        .map(|list| list.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    for (i, skill) in expertise.iter().enumerate() {
+        if let Some(name) = skill.as_str() {
+            if !proficient.contains(&name) {
+                ctx.push(
+                    &format!("/expertise/{}", i),
+                    format!("expertise in `{}` needs proficiency in it", name),
+                );
+            }
+        }
+    }
+}
+
+/// Rules shared by standalone targets and encounter enemies.
+fn check_stat_block(ctx: &mut Ctx, block: &Value, path: &str) {
+    if let Some(hp) = block.get("hp").and_then(Value::as_i64) {
+        if hp <= 0 {
+            ctx.push(
+                &format!("{}/hp", path),
+                format!("hp must be positive (got {})", hp),
+            );
+        }
+    }
+
+    for field in ["resistances", "vulnerabilities", "immunities"] {
+        let Some(list) = block.get(field).and_then(Value::as_array) else {
+            continue;
+        };
+        for (i, entry) in list.iter().enumerate() {
+            let Some(name) = entry.as_str() else {
+                continue;
+            };
+            if !DAMAGE_TYPES.contains(&name) {
+                ctx.push(
+                    &format!("{}/{}/{}", path, field, i),
+                    unknown_id_message("damage type", name, DAMAGE_TYPES),
+                );
+            }
+        }
+    }
+
+    if let Some(list) = block.get("qualified_defenses").and_then(Value::as_array) {
+        for (i, q) in list.iter().enumerate() {
+            let except = q.get("except").and_then(Value::as_str);
+            if except.is_some() && except == q.get("material").and_then(Value::as_str) {
+                ctx.push(
+                    &format!("{}/qualified_defenses/{}", path, i),
+                    format!(
+                        "`except` and `material` are both `{}`, so it never applies",
+                        except.unwrap_or_default()
+                    ),
+                );
+            }
+        }
+    }
+
+    let immune: Vec<&str> = block
+        .get("condition_immunities")
+        .and_then(Value::as_array)
+        .map(|list| list.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if let Some(conditions) = block.get("conditions").and_then(Value::as_array) {
+        for (i, c) in conditions.iter().enumerate() {
+            if let Some(name) = c.as_str().filter(|n| immune.contains(n)) {
+                ctx.push(
+                    &format!("{}/conditions/{}", path, i),
+                    format!("starts {} but is immune to it", name),
+                );
+            }
+        }
+    }
+
+    if let Some(skills) = block.get("skills").and_then(Value::as_object) {
+        for name in skills.keys() {
+            if !SKILLS.contains(&name.as_str()) {
+                ctx.push(
+                    &format!("{}/skills/{}", path, escape_pointer(name)),
+                    unknown_id_message("skill", name, SKILLS),
+                );
+            }
+        }
+    }
+
+    check_monster_actions(ctx, block, path);
+
+    let Some(attacks) = block.get("attacks").and_then(Value::as_array) else {
+        return;
+    };
+    for (i, attack) in attacks.iter().enumerate() {
+        let attack_path = format!("{}/attacks/{}", path, i);
+        if let Some(dice) = attack.get("dice") {
+            check_dice(ctx, dice, &format!("{}/dice", attack_path));
+        }
+        if let Some(spec) = attack.get("apply_condition") {
+            let save_ends = spec
+                .pointer("/duration/save_ends_each_turn")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            if save_ends && spec.get("save").is_none() {
+                ctx.push(
+                    &format!("{}/apply_condition/duration/save_ends_each_turn", attack_path),
+                    "save_ends_each_turn needs a `save` to roll against; the condition would never end",
+                );
+            }
+            if let Some(duration) = spec.get("duration") {
+                check_duration(
+                    ctx,
+                    duration,
+                    &format!("{}/apply_condition/duration", attack_path),
+                );
+            }
+            check_triggers(
+                ctx,
+                spec.get("ongoing"),
+                &format!("{}/apply_condition/ongoing", attack_path),
+            );
+        }
+    }
+}
+
+/// Legendary and lair actions: attacks the stat block has, costs the pool can pay and
+/// saves that do something.
+fn check_monster_actions(ctx: &mut Ctx, block: &Value, path: &str) {
+    let attacks: Vec<&str> = block
+        .get("attacks")
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(|a| a.get("name")?.as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    let per_round = block
+        .pointer("/legendary_actions/per_round")
+        .and_then(Value::as_u64)
+        .unwrap_or(3);
+    let legendary = block
+        .pointer("/legendary_actions/actions")
+        .and_then(Value::as_array)
+        .map(|list| (list, format!("{}/legendary_actions/actions", path)));
+    let lair = block
+        .get("lair_actions")
+        .and_then(Value::as_array)
+        .map(|list| (list, format!("{}/lair_actions", path)));
+    for (list, list_path) in legendary.into_iter().chain(lair) {
+        for (i, action) in list.iter().enumerate() {
+            let action_path = format!("{}/{}", list_path, i);
+            if let Some(cost) = action.get("cost").and_then(Value::as_u64) {
+                if cost > per_round {
+                    ctx.push(
+                        &format!("{}/cost", action_path),
+                        format!(
+                            "costs {} but only {} actions refill each round",
+                            cost, per_round
+                        ),
+                    );
+                }
+            }
+            let Some(effect) = action.get("effect") else {
+                continue;
+            };
+            let effect_path = format!("{}/effect", action_path);
+            let kind = effect.get("kind").and_then(Value::as_str).unwrap_or("");
+            let fields: &[&str] = match kind {
+                "attack" => &["attack"],
+                "save" => &[
+                    "save",
+                    "dice",
+                    "bonus",
+                    "damage_type",
+                    "half_on_success",
+                    "condition",
+                ],
+                _ => &[],
+            };
+            for field in effect.as_object().into_iter().flat_map(|o| o.keys()) {
+                if !fields.is_empty() && field != "kind" && !fields.contains(&field.as_str()) {
+                    ctx.push(
+                        &format!("{}/{}", effect_path, field),
+                        format!("`{}` does not apply to `{}` actions", field, kind),
+                    );
+                }
+            }
+            match kind {
+                "attack" => match effect.get("attack").and_then(Value::as_str) {
+                    Some(name) if !attacks.contains(&name) => ctx.push(
+                        &format!("{}/attack", effect_path),
+                        unknown_id_message("attack", name, &attacks),
+                    ),
+                    Some(_) => {}
+                    None => ctx.push(&effect_path, "an `attack` action needs `attack`"),
+                },
+                "save" => {
+                    if effect.get("save").is_none() {
+                        ctx.push(&effect_path, "a `save` action needs `save`");
+                    }
+                    let dice = effect.get("dice");
+                    if dice.is_none() && effect.get("condition").is_none() {
+                        ctx.push(&effect_path, "a `save` action needs `dice` or `condition`");
+                    }
+                    if dice.is_some() && effect.get("damage_type").is_none() {
+                        ctx.push(
+                            &effect_path,
+                            "a `save` action with `dice` needs `damage_type`",
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Each start- or end-of-turn trigger has exactly the fields of its kind.
+fn check_triggers(ctx: &mut Ctx, triggers: Option<&Value>, path: &str) {
+    let Some(list) = triggers.and_then(Value::as_array) else {
+        return;
+    };
+    for (i, t) in list.iter().enumerate() {
+        let path = format!("{}/{}", path, i);
+        let kind = t.get("kind").and_then(Value::as_str).unwrap_or("");
+        let (needs, allowed): (&[&str], &[&str]) = match kind {
+            "damage" => (&["dice", "damage_type"], &["bonus"]),
+            "heal" => (&["amount"], &["suppressed_by"]),
+            _ => continue,
+        };
+        for field in needs {
+            if t.get(*field).is_none() {
+                ctx.push(&path, format!("a `{}` trigger needs `{}`", kind, field));
+            }
+        }
+        for field in ["dice", "bonus", "damage_type", "amount", "suppressed_by"] {
+            if t.get(field).is_some() && !needs.contains(&field) && !allowed.contains(&field) {
+                ctx.push(
+                    &format!("{}/{}", path, field),
+                    format!("`{}` does not apply to a `{}` trigger", field, kind),
+                );
+            }
+        }
+    }
+}
+
+/// Rules for condition and effect durations the schema can't express.
+fn check_duration(ctx: &mut Ctx, duration: &Value, path: &str) {
+    if duration.get("rounds").is_some() && duration.get("minutes").is_some() {
+        ctx.push(
+            &format!("{}/minutes", path),
+            "give `rounds` or `minutes`, not both",
+        );
+    }
+}
+
+fn check_encounter(ctx: &mut Ctx, encounter: &Value) {
+    let Some(enemies) = encounter.get("enemies").and_then(Value::as_array) else {
+        return;
+    };
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (i, enemy) in enemies.iter().enumerate() {
+        let path = format!("/enemies/{}", i);
+        check_stat_block(ctx, enemy, &path);
+        if let Some(name) = enemy.get("name").and_then(Value::as_str) {
+            if let Some(first) = seen.get(&name.to_lowercase()) {
+                ctx.push(
+                    &format!("{}/name", path),
+                    format!(
+                        "duplicate enemy name `{}` (same as /enemies/{}); logs would be ambiguous",
+                        name, first
+                    ),
+                );
+            } else {
+                seen.insert(name.to_lowercase(), i);
+            }
+        }
+    }
+}
+
+fn check_weapons(ctx: &mut Ctx, weapons: &Value) {
+    let Some(list) = weapons.as_array() else {
+        return;
+    };
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (i, weapon) in list.iter().enumerate() {
+        let path = format!("/{}", i);
+        if let Some(dice) = weapon.get("dice") {
+            check_dice(ctx, dice, &format!("{}/dice", path));
+        }
+        if let Some(dice) = weapon.get("versatile") {
+            check_dice(ctx, dice, &format!("{}/versatile", path));
+            if weapon.get("two_handed").and_then(Value::as_bool) == Some(true) {
+                ctx.push(
+                    &format!("{}/two_handed", path),
+                    "a two-handed weapon cannot also be versatile",
+                );
+            }
+        }
+        // Weapon lookup is case-insensitive, so `Longsword` shadows `longsword`.
+        if let Some(name) = weapon.get("name").and_then(Value::as_str) {
+            if let Some(first) = seen.get(&name.to_lowercase()) {
+                ctx.push(
+                    &format!("{}/name", path),
+                    format!(
+                        "duplicate weapon name `{}` (same as /{}); only the first is reachable",
+                        name, first
+                    ),
+                );
+            } else {
+                seen.insert(name.to_lowercase(), i);
+            }
+        }
+    }
+}
+
+/// Each effect kind needs its own fields; the schema only knows the union of them.
+fn check_items(ctx: &mut Ctx, items: &Value) {
+    let Some(list) = items.as_array() else {
+        return;
+    };
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (i, item) in list.iter().enumerate() {
+        let path = format!("/{}", i);
+        if let Some(id) = item.get("id").and_then(Value::as_str) {
+            if let Some(first) = seen.get(&id.to_lowercase()) {
+                ctx.push(
+                    &format!("{}/id", path),
+                    format!("duplicate item id `{}` (same as /{})", id, first),
+                );
+            } else {
+                seen.insert(id.to_lowercase(), i);
+            }
+        }
+        let Some(effect) = item.get("effect") else {
+            continue;
+        };
+        let path = format!("{}/effect", path);
+        let kind = effect.get("kind").and_then(Value::as_str).unwrap_or("");
+        let needs: &[&str] = match kind {
+            "heal" => &["dice"],
+            "resistance" => &["damage_type"],
+            "save_advantage" => &["against"],
+            "thrown" => &["dice", "damage_type"],
+            _ => &[],
+        };
+        for field in needs {
+            if effect.get(*field).is_none() {
+                ctx.push(&path, format!("a `{}` effect needs `{}`", kind, field));
+            }
+        }
+        for field in ["dice", "bonus", "damage_type", "against"] {
+            if effect.get(field).is_some()
+                && !needs.contains(&field)
+                && !(kind == "heal" && field == "bonus")
+            {
+                ctx.push(
+                    &format!("{}/{}", path, field),
+                    format!("`{}` does not apply to a `{}` effect", field, kind),
+                );
+            }
+        }
+        if let Some(dice) = effect.get("dice") {
+            check_dice(ctx, dice, &format!("{}/dice", path));
+        }
+    }
+}
+
+/// Effect ids must be unique, each modifier kind has its own fields, and dice or
+/// advantage only make sense on rolls.
+fn check_effects(ctx: &mut Ctx, effects: &Value) {
+    let Some(list) = effects.as_array() else {
+        return;
+    };
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (i, effect) in list.iter().enumerate() {
+        let path = format!("/{}", i);
+        if let Some(id) = effect.get("id").and_then(Value::as_str) {
+            if let Some(first) = seen.get(&id.to_lowercase()) {
+                ctx.push(
+                    &format!("{}/id", path),
+                    format!("duplicate effect id `{}` (same as /{})", id, first),
+                );
+            } else {
+                seen.insert(id.to_lowercase(), i);
+            }
+        }
+        let save_ends = effect
+            .pointer("/duration/save_ends_each_turn")
+            .and_then(Value::as_bool)
+            == Some(true);
+        if save_ends && effect.get("end_save").is_none() {
+            ctx.push(
+                &format!("{}/duration/save_ends_each_turn", path),
+                "save_ends_each_turn needs an `end_save`".to_string(),
+            );
+        }
+        if let Some(duration) = effect.get("duration") {
+            check_duration(ctx, duration, &format!("{}/duration", path));
+        }
+        check_triggers(ctx, effect.get("triggers"), &format!("{}/triggers", path));
+        let modifiers = effect
+            .get("modifiers")
+            .and_then(Value::as_array)
+            .map_or(&[][..], Vec::as_slice);
+        let triggers = effect.get("triggers").and_then(Value::as_array);
+        if modifiers.is_empty() && triggers.is_none_or(|t| t.is_empty()) {
+            ctx.push(&path, "an effect needs `modifiers` or `triggers`");
+        }
+        for (j, m) in modifiers.iter().enumerate() {
+            let path = format!("{}/modifiers/{}", path, j);
+            let kind = m.get("kind").and_then(Value::as_str).unwrap_or("");
+            let needs: &[&str] = match kind {
+                "bonus" => &["to", "value"],
+                "dice" => &["to", "dice"],
+                "advantage" | "disadvantage" => &["on"],
+                "resistance" => &["damage_type"],
+                _ => &[],
+            };
+            for field in needs {
+                if m.get(*field).is_none() {
+                    ctx.push(&path, format!("a `{}` modifier needs `{}`", kind, field));
+                }
+            }
+            for field in ["to", "on", "value", "dice", "subtract", "damage_type"] {
+                let allowed = needs.contains(&field) || (kind == "dice" && field == "subtract");
+                if m.get(field).is_some() && !allowed {
+                    ctx.push(
+                        &format!("{}/{}", path, field),
+                        format!("`{}` does not apply to a `{}` modifier", field, kind),
+                    );
+                }
+            }
+            let stat = m.get("to").or_else(|| m.get("on")).and_then(Value::as_str);
+            let rolled = matches!(stat, Some("attack" | "save" | "check"));
+            if matches!(kind, "advantage" | "disadvantage") && stat.is_some() && !rolled {
+                ctx.push(
+                    &format!("{}/on", path),
+                    format!("{} needs a d20 roll (attack, save or check)", kind),
+                );
+            }
+            if kind == "dice" && matches!(stat, Some("ac" | "speed")) {
+                ctx.push(
+                    &format!("{}/to", path),
+                    "dice only apply to rolls (attack, damage, save or check)".to_string(),
+                );
+            }
+            if let Some(dice) = m.get("dice") {
+                check_dice(ctx, dice, &format!("{}/dice", path));
+            }
+        }
+    }
+}
+
+/// Node ids must be unique and every link must land on one; each node kind has
+/// its own required fields.
+fn check_campaign(ctx: &mut Ctx, campaign: &Value) {
+    let Some(nodes) = campaign.get("nodes").and_then(Value::as_array) else {
+        return;
+    };
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(id) = node.get("id").and_then(Value::as_str) {
+            if let Some(first) = ids.get(id) {
+                ctx.push(
+                    &format!("/nodes/{}/id", i),
+                    format!("duplicate node id `{}` (same as /nodes/{})", id, first),
+                );
+            } else {
+                ids.insert(id, i);
+            }
+        }
+    }
+    let mut known: Vec<&str> = ids.keys().copied().collect();
+    known.sort_unstable();
+    if let Some(start) = campaign.get("start").and_then(Value::as_str) {
+        if !ids.contains_key(start) {
+            ctx.push("/start", unknown_id_message("node", start, &known));
+        }
+    }
+
+    for (i, node) in nodes.iter().enumerate() {
+        let path = format!("/nodes/{}", i);
+        let kind = node.get("kind").and_then(Value::as_str).unwrap_or("");
+        let (needs, allowed): (&[&str], &[&str]) = match kind {
+            "encounter" => (
+                &["encounter"],
+                &["encounter", "focus", "ambush", "nonlethal", "loot", "next"],
+            ),
+            "rest" => (&["rest"], &["rest", "next"]),
+            "check" => (
+                &["dc"],
+                &["skill", "ability", "dc", "passive", "success", "failure"],
+            ),
+            _ => (&[], &[]),
+        };
+        for field in needs {
+            if node.get(*field).is_none() {
+                ctx.push(&path, format!("a `{}` node needs `{}`", kind, field));
+            }
+        }
+        if let Some(fields) = node.as_object() {
+            for field in fields.keys() {
+                let common = matches!(field.as_str(), "kind" | "id" | "text");
+                if !common && !allowed.contains(&field.as_str()) {
+                    ctx.push(
+                        &format!("{}/{}", path, escape_pointer(field)),
+                        format!("`{}` does not apply to a `{}` node", field, kind),
+                    );
+                }
+            }
+        }
+        if kind == "check" && node.get("skill").is_some() == node.get("ability").is_some() {
+            ctx.push(
+                &path,
+                "a `check` node needs exactly one of `skill` or `ability`",
+            );
+        }
+        for link in ["next", "success", "failure"] {
+            if let Some(target) = node.get(link).and_then(Value::as_str) {
+                if !ids.contains_key(target) {
+                    ctx.push(
+                        &format!("{}/{}", path, link),
+                        unknown_id_message("node", target, &known),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn check_dice(ctx: &mut Ctx, dice: &Value, path: &str) {
+    if let Some(count) = dice.get("count").and_then(Value::as_u64) {
+        if count == 0 {
+            ctx.push(&format!("{}/count", path), "dice count must be at least 1");
+        }
+    }
+    if let Some(sides) = dice.get("sides").and_then(Value::as_u64) {
+        if !REAL_DICE.contains(&sides) {
+            ctx.push(
+                &format!("{}/sides", path),
+                format!("d{} is not a real die", sides),
+            );
+        }
+    }
+}
+
+fn unknown_id_message(what: &str, got: &str, known: &[&str]) -> String {
+    match closest(got, known) {
+        Some(hint) => format!("unknown {} `{}` (did you mean `{}`?)", what, got, hint),
+        None => format!("unknown {} `{}`", what, got),
+    }
+}
+
+/// Closest candidate within an edit distance of 2, for "did you mean" hints.
+fn closest<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let input = input.to_lowercase();
+    candidates
+        .iter()
+        .map(|c| (edit_distance(&input, c), *c))
+        .filter(|(d, _)| *d <= 2)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
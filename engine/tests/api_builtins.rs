@@ -1,6 +1,7 @@
 use engine::api::{
     simulate_duel, simulate_duel_many, simulate_encounter, DuelConfig, EncounterConfig,
 };
+use engine::error::EngineError;
 
 #[test]
 fn duel_with_builtins_runs() {
@@ -14,6 +15,16 @@ fn duel_with_builtins_runs() {
         enemy_conditions: vec![],
         seed: 2025,
         actor_hp: Some(12),
+        actor_temp_hp: 0,
+        character_path: None,
+        items_path: None,
+        tactics: Default::default(),
+        ambush: None,
+        initiative_advantage: false,
+        actor_effects: vec![],
+        enemy_effects: vec![],
+        crit_rule: Default::default(),
+        sunlight: false,
     };
     let res = simulate_duel(cfg).unwrap();
     assert!(res.rounds > 0);
@@ -31,6 +42,16 @@ fn duel_many_summary_makes_sense() {
         enemy_conditions: vec![],
         seed: 1,
         actor_hp: Some(12),
+        actor_temp_hp: 0,
+        character_path: None,
+        items_path: None,
+        tactics: Default::default(),
+        ambush: None,
+        initiative_advantage: false,
+        actor_effects: vec![],
+        enemy_effects: vec![],
+        crit_rule: Default::default(),
+        sunlight: false,
     };
     let stats = simulate_duel_many(cfg, 50).unwrap();
     assert_eq!(stats.samples, 50);
@@ -44,8 +65,74 @@ fn encounter_with_builtins_runs() {
         encounter_path: None,
         seed: 4242,
         actor_hp: Some(10),
+        actor_temp_hp: 0,
         actor_conditions: vec![],
+        character_path: None,
+        items_path: None,
+        tactics: Default::default(),
+        ambush: None,
+        initiative_advantage: false,
+        actor_effects: vec![],
+        enemy_effects: vec![],
+        crit_rule: Default::default(),
+        sunlight: false,
+        downed: None,
     };
     let res = simulate_encounter(cfg).unwrap();
     assert!(res.rounds > 0);
 }
+
+#[test]
+fn api_errors_carry_stable_codes() {
+    let base = DuelConfig {
+        target_id: Some("poison_goblin".into()),
+        weapons_id: Some("basic".into()),
+        target_path: None,
+        weapons_path: None,
+        weapon: "longsword".into(),
+        actor_conditions: vec![],
+        enemy_conditions: vec![],
+        seed: 1,
+        actor_hp: Some(12),
+        actor_temp_hp: 0,
+        character_path: None,
+        items_path: None,
+        tactics: Default::default(),
+        ambush: None,
+        initiative_advantage: false,
+        actor_effects: vec![],
+        enemy_effects: vec![],
+        crit_rule: Default::default(),
+        sunlight: false,
+    };
+
+    let missing = DuelConfig {
+        target_id: Some("dragon".into()),
+        ..base.clone()
+    };
+    let err = simulate_duel(missing).unwrap_err();
+    assert_eq!(err.code(), "content_not_found");
+    assert!(err.to_string().contains("dragon"));
+
+    let no_weapon = DuelConfig {
+        weapon: "spork".into(),
+        ..base.clone()
+    };
+    assert_eq!(
+        simulate_duel(no_weapon).unwrap_err().code(),
+        "content_not_found"
+    );
+
+    let no_target = DuelConfig {
+        target_id: None,
+        ..base.clone()
+    };
+    assert_eq!(
+        simulate_duel(no_target).unwrap_err().code(),
+        "invalid_config"
+    );
+
+    let err = simulate_duel_many(base, engine::api::MAX_SAMPLES + 1).unwrap_err();
+    assert!(matches!(err, EngineError::SimulationLimit(_)));
+    assert_eq!(err.code(), "simulation_limit");
+}
@@ -23,6 +23,16 @@ fn duel_api_smoke() {
         enemy_conditions: vec![],
         seed: 2025,
         actor_hp: Some(12),
+        actor_temp_hp: 0,
+        character_path: None,
+        items_path: None,
+        tactics: Default::default(),
+        ambush: None,
+        initiative_advantage: false,
+        actor_effects: vec![],
+        enemy_effects: vec![],
+        crit_rule: Default::default(),
+        sunlight: false,
     };
     let res = simulate_duel(cfg).expect("duel ran");
     assert!(res.rounds > 0);
@@ -0,0 +1,317 @@
+use std::collections::{HashMap, HashSet};
+
+use engine::api::{run_campaign, step_campaign, CampaignConfig};
+use engine::campaign::{step, Campaign, CampaignState, CampaignStatus, Loadout};
+use engine::character::{create_character, CharacterSpec};
+use engine::conditions::{ActiveCondition, ConditionKind};
+use engine::content::{
+    builtin_encounters, builtin_items, builtin_weapons, parse_campaign, parse_content,
+    parse_encounter, parse_items, parse_weapons,
+};
+use engine::life::LifeState;
+use engine::Dice;
+use engine::Skill;
+
+fn loadout() -> Loadout {
+    let weapons = parse_weapons(builtin_weapons()["basic"]).unwrap();
+    Loadout {
+        weapon: weapons
+            .into_iter()
+            .find(|w| w.name == "shortsword")
+            .unwrap(),
+        ac: 14,
+        items: parse_items(builtin_items()["basic"]).unwrap(),
+        tactics: Default::default(),
+        rest_policy: Default::default(),
+        max_rounds: 50,
+        crit_rule: Default::default(),
+    }
+}
+
+fn encounters() -> HashMap<String, engine::content::EncounterData> {
+    builtin_encounters()
+        .into_iter()
+        .map(|(id, text)| (id.to_string(), parse_encounter(text).unwrap()))
+        .collect()
+}
+
+fn rogue() -> engine::character::Character {
+    let spec: CharacterSpec = parse_content(
+        "character spec",
+        r#"{ "name": "Vex", "class": "rogue", "species": "halfling", "level": 3,
+             "inventory": [{ "item": "potion_of_healing", "quantity": 1 }] }"#,
+    )
+    .unwrap();
+    create_character(&spec, &mut Dice::from_seed(0)).unwrap()
+}
+
+fn config(seed: u64) -> CampaignConfig {
+    parse_content(
+        "campaign config",
+        &format!(r#"{{ "campaign_id": "goblin_warrens", "seed": {} }}"#, seed),
+    )
+    .unwrap()
+}
+
+#[test]
+fn links_are_checked() {
+    let bad = |json: &str| {
+        parse_campaign(json)
+            .unwrap()
+            .check()
+            .unwrap_err()
+            .to_string()
+    };
+    assert!(bad(
+        r#"{ "start": "a", "nodes": [{ "kind": "rest", "id": "a", "rest": "long", "next": "b" }] }"#
+    )
+    .contains("unknown node 'b'"));
+    assert!(
+        bad(r#"{ "start": "a", "nodes": [{ "kind": "check", "id": "a", "dc": 10 }] }"#)
+            .contains("exactly one of skill or ability")
+    );
+    assert!(
+        bad(r#"{ "start": "x", "nodes": [{ "kind": "rest", "id": "a", "rest": "long" }] }"#)
+            .contains("start node 'x'")
+    );
+}
+
+#[test]
+fn state_carries_between_nodes() {
+    let campaign: Campaign = parse_campaign(
+        r#"{ "start": "fight", "nodes": [
+            { "kind": "encounter", "id": "fight", "encounter": "goblin_ambush",
+              "loot": [{ "item": "antitoxin" }], "next": "camp" },
+            { "kind": "rest", "id": "camp", "rest": "long" }
+        ] }"#,
+    )
+    .unwrap();
+    campaign.check().unwrap();
+    let (encounters, loadout) = (encounters(), loadout());
+    let mut state = CampaignState::new(&campaign, rogue());
+    state.health.hp = 2;
+
+    let log = step(
+        &campaign,
+        &encounters,
+        &loadout,
+        &mut state,
+        &mut Dice::from_seed(7),
+    )
+    .unwrap();
+    assert!(log
+        .iter()
+        .any(|l| l.starts_with("[ITEM][Vex] drinks Potion of Healing")));
+    assert_eq!(state.status, CampaignStatus::InProgress);
+    assert_eq!(state.node.as_deref(), Some("camp"));
+    let items: Vec<_> = state
+        .character
+        .inventory
+        .iter()
+        .map(|e| e.item.as_str())
+        .collect();
+    assert_eq!(items, ["antitoxin"], "potion drunk, loot picked up");
+    let hurt = state.health.hp;
+    assert!(hurt < state.health.max_hp);
+
+    let log = step(
+        &campaign,
+        &encounters,
+        &loadout,
+        &mut state,
+        &mut Dice::from_seed(5),
+    )
+    .unwrap();
+    assert!(log.iter().any(|l| l.starts_with("[REST][Vex] long rest")));
+    assert_eq!(state.health.hp, state.health.max_hp);
+    assert_eq!(state.status, CampaignStatus::Victory);
+
+    let err = step(
+        &campaign,
+        &encounters,
+        &loadout,
+        &mut state,
+        &mut Dice::from_seed(5),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "invalid_config");
+}
+
+#[test]
+fn poison_gives_campaign_checks_disadvantage() {
+    let (encounters, loadout) = (encounters(), loadout());
+    let passive = 10 + rogue().actor.skill_mod(Skill::Perception);
+    let campaign: Campaign = parse_campaign(&format!(
+        r#"{{ "start": "look", "nodes": [
+            {{ "kind": "check", "id": "look", "skill": "perception", "dc": {},
+              "passive": true, "success": "sneak", "failure": "sneak" }},
+            {{ "kind": "check", "id": "sneak", "skill": "stealth", "dc": 10 }}
+        ] }}"#,
+        passive
+    ))
+    .unwrap();
+    let run = |poisoned: bool| {
+        let mut state = CampaignState::new(&campaign, rogue());
+        if poisoned {
+            state
+                .conditions
+                .push(ActiveCondition::indefinite(ConditionKind::Poisoned));
+        }
+        let mut dice = Dice::from_seed(1);
+        let mut log = step(&campaign, &encounters, &loadout, &mut state, &mut dice).unwrap();
+        log.extend(step(&campaign, &encounters, &loadout, &mut state, &mut dice).unwrap());
+        log
+    };
+
+    let healthy = run(false);
+    assert!(healthy.contains(&format!(
+        "[CHECK][Vex] Perception DC {}: passive={} → SUCCESS",
+        passive, passive
+    )));
+    assert!(!healthy.iter().any(|l| l.contains("disadvantage")));
+
+    let poisoned = run(true);
+    assert!(poisoned.contains(&format!(
+        "[CHECK][Vex] Perception DC {}: passive={} disadvantage (Poisoned) → FAIL",
+        passive,
+        passive - 5
+    )));
+    assert!(poisoned
+        .iter()
+        .any(|l| l.starts_with("[CHECK][Vex] Stealth DC 10: roll=")
+            && l.contains("disadvantage (Poisoned)")));
+}
+
+#[test]
+fn stepping_matches_a_full_run() {
+    let full = run_campaign(config(3)).unwrap();
+    assert_ne!(full.state.status, CampaignStatus::InProgress);
+
+    let mut cfg = config(3);
+    let mut log = Vec::new();
+    while !cfg.state.as_ref().is_some_and(|s| s.is_over()) {
+        let out = step_campaign(cfg.clone()).unwrap();
+        log.extend(out.log);
+        cfg.state = Some(out.state);
+    }
+    let stepped = cfg.state.unwrap();
+    assert_eq!(stepped.steps, full.state.steps);
+    assert_eq!(stepped.health, full.state.health);
+    assert_eq!(stepped.character.inventory, full.state.character.inventory);
+    assert_eq!(log, full.log[1..], "same log minus the campaign title");
+}
+
+#[test]
+fn stable_character_recovers_after_hours_and_rests_take_time() {
+    let campaign: Campaign = parse_campaign(
+        r#"{ "start": "camp", "nodes": [{ "kind": "rest", "id": "camp", "rest": "short" }] }"#,
+    )
+    .unwrap();
+    let mut state = CampaignState::new(&campaign, rogue());
+    state.health.hp = 0;
+    state.health.state = engine::life::LifeState::Unconscious { stable: true };
+    let log = step(
+        &campaign,
+        &HashMap::new(),
+        &loadout(),
+        &mut state,
+        &mut Dice::from_seed(3),
+    )
+    .unwrap();
+    assert!(log.iter().any(|l| l.contains("regains 1 HP and wakes")));
+    assert!((2..=5).contains(&state.hours), "1d4 hours + a short rest");
+    assert!(state.health.hp >= 1);
+}
+
+#[test]
+fn nonlethal_encounters_capture_the_enemies() {
+    let campaign: Campaign = parse_campaign(
+        r#"{ "start": "fight", "nodes": [
+            { "kind": "encounter", "id": "fight", "encounter": "goblin_ambush", "nonlethal": true }
+        ] }"#,
+    )
+    .unwrap();
+    let (encounters, loadout) = (encounters(), loadout());
+    let (log, state) = (0..20)
+        .map(|seed| {
+            let mut state = CampaignState::new(&campaign, rogue());
+            let log = step(
+                &campaign,
+                &encounters,
+                &loadout,
+                &mut state,
+                &mut Dice::from_seed(seed),
+            )
+            .unwrap();
+            (log, state)
+        })
+        .find(|(_, state)| state.status == CampaignStatus::Victory)
+        .expect("the rogue wins some fights");
+    assert!(log.iter().any(|l| l.starts_with("[CAPTURED][Vex] Goblin")));
+    assert!(!log.iter().any(|l| l.starts_with("[ENEMY]")), "{:#?}", log);
+    assert!(state.health.hp > 0);
+}
+
+#[test]
+fn a_solo_character_can_be_finished_off_or_left_for_dead() {
+    let campaign: Campaign = parse_campaign(
+        r#"{ "start": "fight", "nodes": [
+            { "kind": "encounter", "id": "fight", "encounter": "orcs" }
+        ] }"#,
+    )
+    .unwrap();
+    let orcs = |downed: &str| {
+        parse_encounter(&format!(
+            r#"{{ "name": "Orc raiders", "downed": "{}", "enemies": [
+                {{ "name": "Orc", "ac": 13, "hp": 60, "attacks": [{{ "name": "Greataxe",
+                    "to_hit": 5, "dice": {{ "count": 1, "sides": 12 }} }}] }}
+            ] }}"#,
+            downed
+        ))
+        .unwrap()
+    };
+    let loadout = loadout();
+    let mut outcomes = HashSet::new();
+    for policy in ["finish", "ignore"] {
+        let encounters = HashMap::from([("orcs".to_string(), orcs(policy))]);
+        for seed in 0..40 {
+            let mut state = CampaignState::new(&campaign, rogue());
+            let mut dice = Dice::from_seed(seed);
+            let log = step(&campaign, &encounters, &loadout, &mut state, &mut dice).unwrap();
+            let finished = log
+                .iter()
+                .any(|l| l == "[AI][Orc] goes after the downed Vex");
+            assert!(policy == "finish" || !finished);
+            if finished {
+                assert!(log.iter().any(|l| l.starts_with("[DEATHSAVE][Vex] takes")));
+                outcomes.insert((policy, "finished off"));
+            }
+            if state.status == CampaignStatus::Defeat {
+                assert_eq!(state.health.state, LifeState::Dead);
+                outcomes.insert((policy, "dead"));
+                continue;
+            }
+            if state.health.state != (LifeState::Unconscious { stable: true }) {
+                continue;
+            }
+            // Left for dead: the campaign waits at the fight while the character recovers.
+            assert_eq!(state.status, CampaignStatus::InProgress);
+            assert_eq!(state.node.as_deref(), Some("fight"));
+            assert!(log.iter().any(|l| l == "[CAMPAIGN] Vex is left for dead"));
+            let log = step(&campaign, &encounters, &loadout, &mut state, &mut dice).unwrap();
+            assert!(log.iter().any(|l| l.contains("regains 1 HP and wakes")));
+            outcomes.insert((policy, "recovered"));
+        }
+    }
+    assert!(
+        outcomes.contains(&("finish", "finished off")),
+        "{:?}",
+        outcomes
+    );
+    assert!(outcomes.contains(&("finish", "dead")), "{:?}", outcomes);
+    assert!(
+        outcomes.contains(&("ignore", "recovered")),
+        "{:?}",
+        outcomes
+    );
+}
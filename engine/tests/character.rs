@@ -0,0 +1,200 @@
+use engine::character::{
+    create_character, proficiency_bonus, roll_4d6_drop_lowest, sample_fighter, AbilityMethod,
+    Background, Character, CharacterSpec, Class, HpMethod, Species,
+};
+use engine::content::parse_content;
+use engine::features::{crit_threshold, Subclass};
+use engine::{Ability, AbilityScores, Actor, Dice, Skill};
+
+fn spec(class: Class, species: Species) -> CharacterSpec {
+    parse_content(
+        "character spec",
+        &format!(
+            r#"{{ "class": {}, "species": {} }}"#,
+            serde_json::to_string(&class).unwrap(),
+            serde_json::to_string(&species).unwrap()
+        ),
+    )
+    .unwrap()
+}
+
+#[test]
+fn proficiency_bonus_by_level() {
+    let pb: Vec<i32> = [1, 4, 5, 8, 9, 12, 13, 16, 17, 20]
+        .into_iter()
+        .map(proficiency_bonus)
+        .collect();
+    assert_eq!(pb, vec![2, 2, 3, 3, 4, 4, 5, 5, 6, 6]);
+}
+
+#[test]
+fn standard_array_fighter_with_species_bonus() {
+    let mut dice = Dice::from_seed(1);
+    let c = create_character(&spec(Class::Fighter, Species::HalfOrc), &mut dice).unwrap();
+    // Str 15+2, Con 14+1, Dex 13, Wis 12, Int 10, Cha 8
+    assert_eq!(c.actor.abilities.str_, 17);
+    assert_eq!(c.actor.abilities.con, 15);
+    assert_eq!(c.actor.abilities.dex, 13);
+    assert_eq!(c.actor.abilities.cha, 8);
+    assert_eq!(c.max_hp, 12); // d10 + 2
+    assert!(c.actor.save_proficiencies.contains(&Ability::Str));
+    // Menacing + Acolyte + two fighter picks
+    let skills = &c.actor.skill_proficiencies;
+    assert!(skills.contains(&Skill::Intimidation));
+    assert!(skills.contains(&Skill::Insight) && skills.contains(&Skill::Religion));
+    assert_eq!(skills.len(), 5);
+}
+
+#[test]
+fn point_buy_is_validated() {
+    let mut s = spec(Class::Wizard, Species::Gnome);
+    s.abilities = AbilityMethod::PointBuy {
+        scores: AbilityScores {
+            str_: 8,
+            dex: 14,
+            con: 14,
+            int_: 15,
+            wis: 12,
+            cha: 8,
+        },
+    };
+    let c = create_character(&s, &mut Dice::from_seed(0)).unwrap();
+    assert_eq!(c.actor.abilities.int_, 17);
+
+    s.abilities = AbilityMethod::PointBuy {
+        scores: AbilityScores {
+            str_: 15,
+            dex: 15,
+            con: 15,
+            int_: 15,
+            wis: 8,
+            cha: 8,
+        },
+    };
+    let err = create_character(&s, &mut Dice::from_seed(0)).unwrap_err();
+    assert_eq!(err.code(), "invalid_config");
+    assert!(err.to_string().contains("36 points"));
+}
+
+#[test]
+fn rolled_scores_drop_lowest_die() {
+    let mut dice = Dice::from_scripted(vec![1, 6, 5, 4]);
+    assert_eq!(roll_4d6_drop_lowest(&mut dice), 15);
+
+    let mut s = spec(Class::Rogue, Species::Human);
+    s.abilities = AbilityMethod::Roll;
+    let a = create_character(&s, &mut Dice::from_seed(7)).unwrap();
+    let b = create_character(&s, &mut Dice::from_seed(7)).unwrap();
+    assert_eq!(a.actor.abilities.dex, b.actor.abilities.dex);
+    // Highest roll goes to Dex (rogue priority), then +1 for human.
+    for ab in Ability::ALL {
+        assert!(a.actor.abilities.dex >= a.actor.abilities.get(ab));
+    }
+}
+
+#[test]
+fn skill_choices_must_come_from_class_list() {
+    let mut s = spec(Class::Cleric, Species::Dwarf);
+    s.skills = vec![Skill::Medicine, Skill::Stealth];
+    let err = create_character(&s, &mut Dice::from_seed(0)).unwrap_err();
+    assert!(err.to_string().contains("Stealth"));
+
+    s.skills = vec![Skill::Medicine, Skill::Insight];
+    let err = create_character(&s, &mut Dice::from_seed(0)).unwrap_err();
+    assert!(err.to_string().contains("already proficient"));
+
+    s.background = Background::Custom;
+    s.background_skills = vec![Skill::Stealth, Skill::Perception];
+    let c = create_character(&s, &mut Dice::from_seed(0)).unwrap();
+    assert!(c.actor.skill_proficiencies.contains(&Skill::Stealth));
+}
+
+#[test]
+fn rogues_gain_expertise() {
+    let mut s = spec(Class::Rogue, Species::Human);
+    s.skills = vec![
+        Skill::Stealth,
+        Skill::Perception,
+        Skill::Acrobatics,
+        Skill::Deception,
+    ];
+    s.expertise = vec![Skill::Perception];
+    let mut c = create_character(&s, &mut Dice::from_seed(0)).unwrap();
+    let expert = |c: &Character| {
+        let mut v: Vec<Skill> = c.actor.expertise.iter().copied().collect();
+        v.sort_by_key(|s| format!("{:?}", s));
+        v
+    };
+    assert_eq!(expert(&c), [Skill::Perception, Skill::Stealth]);
+    let dex = c.actor.ability_mod(Ability::Dex);
+    assert_eq!(c.actor.skill_mod(Skill::Stealth), dex + 4);
+    assert_eq!(c.actor.skill_mod(Skill::Acrobatics), dex + 2);
+
+    for _ in 1..6 {
+        c.level_up(HpMethod::Average, &mut Dice::from_seed(0))
+            .unwrap();
+    }
+    assert_eq!(c.actor.expertise.len(), 4);
+    assert_eq!(c.actor.skill_mod(Skill::Acrobatics), dex + 6);
+
+    s.expertise = vec![Skill::Arcana];
+    let err = create_character(&s, &mut Dice::from_seed(0)).unwrap_err();
+    assert!(err.to_string().contains("needs proficiency"));
+    s.expertise = vec![Skill::Stealth, Skill::Perception, Skill::Deception];
+    assert!(create_character(&s, &mut Dice::from_seed(0)).is_err());
+    let mut fighter = spec(Class::Fighter, Species::Human);
+    fighter.expertise = vec![Skill::Athletics];
+    assert!(create_character(&fighter, &mut Dice::from_seed(0)).is_err());
+}
+
+#[test]
+fn level_up_average_and_roll() {
+    let mut s = spec(Class::Fighter, Species::Human);
+    s.level = 5;
+    let c = create_character(&s, &mut Dice::from_seed(0)).unwrap();
+    // Con 14+1 → +2: 12 at 1st, then 6+2 per level
+    assert_eq!(c.max_hp, 12 + 4 * 8);
+    assert_eq!(c.actor.proficiency_bonus, 3);
+
+    let mut c = sample_fighter();
+    let mut dice = Dice::from_scripted(vec![1]);
+    assert_eq!(c.level_up(HpMethod::Roll, &mut dice).unwrap(), 3);
+    assert_eq!((c.level, c.max_hp), (2, 15));
+
+    c.level = 20;
+    assert!(c.level_up(HpMethod::Average, &mut dice).is_err());
+}
+
+#[test]
+fn champions_crit_on_19_from_third_level() {
+    let mut s = spec(Class::Fighter, Species::Human);
+    s.subclass = Some(Subclass::Champion);
+    let c = create_character(&s, &mut Dice::from_seed(0)).unwrap();
+    assert_eq!(c.active_subclass(), None);
+    assert_eq!(crit_threshold(c.active_subclass(), c.level), 20);
+
+    s.level = 3;
+    let c = create_character(&s, &mut Dice::from_seed(0)).unwrap();
+    assert_eq!(c.active_subclass(), Some(Subclass::Champion));
+    assert_eq!(crit_threshold(c.active_subclass(), c.level), 19);
+    assert_eq!(crit_threshold(c.active_subclass(), 15), 18);
+
+    let mut s = spec(Class::Rogue, Species::Human);
+    s.subclass = Some(Subclass::Champion);
+    let err = create_character(&s, &mut Dice::from_seed(0)).unwrap_err();
+    assert!(err.to_string().contains("Fighter subclass"));
+}
+
+#[test]
+fn character_json_loads_as_actor() {
+    let mut s = spec(Class::Paladin, Species::Dragonborn);
+    s.name = "Aster".into();
+    let c = create_character(&s, &mut Dice::from_seed(3)).unwrap();
+    let json = serde_json::to_string(&c).unwrap();
+
+    let actor: Actor = serde_json::from_str(&json).unwrap();
+    assert_eq!(actor.abilities.str_, c.actor.abilities.str_);
+    let back: Character = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.name, "Aster");
+    assert_eq!(back.class, Class::Paladin);
+}
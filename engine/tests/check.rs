@@ -1,4 +1,9 @@
-use engine::{ability_mod, check, AdMode, CheckInput, Dice};
+mod common;
+
+use engine::character::sample_fighter;
+use engine::checks::{contest, grapple_defense, group_check, passive_score, Contestant};
+use engine::conditions::{ActiveCondition, ConditionKind, Vantage};
+use engine::{ability_mod, check, Ability, AdMode, CheckInput, Dice, Skill};
 
 #[test]
 fn ability_mod_rounds_down() {
@@ -22,3 +27,73 @@ fn deterministic_check_total_consistent() {
     );
     assert_eq!(res.passed, res.total >= res.dc);
 }
+
+#[test]
+fn passive_scores_shift_by_five() {
+    assert_eq!(passive_score(3, AdMode::Normal), 13);
+    assert_eq!(passive_score(3, AdMode::Advantage), 18);
+    assert_eq!(passive_score(3, AdMode::Disadvantage), 8);
+}
+
+#[test]
+fn contests_and_group_checks() {
+    // Initiator rolls 12 (+3 = 15), defender 13 (+2 = 15): a tie, so the defender holds.
+    let mut dice = Dice::from_scripted(vec![12, 13]);
+    let res = contest(
+        &mut dice,
+        Contestant::new(3, AdMode::Normal),
+        Contestant::new(2, AdMode::Normal),
+    );
+    assert_eq!((res.initiator_total, res.defender_total), (15, 15));
+    assert!(!res.initiator_wins);
+
+    let mut dice = Dice::from_scripted(vec![4, 18, 9, 2]);
+    let members = [Contestant::new(1, AdMode::Normal); 4];
+    let res = group_check(&mut dice, 10, &members);
+    assert_eq!(res.successes, 2);
+    assert!(res.passed, "half the group is enough");
+    let res = group_check(&mut Dice::from_scripted(vec![1, 1, 20]), 10, &members[..3]);
+    assert!(!res.passed);
+
+    assert_eq!(
+        grapple_defense(|s| if s == Skill::Acrobatics { 5 } else { 1 }),
+        Skill::Acrobatics
+    );
+    assert_eq!(grapple_defense(|_| 2), Skill::Athletics);
+}
+
+#[test]
+fn poisoned_creatures_have_disadvantage_on_checks() {
+    let actor = sample_fighter().actor;
+    let poisoned = [ActiveCondition::indefinite(ConditionKind::Poisoned)];
+    let mut dice = Dice::from_scripted(vec![18, 4]);
+    let res = actor.skill_check_with_conditions(
+        &mut dice,
+        Skill::Athletics,
+        &poisoned,
+        AdMode::Normal,
+        10,
+    );
+    assert_eq!(res.mode, AdMode::Disadvantage);
+    assert_eq!(res.result.roll, 4);
+    assert_eq!(res.vantage.to_string(), "disadvantage (Poisoned)");
+
+    let mut dice = Dice::from_scripted(vec![18]);
+    let res = actor.ability_check_with_conditions(
+        &mut dice,
+        Ability::Str,
+        &poisoned,
+        AdMode::Advantage,
+        10,
+    );
+    assert_eq!(res.mode, AdMode::Normal);
+    assert_eq!(res.vantage.net(), Vantage::Normal);
+    assert_eq!(res.result.roll, 18);
+
+    let watcher = |conds: Vec<ActiveCondition>| {
+        let mut c = common::fighter();
+        c.conditions = conds;
+        c.passive_perception()
+    };
+    assert_eq!(watcher(poisoned.to_vec()), watcher(Vec::new()) - 5);
+}
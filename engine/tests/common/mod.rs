@@ -0,0 +1,52 @@
+//! Fixtures shared by the integration tests. Each test file uses only some of them.
+#![allow(dead_code)]
+
+use engine::character::{create_character, sample_fighter, Character, CharacterSpec};
+use engine::combat::{weapon_ability, AttackProfile, Combatant};
+use engine::content::{parse_content, parse_target, parse_weapons};
+use engine::Dice;
+
+/// A stat-block sword: `to_hit` to hit, 1d`sides` slashing.
+pub fn sword(to_hit: i32, sides: u8) -> AttackProfile {
+    let block = parse_target(&format!(
+        r#"{{ "name": "x", "ac": 10, "hp": 1,
+            "attacks": [{{ "name": "Sword", "to_hit": {}, "dice": {{ "count": 1, "sides": {} }} }}] }}"#,
+        to_hit, sides
+    ))
+    .unwrap();
+    AttackProfile::from_target_attack(&block.attacks[0])
+}
+
+/// The sample fighter at AC 16 with `attack`.
+pub fn fighter_with(attack: AttackProfile) -> Combatant {
+    Combatant::from_character(&sample_fighter(), 16, attack)
+}
+
+/// The sample fighter at AC 16 with a +5 1d8 sword.
+pub fn fighter() -> Combatant {
+    fighter_with(sword(5, 8))
+}
+
+/// A character named Hero built from a spec, with an optional fighting style.
+pub fn character(class: &str, species: &str, level: u8, style: Option<&str>) -> Character {
+    let style = style.map_or(String::new(), |s| format!(r#", "fighting_style": "{}""#, s));
+    let spec: CharacterSpec = parse_content(
+        "character spec",
+        &format!(
+            r#"{{ "name": "Hero", "class": "{}", "species": "{}", "level": {}{} }}"#,
+            class, species, level, style
+        ),
+    )
+    .unwrap();
+    create_character(&spec, &mut Dice::from_seed(0)).unwrap()
+}
+
+/// That character at AC 16, wielding `weapon` from the basic weapon list.
+pub fn hero(class: &str, species: &str, level: u8, weapon: &str) -> Combatant {
+    let c = character(class, species, level, None);
+    let weapons =
+        parse_weapons(include_str!("../../../content/weapons/basic.json")).expect("weapons parse");
+    let w = weapons.iter().find(|w| w.name == weapon).unwrap();
+    let attack = AttackProfile::from_weapon(&c.actor, w, weapon_ability(&c.actor, w), true, false);
+    Combatant::from_character(&c, 16, attack)
+}
@@ -1,8 +1,14 @@
+use std::collections::HashSet;
+
 use engine::conditions::{
-    maybe_apply_on_hit_condition, process_turn_boundary, vantage_from_conditions, ActiveCondition,
-    AttackStyle, ConditionDuration, ConditionKind, ConditionSpec, DurationPhase, TurnBoundary,
+    apply_condition, end_conditions_at_rest, maybe_apply_on_hit_condition, process_source_boundary,
+    process_turn_boundary, vantage_from_conditions, ActiveCondition, Applied, AttackStyle,
+    ConditionDuration, ConditionKind, ConditionSource, ConditionSpec, DurationPhase, TurnBoundary,
     Vantage,
 };
+use engine::content::parse_target;
+use engine::features::RestKind;
+use engine::saves::{SaveContext, SaveOutcome};
 use engine::{Ability, SavingThrow};
 
 #[test]
@@ -13,6 +19,11 @@ fn poisoned_gives_attacker_disadvantage() {
         end_phase: None,
         end_save: None,
         pending_one_turn: false,
+        magical: false,
+        source: None,
+        countdown: Default::default(),
+        escape_dc: None,
+        ongoing: Vec::new(),
     }];
     let target: Vec<ActiveCondition> = vec![];
     assert_eq!(
@@ -30,6 +41,11 @@ fn prone_interactions_melee_and_ranged() {
         end_phase: None,
         end_save: None,
         pending_one_turn: false,
+        magical: false,
+        source: None,
+        countdown: Default::default(),
+        escape_dc: None,
+        ongoing: Vec::new(),
     }];
 
     assert_eq!(
@@ -50,6 +66,11 @@ fn poisoned_attacker_vs_restrained_target_cancels() {
         end_phase: None,
         end_save: None,
         pending_one_turn: false,
+        magical: false,
+        source: None,
+        countdown: Default::default(),
+        escape_dc: None,
+        ongoing: Vec::new(),
     }];
     let target = vec![ActiveCondition {
         kind: ConditionKind::Restrained,
@@ -57,6 +78,11 @@ fn poisoned_attacker_vs_restrained_target_cancels() {
         end_phase: None,
         end_save: None,
         pending_one_turn: false,
+        magical: false,
+        source: None,
+        countdown: Default::default(),
+        escape_dc: None,
+        ongoing: Vec::new(),
     }];
 
     assert_eq!(
@@ -73,6 +99,11 @@ fn one_turn_duration_expires_on_next_boundary() {
         end_phase: Some(DurationPhase::StartOfTurn),
         end_save: None,
         pending_one_turn: true,
+        magical: false,
+        source: None,
+        countdown: Default::default(),
+        escape_dc: None,
+        ongoing: Vec::new(),
     }];
 
     let mut logs = Vec::new();
@@ -82,7 +113,7 @@ fn one_turn_duration_expires_on_next_boundary() {
         TurnBoundary::StartOfTurn,
         "Tester",
         &mut conds,
-        |_ability, _dc| (0, 0),
+        |ctx| SaveOutcome::new(0, 0, ctx.dc),
         log,
     );
 
@@ -104,6 +135,11 @@ fn prone_ranged_disadvantage_cancels_with_other_advantage() {
             end_phase: None,
             end_save: None,
             pending_one_turn: false,
+            magical: false,
+            source: None,
+            countdown: Default::default(),
+            escape_dc: None,
+            ongoing: Vec::new(),
         },
         ActiveCondition {
             kind: ConditionKind::Restrained,
@@ -111,6 +147,11 @@ fn prone_ranged_disadvantage_cancels_with_other_advantage() {
             end_phase: None,
             end_save: None,
             pending_one_turn: false,
+            magical: false,
+            source: None,
+            countdown: Default::default(),
+            escape_dc: None,
+            ongoing: Vec::new(),
         },
     ];
 
@@ -130,16 +171,21 @@ fn application_save_uses_target_ability() {
             dc: 12,
         }),
         duration: ConditionDuration::default(),
+        magical: false,
+        escape_dc: None,
+        ongoing: Vec::new(),
     };
 
     let mut captured = Vec::new();
     maybe_apply_on_hit_condition(
         "Target",
         &mut conds,
+        &HashSet::new(),
         &spec,
-        |ability, dc| {
-            captured.push((ability, dc));
-            (1, 1)
+        None,
+        |ctx| {
+            captured.push((ctx.ability, ctx.dc));
+            SaveOutcome::new(1, 1, ctx.dc)
         },
         |_msg| {},
     );
@@ -158,11 +204,16 @@ fn save_ends_each_turn_drops_condition_when_dc_zero() {
             dc: 0,
         }),
         pending_one_turn: false,
+        magical: false,
+        source: None,
+        countdown: Default::default(),
+        escape_dc: None,
+        ongoing: Vec::new(),
     }];
 
     let mut logs = Vec::new();
     let log = |msg: String| logs.push(msg);
-    let save = |_ability: Ability, _dc: i32| (15, 15);
+    let save = |ctx: &SaveContext| SaveOutcome::new(15, 15, ctx.dc);
 
     process_turn_boundary(TurnBoundary::EndOfTurn, "Tester", &mut conds, save, log);
 
@@ -171,3 +222,220 @@ fn save_ends_each_turn_drops_condition_when_dc_zero() {
         "Condition should be removed on successful end-of-turn save"
     );
 }
+
+#[test]
+fn prone_attackers_have_disadvantage() {
+    let attacker = vec![ActiveCondition::indefinite(ConditionKind::Prone)];
+    assert_eq!(
+        vantage_from_conditions(&attacker, &[], AttackStyle::Melee),
+        Vantage::Disadvantage
+    );
+    let target = vec![ActiveCondition::indefinite(ConditionKind::Grappled)];
+    assert_eq!(
+        vantage_from_conditions(&[], &target, AttackStyle::Melee),
+        Vantage::Normal
+    );
+}
+
+fn poison_spec() -> ConditionSpec {
+    ConditionSpec {
+        kind: ConditionKind::Poisoned,
+        save: Some(SavingThrow {
+            ability: Ability::Con,
+            dc: 12,
+        }),
+        duration: ConditionDuration {
+            until: Some(DurationPhase::EndOfTurn),
+            save_ends_each_turn: false,
+            ..Default::default()
+        },
+        magical: false,
+        escape_dc: None,
+        ongoing: Vec::new(),
+    }
+}
+
+#[test]
+fn reapplying_a_condition_refreshes_it_instead_of_stacking() {
+    let mut conds = Vec::new();
+    let mut log = Vec::new();
+    let apply = |conds: &mut Vec<ActiveCondition>, who: &str, log: &mut Vec<String>| {
+        maybe_apply_on_hit_condition(
+            "Hero",
+            conds,
+            &HashSet::new(),
+            &poison_spec(),
+            Some(ConditionSource::new(1, who, "Bite")),
+            |ctx| SaveOutcome::new(1, 1, ctx.dc),
+            |m| log.push(m),
+        )
+    };
+    assert_eq!(apply(&mut conds, "Goblin", &mut log), Applied::Gained);
+    conds[0].pending_one_turn = false;
+    assert_eq!(apply(&mut conds, "Wolf", &mut log), Applied::Refreshed);
+    assert_eq!(conds.len(), 1);
+    assert!(conds[0].pending_one_turn);
+    assert_eq!(conds[0].source.as_ref().unwrap().creature, "Wolf");
+    assert!(log.contains(&"[COND][Hero] gains Poisoned (Goblin's Bite)".to_string()));
+    assert!(log.contains(&"[COND][Hero] Poisoned refreshed (Wolf's Bite)".to_string()));
+
+    let mut lasting = vec![ActiveCondition::indefinite(ConditionKind::Poisoned)];
+    assert_eq!(apply(&mut lasting, "Wolf", &mut log), Applied::Kept);
+    assert!(lasting[0].is_indefinite());
+}
+
+#[test]
+fn immune_creatures_neither_save_nor_gain_the_condition() {
+    let skeleton = parse_target(include_str!("../../content/targets/skeleton.json")).unwrap();
+    assert!(skeleton
+        .condition_immunities
+        .contains(&ConditionKind::Poisoned));
+
+    let mut conds = Vec::new();
+    let mut saves = 0;
+    let mut log = Vec::new();
+    let applied = maybe_apply_on_hit_condition(
+        "Skeleton",
+        &mut conds,
+        &skeleton.condition_immunities,
+        &poison_spec(),
+        None,
+        |ctx| {
+            saves += 1;
+            SaveOutcome::new(1, 1, ctx.dc)
+        },
+        |m| log.push(m),
+    );
+    assert_eq!(applied, Applied::Immune);
+    assert_eq!(saves, 0);
+    assert!(conds.is_empty());
+    assert_eq!(log, vec!["[COND][Skeleton] is immune to Poisoned"]);
+
+    let prone = ActiveCondition::indefinite(ConditionKind::Prone);
+    assert_eq!(
+        apply_condition(
+            "Skeleton",
+            &mut conds,
+            &skeleton.condition_immunities,
+            prone,
+            |_| {}
+        ),
+        Applied::Gained
+    );
+}
+
+fn lasting(kind: ConditionKind, duration: ConditionDuration) -> ActiveCondition {
+    ActiveCondition::from_spec_for_application(&ConditionSpec {
+        kind,
+        save: None,
+        duration,
+        magical: false,
+        escape_dc: None,
+        ongoing: Vec::new(),
+    })
+    .with_source(ConditionSource::new(1, "Ghoul", "Claws"))
+}
+
+#[test]
+fn counted_durations_run_out_at_the_end_of_the_holders_turns() {
+    let minute = lasting(
+        ConditionKind::Paralyzed,
+        ConditionDuration {
+            minutes: Some(1),
+            ..Default::default()
+        },
+    );
+    assert_eq!(minute.countdown.rounds_left, Some(10));
+    assert!(!minute.is_indefinite());
+
+    let mut conds = vec![lasting(
+        ConditionKind::Restrained,
+        ConditionDuration {
+            rounds: Some(2),
+            ..Default::default()
+        },
+    )];
+    let mut log = Vec::new();
+    let end_turn = |conds: &mut Vec<ActiveCondition>, log: &mut Vec<String>| {
+        process_turn_boundary(
+            TurnBoundary::StartOfTurn,
+            "Hero",
+            conds,
+            |ctx| SaveOutcome::new(1, 1, ctx.dc),
+            |m| log.push(m),
+        );
+        process_turn_boundary(
+            TurnBoundary::EndOfTurn,
+            "Hero",
+            conds,
+            |ctx| SaveOutcome::new(1, 1, ctx.dc),
+            |m| log.push(m),
+        );
+    };
+    end_turn(&mut conds, &mut log);
+    assert_eq!(conds[0].countdown.rounds_left, Some(1));
+    end_turn(&mut conds, &mut log);
+    assert!(conds.is_empty());
+    assert_eq!(log, vec!["[COND][Hero] Restrained runs out"]);
+}
+
+#[test]
+fn until_the_sources_next_turn_ends_on_the_sources_boundary() {
+    let until_end = ConditionDuration {
+        until_source: Some(DurationPhase::EndOfTurn),
+        ..Default::default()
+    };
+    let mut conds = vec![lasting(ConditionKind::Prone, until_end)];
+    let mut log = Vec::new();
+
+    // Another creature's turn, even one with the same name, and the end of the turn it
+    // was applied on, don't count.
+    let mut boundary = |when, source, name, conds: &mut Vec<ActiveCondition>| {
+        process_source_boundary(when, source, name, "Hero", conds, |m| log.push(m))
+    };
+    boundary(TurnBoundary::EndOfTurn, 2, "Wolf", &mut conds);
+    boundary(TurnBoundary::EndOfTurn, 1, "Ghoul", &mut conds);
+    assert_eq!(conds.len(), 1);
+    boundary(TurnBoundary::StartOfTurn, 1, "Ghoul", &mut conds);
+    boundary(TurnBoundary::EndOfTurn, 3, "Ghoul", &mut conds);
+    assert_eq!(conds.len(), 1);
+    boundary(TurnBoundary::EndOfTurn, 1, "Ghoul", &mut conds);
+    assert!(conds.is_empty());
+    assert_eq!(log, vec!["[COND][Hero] Prone ends at Ghoul's EndOfTurn"]);
+}
+
+#[test]
+fn rests_end_counted_conditions_and_long_rest_ones_only_at_a_long_rest() {
+    let mut conds = vec![
+        lasting(
+            ConditionKind::Paralyzed,
+            ConditionDuration {
+                minutes: Some(1),
+                ..Default::default()
+            },
+        ),
+        lasting(
+            ConditionKind::Poisoned,
+            ConditionDuration {
+                until_rest: Some(RestKind::Long),
+                ..Default::default()
+            },
+        ),
+        ActiveCondition::indefinite(ConditionKind::Prone),
+    ];
+    let mut log = Vec::new();
+    end_conditions_at_rest("Hero", &mut conds, RestKind::Short, |m| log.push(m));
+    assert_eq!(conds.len(), 2);
+    end_conditions_at_rest("Hero", &mut conds, RestKind::Long, |m| log.push(m));
+    assert_eq!(
+        conds,
+        vec![ActiveCondition::indefinite(ConditionKind::Prone)]
+    );
+    assert_eq!(
+        log,
+        vec![
+            "[COND][Hero] Paralyzed ends with the rest",
+            "[COND][Hero] Poisoned ends with the rest",
+        ]
+    );
+}
@@ -0,0 +1,82 @@
+use engine::api::DuelConfig;
+use engine::conditions::ConditionKind;
+use engine::content::{
+    builtin_campaigns, builtin_encounters, builtin_items, builtin_targets, builtin_weapons,
+    parse_campaign, parse_content, parse_encounter, parse_items, parse_target, parse_weapons,
+    Focus,
+};
+use engine::DamageType;
+
+#[test]
+fn builtins_parse_strictly() {
+    for text in builtin_targets().values() {
+        parse_target(text).unwrap();
+    }
+    for text in builtin_weapons().values() {
+        parse_weapons(text).unwrap();
+    }
+    for text in builtin_encounters().values() {
+        parse_encounter(text).unwrap();
+    }
+    let ambush = parse_encounter(builtin_encounters()["goblin_ambush"]).unwrap();
+    assert_eq!(ambush.focus, Focus::First);
+    for text in builtin_items().values() {
+        parse_items(text).unwrap();
+    }
+    for text in builtin_campaigns().values() {
+        parse_campaign(text).unwrap().check().unwrap();
+    }
+}
+
+#[test]
+fn damage_types_are_typed_sets() {
+    let json = r#"{ "name": "Skeleton", "ac": 13, "hp": 13,
+        "vulnerabilities": ["bludgeoning"], "immunities": ["poison", "poison"] }"#;
+    let t = parse_target(json).unwrap();
+    assert!(t.vulnerabilities.contains(&DamageType::Bludgeoning));
+    assert_eq!(t.immunities.len(), 1);
+}
+
+#[test]
+fn typo_in_damage_type_names_the_field() {
+    let json = r#"{ "name": "Zombie", "ac": 8, "hp": 22, "resistances": ["poison", "slashng"] }"#;
+    let err = parse_target(json).unwrap_err();
+    assert_eq!(err.what, "target");
+    assert_eq!(err.field, "resistances[1]");
+    assert!(err.message.contains("slashng"));
+    assert_eq!(err.line, 1);
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    let json = r#"{ "name": "Orc", "ac": 13, "hp": 15,
+        "attacks": [{ "name": "Greataxe", "to_hit": 5, "dice": { "count": 1, "sides": 12 }, "dmg_type": "slashing" }] }"#;
+    let err = parse_target(json).unwrap_err();
+    assert!(
+        err.field.starts_with("attacks[0]"),
+        "field was {}",
+        err.field
+    );
+    assert!(err.message.contains("dmg_type"));
+}
+
+#[test]
+fn config_conditions_are_typed() {
+    let ok: DuelConfig = parse_content(
+        "duel config",
+        r#"{ "weapon": "longsword", "actor_conditions": ["poisoned"] }"#,
+    )
+    .unwrap();
+    assert_eq!(ok.actor_conditions, vec![ConditionKind::Poisoned]);
+
+    let err = parse_content::<DuelConfig>(
+        "duel config",
+        r#"{ "weapon": "longsword", "enemy_conditions": ["prone", "stunnd"] }"#,
+    )
+    .unwrap_err();
+    assert_eq!(err.field, "enemy_conditions[1]");
+
+    let err = parse_content::<DuelConfig>("duel config", r#"{ "weapon": "longsword", "sed": 5 }"#)
+        .unwrap_err();
+    assert!(err.message.contains("sed"));
+}
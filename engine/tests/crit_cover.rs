@@ -1,4 +1,10 @@
-use engine::{attack, damage, AdMode, Cover, DamageDice, Dice};
+use engine::combat::{run_combat, AttackProfile, CombatOptions, Combatant, Side};
+use engine::conditions::{ActiveCondition, ConditionKind};
+use engine::content::parse_target;
+use engine::{
+    attack, attack_with_crit_range, damage, damage_with_rule, AdMode, Cover, CritRule, DamageDice,
+    Dice,
+};
 
 #[test]
 fn crit_on_kept_20_without_advantage() {
@@ -47,3 +53,102 @@ fn crit_damage_doubles_dice_only() {
     let crit = damage(&mut crit_dice, dd, 3, true);
     assert_eq!(crit - 3, 9);
 }
+
+#[test]
+fn expanded_crit_range_crits_and_hits_on_19() {
+    let mut dice = Dice::from_scripted(vec![19]);
+    let res = attack_with_crit_range(&mut dice, AdMode::Normal, 0, 25, 19);
+    assert!(res.is_crit);
+    assert!(res.hit);
+
+    let mut dice = Dice::from_scripted(vec![19]);
+    assert!(!attack(&mut dice, AdMode::Normal, 0, 25).is_crit);
+}
+
+#[test]
+fn crit_rule_variants() {
+    let dd = DamageDice::new(1, 8);
+    let mut dice = Dice::from_scripted(vec![3]);
+    assert_eq!(
+        damage_with_rule(&mut dice, dd, 2, Some(CritRule::MaxPlusRoll)),
+        8 + 3 + 2
+    );
+    let mut dice = Dice::from_scripted(vec![3]);
+    assert_eq!(
+        damage_with_rule(&mut dice, dd, 2, Some(CritRule::DoubleTotal)),
+        (3 + 2) * 2
+    );
+    assert_eq!(
+        "max_plus_roll".parse::<CritRule>(),
+        Ok(CritRule::MaxPlusRoll)
+    );
+}
+
+fn brute(extra: &str) -> Combatant {
+    let mut c = Combatant::from_target(
+        &parse_target(&format!(
+            r#"{{ "name": "Brute", "ac": 5, "hp": 200, {extra}
+                "attacks": [{{ "name": "Club", "to_hit": 20, "dice": {{ "count": 1, "sides": 4 }} }}] }}"#
+        ))
+        .unwrap(),
+    );
+    c.side = Side::Party;
+    c
+}
+
+fn paralyzed(extra: &str) -> Combatant {
+    let mut c = brute(extra);
+    c.name = "Statue".to_string();
+    c.side = Side::Enemies;
+    c.conditions
+        .push(ActiveCondition::indefinite(ConditionKind::Paralyzed));
+    c
+}
+
+#[test]
+fn melee_hits_on_paralyzed_targets_crit_unless_crit_immune() {
+    let opts = CombatOptions {
+        max_rounds: 3,
+        ..CombatOptions::default()
+    };
+    let out = run_combat(
+        vec![brute(""), paralyzed("")],
+        &opts,
+        &mut Dice::from_seed(1),
+    );
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l == "[ATTACK][Brute] hits a Paralyzed target within 5 ft → CRIT!"));
+    assert!(out.log.iter().any(|l| l.contains("crit: rolled 2×(1d4)")));
+
+    let out = run_combat(
+        vec![brute(""), paralyzed(r#""crit_immune": true,"#)],
+        &opts,
+        &mut Dice::from_seed(1),
+    );
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l == "[DEF][Statue] critical hit becomes a normal hit"));
+    assert!(!out.log.iter().any(|l| l.contains("crit:")));
+}
+
+#[test]
+fn double_total_rule_doubles_modifiers_too() {
+    let mut hero = brute("");
+    hero.attacks = vec![AttackProfile {
+        damage_mod: 3,
+        ..hero.attacks[0].clone()
+    }];
+    let opts = CombatOptions {
+        max_rounds: 1,
+        crit_rule: CritRule::DoubleTotal,
+        ..CombatOptions::default()
+    };
+    let out = run_combat(vec![hero, paralyzed("")], &opts, &mut Dice::from_seed(1));
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[DMG][Brute] crit: total doubled ")));
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use engine::combat::{run_combat, CombatOptions, CombatOutcome, Combatant};
+use engine::content::parse_target;
+use engine::{AttackSource, DamageType, Dice, Material, QualifiedDefense};
+
+fn werewolf() -> Combatant {
+    Combatant::from_target(
+        &parse_target(include_str!("../../content/targets/werewolf.json")).unwrap(),
+    )
+}
+
+fn fighter(source: AttackSource) -> Combatant {
+    let mut attack = common::sword(9, 8);
+    attack.source = source;
+    common::fighter_with(attack)
+}
+
+fn fight(source: AttackSource) -> CombatOutcome {
+    run_combat(
+        vec![fighter(source), werewolf()],
+        &CombatOptions {
+            max_rounds: 3,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(11),
+    )
+}
+
+#[test]
+fn qualifiers_match_magic_and_material() {
+    let q: QualifiedDefense = serde_json::from_str(
+        r#"{ "defense": "resistance", "types": ["slashing"], "nonmagical": true, "except": "silvered" }"#,
+    )
+    .unwrap();
+    let plain = AttackSource::default();
+    let silvered = AttackSource {
+        magical: false,
+        material: Some(Material::Silvered),
+    };
+    let magic = AttackSource {
+        magical: true,
+        material: None,
+    };
+    assert!(q.applies(DamageType::Slashing, plain));
+    assert!(!q.applies(DamageType::Fire, plain));
+    assert!(!q.applies(DamageType::Slashing, silvered));
+    assert!(!q.applies(DamageType::Slashing, magic));
+    assert_eq!(q.describe(), "nonmagical, not silvered");
+
+    let vuln: QualifiedDefense = serde_json::from_str(
+        r#"{ "defense": "vulnerability", "types": ["slashing"], "material": "silvered" }"#,
+    )
+    .unwrap();
+    assert!(vuln.applies(DamageType::Slashing, silvered));
+    assert!(!vuln.applies(DamageType::Slashing, plain));
+}
+
+#[test]
+fn werewolf_shrugs_off_plain_steel_and_the_log_says_why() {
+    let out = fight(AttackSource::default());
+    let log = &out.log;
+    assert_eq!(out.combatants[1].health.hp, 58);
+    assert!(
+        log.iter().any(|l| l.starts_with("[DEF][Werewolf]")
+            && l.contains("Slashing immunity (nonmagical, not silvered)")),
+        "{:#?}",
+        log
+    );
+}
+
+#[test]
+fn silvered_and_magic_weapons_get_through() {
+    for source in [
+        AttackSource {
+            magical: false,
+            material: Some(Material::Silvered),
+        },
+        AttackSource {
+            magical: true,
+            material: None,
+        },
+    ] {
+        let out = fight(source);
+        assert!(
+            !out.log.iter().any(|l| l.contains("immunity")),
+            "{:#?}",
+            out.log
+        );
+        assert!(out.combatants[1].health.hp < 58, "{:#?}", out.log);
+    }
+}
+
+#[test]
+fn wraith_life_drain_lowers_max_hp_on_a_failed_save() {
+    let wraith = Combatant::from_target(
+        &parse_target(include_str!("../../content/targets/wraith.json")).unwrap(),
+    );
+    let out = (0..20)
+        .map(|seed| {
+            run_combat(
+                vec![fighter(AttackSource::default()), wraith.clone()],
+                &CombatOptions {
+                    max_rounds: 2,
+                    ..CombatOptions::default()
+                },
+                &mut Dice::from_seed(seed),
+            )
+        })
+        .find(|out| {
+            out.log
+                .iter()
+                .any(|l| l.starts_with("[MAXHP][Sample Fighter]"))
+        })
+        .expect("some seed fails the Con save");
+    let hero = &out.combatants[0].health;
+    assert!(hero.max_hp_reduction > 0);
+    assert!(hero.hp <= hero.effective_max_hp());
+}
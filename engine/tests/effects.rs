@@ -0,0 +1,163 @@
+mod common;
+
+use common::fighter;
+use engine::combat::{run_combat, CombatOptions, Combatant};
+use engine::conditions::TurnBoundary;
+use engine::content::{builtin_effects, parse_effects, parse_target};
+use engine::effects::{
+    find_effect, process_effect_boundary, resolve_effects, roll_terms, ActiveEffect, Breakdown,
+    EffectDef, Stat,
+};
+use engine::features::RestKind;
+use engine::life::ShortRestPolicy;
+use engine::saves::SaveOutcome;
+use engine::{DamageType, Dice};
+
+fn catalog() -> Vec<EffectDef> {
+    parse_effects(builtin_effects()["basic"]).expect("built-in effects parse")
+}
+
+#[test]
+fn bless_and_bane_roll_their_dice_with_a_breakdown() {
+    let effects = resolve_effects(
+        &["bless".to_string(), "bane".to_string()],
+        &catalog(),
+        "Cleric",
+    )
+    .unwrap();
+    assert_eq!(effects[0].source, "Cleric");
+    let terms = roll_terms(&mut Dice::from_scripted(vec![4, 1]), &effects, Stat::Attack);
+    let mut breakdown = Breakdown::new("to-hit", 5);
+    breakdown.terms.extend(terms);
+    assert_eq!(breakdown.total(), 8);
+    assert_eq!(
+        breakdown.to_string(),
+        "to-hit 5 + Bless 1d4 4 − Bane 1d4 1 = 8"
+    );
+
+    assert!(resolve_effects(&["haste".to_string()], &catalog(), "x").is_err());
+}
+
+#[test]
+fn bless_adds_its_save_die_once() {
+    let mut hero = fighter();
+    hero.effects = resolve_effects(
+        &["bless".to_string(), "BLESS".to_string()],
+        &catalog(),
+        "setup",
+    )
+    .unwrap();
+    assert_eq!(hero.effects.len(), 1);
+    let dice = hero.save_profile().dice;
+    assert_eq!(dice.len(), 1);
+    assert_eq!(dice[0].label, "Bless");
+}
+
+#[test]
+fn effects_change_ac_saves_and_resistances_until_a_rest() {
+    let catalog = catalog();
+    let mut hero = fighter();
+    let base_ac = hero.armor_class();
+    hero.effects = resolve_effects(
+        &[
+            "shield_of_faith".to_string(),
+            "protection_from_fire".to_string(),
+            "bless".to_string(),
+        ],
+        &catalog,
+        "setup",
+    )
+    .unwrap();
+    assert_eq!(hero.armor_class(), base_ac + 2);
+    assert_eq!(hero.save_profile().dice.len(), 1);
+
+    hero.short_rest(&ShortRestPolicy::default(), &mut Dice::from_seed(0), |_| {});
+    assert!(hero.effects.is_empty());
+    assert_eq!(hero.armor_class(), base_ac);
+
+    let fire = find_effect(&catalog, "protection_from_fire").unwrap();
+    assert!(fire.modifiers.iter().any(|m| matches!(
+        m,
+        engine::effects::Modifier::Resistance {
+            damage_type: DamageType::Fire
+        }
+    )));
+}
+
+#[test]
+fn durations_follow_condition_rules() {
+    let mut brief = find_effect(&catalog(), "bless").unwrap().clone();
+    brief.duration.until = Some(engine::conditions::DurationPhase::StartOfTurn);
+    let mut lasting = brief.clone();
+    lasting.duration.until = None;
+    lasting.duration.save_ends_each_turn = true;
+    lasting.end_save = Some(engine::SavingThrow {
+        ability: engine::Ability::Wis,
+        dc: 10,
+    });
+    let mut effects = vec![
+        ActiveEffect::new(brief, "a"),
+        ActiveEffect::new(lasting, "b"),
+    ];
+    let mut log = Vec::new();
+    process_effect_boundary(
+        TurnBoundary::StartOfTurn,
+        "Hero",
+        &mut effects,
+        |ctx| SaveOutcome::new(1, 1, ctx.dc),
+        |m| log.push(m),
+    );
+    assert_eq!(effects.len(), 1);
+    assert!(log.contains(&"[EFFECT][Hero] Bless ends at StartOfTurn".to_string()));
+    process_effect_boundary(
+        TurnBoundary::EndOfTurn,
+        "Hero",
+        &mut effects,
+        |ctx| SaveOutcome::new(15, 15, ctx.dc),
+        |m| log.push(m),
+    );
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn counted_effects_run_out_and_long_rest_effects_outlast_a_short_rest() {
+    let mut counted = find_effect(&catalog(), "bless").unwrap().clone();
+    counted.duration.rounds = Some(1);
+    let mut until_long_rest = find_effect(&catalog(), "shield_of_faith").unwrap().clone();
+    until_long_rest.duration.until_rest = Some(RestKind::Long);
+    let mut hero = fighter();
+    hero.effects = vec![
+        ActiveEffect::new(counted, "Cleric"),
+        ActiveEffect::new(until_long_rest, "Cleric"),
+    ];
+    let mut log = Vec::new();
+    process_effect_boundary(
+        TurnBoundary::EndOfTurn,
+        "Hero",
+        &mut hero.effects,
+        |ctx| SaveOutcome::new(1, 1, ctx.dc),
+        |m| log.push(m),
+    );
+    assert_eq!(log, vec!["[EFFECT][Hero] Bless runs out"]);
+
+    hero.short_rest(&ShortRestPolicy::default(), &mut Dice::from_seed(0), |_| {});
+    assert_eq!(hero.effects.len(), 1);
+    hero.long_rest(|_| {});
+    assert!(hero.effects.is_empty());
+}
+
+#[test]
+fn blessed_attacks_log_their_breakdown() {
+    let mut hero = fighter();
+    hero.effects = resolve_effects(&["bless".to_string()], &catalog(), "setup").unwrap();
+    let goblin = parse_target(include_str!("../../content/targets/goblin.json")).unwrap();
+    let out = run_combat(
+        vec![hero, Combatant::from_target(&goblin)],
+        &CombatOptions::default(),
+        &mut Dice::from_seed(9),
+    );
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[EFFECT][") && l.contains("] attack: to-hit 5 + Bless 1d4 ")));
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::hero;
+use engine::api::{simulate_duel, DuelConfig};
+use engine::combat::{run_combat, CombatOptions, Combatant};
+use engine::conditions::{
+    vantage_sources_from_conditions, ActiveCondition, AttackStyle, ConditionKind, Vantage,
+};
+use engine::content::parse_target;
+use engine::explain::{RollKind, VantageBreakdown};
+use engine::Dice;
+
+fn dwarf() -> Combatant {
+    hero("fighter", "dwarf", 1, "longsword")
+}
+
+#[test]
+fn vantage_sources_name_each_condition_and_cancel() {
+    let attacker = [ActiveCondition::indefinite(ConditionKind::Poisoned)];
+    let target = [
+        ActiveCondition::indefinite(ConditionKind::Prone),
+        ActiveCondition::indefinite(ConditionKind::Restrained),
+    ];
+    let sources = vantage_sources_from_conditions(&attacker, &target, AttackStyle::Melee);
+    assert_eq!(sources.sources.len(), 3);
+    assert_eq!(sources.net(), Vantage::Normal);
+    assert_eq!(
+        sources.to_string(),
+        "advantage (target Prone (melee), target Restrained) and disadvantage (attacker Poisoned) cancel"
+    );
+
+    let mut hidden = VantageBreakdown::default();
+    hidden.add("attacking from hiding", Vantage::Advantage);
+    hidden.add("ignored", Vantage::Normal);
+    assert_eq!(hidden.net(), Vantage::Advantage);
+    assert_eq!(hidden.to_string(), "advantage (attacking from hiding)");
+}
+
+#[test]
+fn combat_rolls_point_at_their_log_lines() {
+    let mut hero = dwarf();
+    hero.conditions
+        .push(ActiveCondition::indefinite(ConditionKind::Poisoned));
+    let goblin = parse_target(include_str!("../../content/targets/poison_goblin.json")).unwrap();
+    let out = run_combat(
+        vec![hero, Combatant::from_target(&goblin)],
+        &CombatOptions::default(),
+        &mut Dice::from_seed(4),
+    );
+
+    let attack = out
+        .rolls
+        .iter()
+        .find(|r| r.kind == RollKind::Attack && r.roller == "Hero")
+        .expect("the hero attacked");
+    assert!(out.log[attack.log_index].starts_with("[ATTACK][Hero (longsword)]"));
+    assert_eq!(attack.vantage.net(), Vantage::Disadvantage);
+    assert_eq!(attack.vantage.sources[0].source, "attacker Poisoned");
+    assert_eq!(attack.rolls.len(), 2);
+    assert_eq!(attack.modifiers.terms[0].label, "d20");
+    assert_eq!(attack.total, attack.modifiers.total());
+
+    for r in &out.rolls {
+        let line = &out.log[r.log_index];
+        match r.kind {
+            RollKind::Attack => assert!(line.starts_with("[ATTACK]")),
+            RollKind::Damage => assert!(line.starts_with("[DMG]")),
+            RollKind::Save => {
+                assert!(line.starts_with("[SAVE]") || line.starts_with("[DEATHSAVE]"))
+            }
+            RollKind::Check => {
+                assert!(line.starts_with("[INIT]") || line.starts_with("[FEATURE]"))
+            }
+        }
+    }
+}
+
+#[test]
+fn saves_explain_modifiers_and_resilience() {
+    let goblin = parse_target(include_str!("../../content/targets/poison_goblin.json")).unwrap();
+    let out = run_combat(
+        vec![dwarf(), Combatant::from_target(&goblin)],
+        &CombatOptions::default(),
+        &mut Dice::from_seed(11),
+    );
+    let save = out
+        .rolls
+        .iter()
+        .find(|r| r.kind == RollKind::Save && r.what == "Con save vs Poisoned")
+        .expect("the goblin poisoned the dwarf");
+    assert_eq!(save.against, Some(12));
+    assert_eq!(save.vantage.sources[0].source, "resilience");
+    assert_eq!(save.vantage.net(), Vantage::Advantage);
+    assert!(save.modifiers.terms.iter().any(|t| t.label == "Con save"));
+}
+
+#[test]
+fn duel_results_carry_rolls_for_their_log() {
+    let res = simulate_duel(DuelConfig {
+        target_path: None,
+        weapons_path: None,
+        target_id: Some("poison_goblin".to_string()),
+        weapons_id: Some("basic".to_string()),
+        weapon: "longsword".to_string(),
+        actor_conditions: vec![],
+        enemy_conditions: vec![ConditionKind::Prone],
+        seed: 7,
+        actor_hp: None,
+        actor_temp_hp: 0,
+        character_path: None,
+        items_path: None,
+        tactics: Default::default(),
+        ambush: None,
+        initiative_advantage: false,
+        actor_effects: vec!["bless".to_string()],
+        enemy_effects: vec![],
+        crit_rule: Default::default(),
+        sunlight: false,
+    })
+    .unwrap();
+    let first = res
+        .rolls
+        .iter()
+        .find(|r| r.kind == RollKind::Attack)
+        .expect("someone attacked");
+    assert!(res.log[first.log_index].starts_with("[ATTACK]"));
+    assert!(res.rolls.iter().any(|r| r.kind == RollKind::Attack
+        && r.roller == "Actor"
+        && r.modifiers.terms.iter().any(|t| t.label == "Bless 1d4")));
+}
@@ -0,0 +1,271 @@
+mod common;
+
+use engine::character::{create_character, Character, CharacterSpec, Class, HpMethod};
+use engine::combat::{run_combat, CombatOptions, Combatant, Side};
+use engine::conditions::{ActiveCondition, ConditionKind};
+use engine::content::{builtin_items, parse_content, parse_items, parse_target};
+use engine::features::{
+    attacks_per_action, sneak_attack_dice, FightingStyle, Resource, Resources, RestKind,
+    SmitePolicy, Tactics,
+};
+use engine::items::{ItemAction, ItemStack};
+use engine::life::ShortRestPolicy;
+use engine::Dice;
+
+fn character(class: &str, level: u8, style: Option<&str>) -> Character {
+    common::character(class, "human", level, style)
+}
+
+fn hero(class: &str, level: u8, weapon: &str) -> Combatant {
+    common::hero(class, "human", level, weapon)
+}
+
+fn dummy(hp: i32) -> Combatant {
+    let t = parse_target(&format!(
+        r#"{{ "name": "Dummy", "ac": 10, "hp": {},
+            "attacks": [{{ "name": "Poke", "to_hit": 0, "dice": {{ "count": 1, "sides": 4 }} }}] }}"#,
+        hp
+    ))
+    .unwrap();
+    Combatant::from_target(&t)
+}
+
+#[test]
+fn resources_scale_with_level_and_refresh_on_rest() {
+    let mut r = Resources::for_class(Class::Fighter, 17);
+    assert_eq!(r.remaining(Resource::ActionSurge), 2);
+    assert!(r.spend(Resource::SecondWind));
+    assert!(!r.spend(Resource::SecondWind));
+    r.refresh(RestKind::Short);
+    assert_eq!(r.remaining(Resource::SecondWind), 1);
+
+    let mut rages = Resources::for_class(Class::Barbarian, 3);
+    assert!(rages.spend(Resource::Rage));
+    rages.refresh(RestKind::Short);
+    assert_eq!(rages.remaining(Resource::Rage), 2);
+    rages.sync(Class::Barbarian, 6);
+    assert_eq!(rages.remaining(Resource::Rage), 3); // one still spent out of 4
+    rages.refresh(RestKind::Long);
+    assert_eq!(rages.remaining(Resource::Rage), 4);
+
+    let slots = Resources::for_class(Class::Paladin, 5);
+    assert_eq!(slots.remaining(Resource::SpellSlot1), 4);
+    assert_eq!(slots.remaining(Resource::SpellSlot2), 2);
+    assert!(Resources::for_class(Class::Paladin, 1)
+        .lowest_slot()
+        .is_none());
+}
+
+#[test]
+fn derived_features_by_level() {
+    assert_eq!(attacks_per_action(Class::Fighter, 4), 1);
+    assert_eq!(attacks_per_action(Class::Fighter, 11), 3);
+    assert_eq!(attacks_per_action(Class::Ranger, 5), 2);
+    assert_eq!(attacks_per_action(Class::Wizard, 20), 1);
+    assert_eq!(sneak_attack_dice(Class::Rogue, 1), 1);
+    assert_eq!(sneak_attack_dice(Class::Rogue, 9), 5);
+    assert_eq!(sneak_attack_dice(Class::Fighter, 9), 0);
+}
+
+#[test]
+fn fighting_styles_follow_class_rules() {
+    assert_eq!(
+        character("fighter", 1, None).active_fighting_style(),
+        Some(FightingStyle::Defense)
+    );
+    assert_eq!(character("paladin", 1, None).active_fighting_style(), None);
+    let mut pal = character("paladin", 1, Some("dueling"));
+    assert_eq!(pal.active_fighting_style(), None);
+    pal.level_up(HpMethod::Average, &mut Dice::from_seed(0))
+        .unwrap();
+    assert_eq!(pal.active_fighting_style(), Some(FightingStyle::Dueling));
+
+    let spec: CharacterSpec = parse_content(
+        "character spec",
+        r#"{ "class": "paladin", "species": "elf", "fighting_style": "archery" }"#,
+    )
+    .unwrap();
+    let err = create_character(&spec, &mut Dice::from_seed(0)).unwrap_err();
+    assert_eq!(err.code(), "invalid_config");
+
+    let defended = hero("fighter", 1, "longsword");
+    assert_eq!(defended.armor_class(), 17);
+}
+
+#[test]
+fn second_wind_heals_when_bloodied() {
+    let mut fighter = hero("fighter", 3, "longsword");
+    fighter.health.hp = 10;
+    let out = run_combat(
+        vec![fighter, dummy(200)],
+        &CombatOptions {
+            max_rounds: 2,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(8),
+    );
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[FEATURE][Hero] Second Wind")));
+    let kit = out.combatants[0].kit.as_ref().unwrap();
+    assert_eq!(kit.resources.remaining(Resource::SecondWind), 0);
+    assert_eq!(kit.resources.remaining(Resource::ActionSurge), 0);
+    assert!(out.combatants[0].health.hp > 10);
+}
+
+#[test]
+fn smite_policy_controls_slot_use() {
+    let run = |smite| {
+        let out = run_combat(
+            vec![hero("paladin", 5, "longsword"), dummy(500)],
+            &CombatOptions {
+                max_rounds: 3,
+                tactics: Tactics {
+                    smite,
+                    ..Tactics::default()
+                },
+                ..CombatOptions::default()
+            },
+            &mut Dice::from_seed(21),
+        );
+        let kit = out.combatants[0].kit.clone().unwrap();
+        (out, kit.resources)
+    };
+    let (_, unspent) = run(SmitePolicy::Never);
+    assert_eq!(unspent.remaining(Resource::SpellSlot1), 4);
+    let (out, spent) = run(SmitePolicy::Always);
+    assert!(spent.remaining(Resource::SpellSlot1) < 4);
+    assert!(out.log.iter().any(|l| l.contains("Divine Smite")));
+}
+
+#[test]
+fn rage_and_sneak_attack_show_up_in_the_log() {
+    let out = run_combat(
+        vec![hero("barbarian", 1, "greatsword"), dummy(300)],
+        &CombatOptions {
+            max_rounds: 1,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(4),
+    );
+    assert!(out.log.iter().any(|l| l.contains("[FEATURE][Hero] Rage")));
+    assert!(out.combatants[0].raging());
+
+    // An ally next to the target enables Sneak Attack without advantage.
+    let mut ally = hero("fighter", 1, "longsword");
+    ally.name = "Ally".into();
+    let mut rogue = hero("rogue", 1, "shortsword");
+    rogue.name = "Rogue".into();
+    let out = run_combat(
+        vec![rogue, ally, dummy(300)],
+        &CombatOptions {
+            max_rounds: 6,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(4),
+    );
+    let sneaks = out
+        .log
+        .iter()
+        .filter(|l| l.starts_with("[FEATURE][Rogue] Sneak Attack"))
+        .count();
+    assert!((1..=6).contains(&sneaks), "once per turn at most");
+    assert_eq!(out.winner, None);
+    assert_eq!(out.rounds, 6);
+    assert!(out
+        .combatants
+        .iter()
+        .all(|c| c.side != Side::Enemies || c.health.hp > 0));
+}
+
+#[test]
+fn a_stunned_ally_does_not_enable_sneak_attack() {
+    let mut ally = hero("fighter", 1, "longsword");
+    ally.name = "Ally".into();
+    ally.conditions
+        .push(ActiveCondition::indefinite(ConditionKind::Stunned));
+    let mut rogue = hero("rogue", 1, "shortsword");
+    rogue.name = "Rogue".into();
+    let out = run_combat(
+        vec![rogue, ally, dummy(300)],
+        &CombatOptions {
+            max_rounds: 6,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(4),
+    );
+    assert!(out.log.iter().any(|l| l.starts_with("[ATTACK][Rogue")));
+    assert!(!out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[FEATURE][Rogue] Sneak Attack")));
+}
+
+#[test]
+fn cunning_action_hide_uses_up_the_bonus_action() {
+    // A potion drunk as a bonus action competes with hiding for the same slot.
+    let mut potion = ItemStack::new(
+        parse_items(builtin_items()["basic"])
+            .unwrap()
+            .into_iter()
+            .find(|d| d.id == "potion_of_healing")
+            .unwrap(),
+        1,
+    );
+    potion.def.action = ItemAction::BonusAction;
+    let mut rogue = hero("rogue", 2, "shortsword");
+    rogue.health.hp = 1;
+    rogue.inventory = vec![potion];
+    let out = run_combat(
+        vec![rogue, dummy(300)],
+        &CombatOptions {
+            max_rounds: 1,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(4),
+    );
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[FEATURE][Hero] Cunning Action: Hide")));
+    assert!(!out.log.iter().any(|l| l.starts_with("[ITEM][Hero]")));
+    assert_eq!(out.combatants[0].inventory[0].quantity, 1);
+    assert!(out.log.iter().any(|l| l.starts_with("[ATTACK][Hero")));
+}
+
+#[test]
+fn rests_refresh_features_between_fights() {
+    let mut fighter = hero("fighter", 4, "longsword");
+    fighter.health.hp = 10;
+    let out = run_combat(
+        vec![fighter, dummy(200)],
+        &CombatOptions {
+            max_rounds: 1,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(8),
+    );
+    let mut fighter = out.combatants.into_iter().next().unwrap();
+    let kit = fighter.kit.as_ref().unwrap();
+    assert_eq!(kit.resources.remaining(Resource::SecondWind), 0);
+
+    let mut dice = Dice::from_seed(1);
+    fighter.short_rest(&ShortRestPolicy::default(), &mut dice, |_| {});
+    let kit = fighter.kit.as_ref().unwrap();
+    assert_eq!(kit.resources.remaining(Resource::SecondWind), 1);
+    assert_eq!(kit.resources.remaining(Resource::ActionSurge), 1);
+    assert!(fighter.hit_dice.unwrap().remaining < 4);
+
+    let mut barbarian = hero("barbarian", 1, "greatsword");
+    let kit = barbarian.kit.as_mut().unwrap();
+    kit.resources.spend(Resource::Rage);
+    kit.rage_rounds = 5;
+    barbarian.short_rest(&ShortRestPolicy::default(), &mut dice, |_| {});
+    assert!(!barbarian.raging());
+    let kit = barbarian.kit.as_ref().unwrap();
+    assert_eq!(kit.resources.remaining(Resource::Rage), 1);
+    barbarian.long_rest(|_| {});
+    let kit = barbarian.kit.as_ref().unwrap();
+    assert_eq!(kit.resources.remaining(Resource::Rage), 2);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::hero;
+use engine::combat::{run_combat, CombatOptions, Combatant};
+use engine::conditions::ConditionKind;
+use engine::content::parse_target;
+use engine::features::Tactics;
+use engine::Dice;
+
+fn fighter(level: u8) -> Combatant {
+    hero("fighter", "human", level, "longsword")
+}
+
+fn brute(hp: i32, attack_extra: &str) -> Combatant {
+    let t = parse_target(&format!(
+        r#"{{ "name": "Brute", "ac": 12, "hp": {},
+            "abilities": {{ "str": 10, "dex": 8, "con": 10, "int": 10, "wis": 10, "cha": 10 }},
+            "attacks": [{{ "name": "Slam", "to_hit": 2, "dice": {{ "count": 1, "sides": 4 }}{} }}] }}"#,
+        hp, attack_extra
+    ))
+    .unwrap();
+    Combatant::from_target(&t)
+}
+
+fn grappling() -> CombatOptions {
+    CombatOptions {
+        max_rounds: 6,
+        tactics: Tactics {
+            grapple_shove: true,
+            ..Tactics::default()
+        },
+        ..CombatOptions::default()
+    }
+}
+
+#[test]
+fn grapple_then_shove_prone_pins_the_target() {
+    let out = run_combat(
+        vec![fighter(5), brute(200, "")],
+        &grappling(),
+        &mut Dice::from_seed(11),
+    );
+    let has = |prefix: &str| out.log.iter().any(|l| l.starts_with(prefix));
+    assert!(has("[GRAPPLE][Hero] grapples Brute"));
+    assert!(has("[SHOVE][Hero] shoves Brute prone"));
+    assert!(has("[COND][Brute] gains Prone (Hero's shove)"));
+    assert!(has("[GRAPPLE][Brute] tries to escape Hero"));
+    // Pinned: the brute never stands while still grappled.
+    let pinned = out
+        .log
+        .iter()
+        .position(|l| l == "[COND][Brute] gains Prone (Hero's shove)");
+    let freed = out
+        .log
+        .iter()
+        .position(|l| l.starts_with("[COND][Brute] is no longer Grappled"));
+    let stood = out.log.iter().position(|l| l == "[COND][Brute] stands up");
+    if let Some(stood) = stood {
+        assert!(freed.is_some_and(|f| f < stood && pinned.unwrap() < f));
+    }
+    assert!(
+        out.combatants
+            .iter()
+            .all(|c| !c.has_condition(ConditionKind::Grappled)),
+        "grapples end with the fight"
+    );
+
+    let plain = run_combat(
+        vec![fighter(5), brute(200, "")],
+        &CombatOptions {
+            max_rounds: 6,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(11),
+    );
+    assert!(!plain.log.iter().any(|l| l.starts_with("[GRAPPLE]")));
+}
+
+#[test]
+fn stat_block_grabs_are_held_by_the_attacker() {
+    let out = run_combat(
+        vec![
+            fighter(1),
+            brute(60, r#", "apply_condition": { "kind": "grappled" }"#),
+        ],
+        &CombatOptions {
+            max_rounds: 4,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(2),
+    );
+    let grabbed = out
+        .log
+        .iter()
+        .filter(|l| *l == "[COND][Hero] gains Grappled (Brute's Slam)")
+        .count();
+    assert_eq!(grabbed, 1, "a held creature isn't grabbed again");
+    assert!(!out.combatants[0].has_condition(ConditionKind::Grappled));
+}
+
+#[test]
+fn restrained_creatures_spend_their_action_on_the_escape_dc() {
+    let out = run_combat(
+        vec![
+            fighter(1),
+            brute(
+                200,
+                r#", "apply_condition": { "kind": "restrained", "escape_dc": 12 }"#,
+            ),
+        ],
+        &CombatOptions {
+            max_rounds: 6,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(3),
+    );
+    let tries = out
+        .log
+        .iter()
+        .position(|l| l.starts_with("[COND][Hero] tries to escape Restrained: "))
+        .expect("the hero tried to break free");
+    assert!(out.log[tries].contains(" vs DC 12 → "));
+    assert!(!out.log[tries + 1].starts_with("[ATTACK][Hero"));
+    let roll = out
+        .rolls
+        .iter()
+        .find(|r| r.log_index == tries)
+        .expect("the escape check is explained");
+    assert_eq!(roll.against, Some(12));
+}
@@ -0,0 +1,206 @@
+use engine::combat::{run_combat, AttackProfile, CombatOptions, Combatant};
+use engine::conditions::ConditionKind;
+use engine::content::{builtin_items, parse_target};
+use engine::features::Tactics;
+use engine::items::{carried_weight, resolve_inventory, InventoryEntry, ItemDef, ItemStack};
+use engine::life::ShortRestPolicy;
+use engine::{Ability, AbilityScores, Actor, DamageDice, DamageType, Dice};
+use std::collections::HashSet;
+
+fn catalog() -> Vec<ItemDef> {
+    engine::content::parse_items(builtin_items()["basic"]).expect("built-in items parse")
+}
+
+fn pack(items: &[(&str, u32)]) -> Vec<ItemStack> {
+    let entries: Vec<_> = items
+        .iter()
+        .map(|&(item, quantity)| InventoryEntry {
+            item: item.to_string(),
+            quantity,
+            charges: None,
+        })
+        .collect();
+    resolve_inventory(&entries, &catalog()).unwrap()
+}
+
+fn adventurer(hp: i32, items: &[(&str, u32)]) -> Combatant {
+    let actor = Actor {
+        abilities: AbilityScores {
+            str_: 16,
+            dex: 14,
+            con: 12,
+            int_: 10,
+            wis: 10,
+            cha: 10,
+        },
+        proficiency_bonus: 2,
+        save_proficiencies: HashSet::new(),
+        skill_proficiencies: HashSet::new(),
+        expertise: HashSet::new(),
+    };
+    let attack = AttackProfile {
+        name: "Club".into(),
+        to_hit: actor.attack_bonus(Ability::Str, true),
+        dice: DamageDice::new(1, 4),
+        damage_mod: 3,
+        damage_type: DamageType::Bludgeoning,
+        ranged: false,
+        finesse: false,
+        two_handed: false,
+        ability: Some(Ability::Str),
+        weapon: true,
+        source: Default::default(),
+        apply_condition: None,
+        drain: None,
+    };
+    let mut c = Combatant::from_actor("Hero", actor, 20, 14, attack);
+    c.health.hp = hp;
+    c.inventory = pack(items);
+    c
+}
+
+fn enemy(extra: &str) -> Combatant {
+    let t = parse_target(&format!(
+        r#"{{ "name": "Foe", "ac": 10, "hp": 200, {}
+            "attacks": [{{ "name": "Singe", "to_hit": 0, "damage_type": "fire",
+                "dice": {{ "count": 1, "sides": 4 }},
+                "apply_condition": {{ "kind": "poisoned", "save": {{ "ability": "con", "dc": 12 }},
+                    "duration": {{ "save_ends_each_turn": true }} }} }}] }}"#,
+        extra
+    ))
+    .unwrap();
+    Combatant::from_target(&t)
+}
+
+fn one_round(party: Combatant, foe: Combatant, tactics: Tactics) -> engine::combat::CombatOutcome {
+    run_combat(
+        vec![party, foe],
+        &CombatOptions {
+            max_rounds: 1,
+            tactics,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(3),
+    )
+}
+
+#[test]
+fn inventory_resolves_ids_charges_and_weight() {
+    let stacks = pack(&[("potion_of_healing", 2), ("healers_kit", 1)]);
+    assert_eq!(carried_weight(&stacks), 4.0);
+
+    let mut kit = stacks[1].clone();
+    assert_eq!(kit.charges, Some(10));
+    for _ in 0..10 {
+        assert!(kit.use_one());
+    }
+    assert!(kit.is_empty());
+    assert!(!kit.use_one());
+
+    let err = resolve_inventory(
+        &[InventoryEntry {
+            item: "bag_of_holding".into(),
+            quantity: 1,
+            charges: None,
+        }],
+        &catalog(),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "content_not_found");
+}
+
+#[test]
+fn healing_potion_rolls_and_costs_the_action() {
+    let out = one_round(
+        adventurer(4, &[("potion_of_healing", 2)]),
+        enemy(""),
+        Tactics::default(),
+    );
+    let drink = out
+        .log
+        .iter()
+        .position(|l| l.starts_with("[ITEM][Hero] drinks Potion of Healing (1 left): 2d4+2 = "))
+        .expect("potion drunk");
+    assert!(out.log[drink + 1].starts_with("[HEAL][Hero]"));
+    assert!(
+        !out.log.iter().any(|l| l.starts_with("[ATTACK][Hero")),
+        "drinking takes the action"
+    );
+    assert_eq!(out.combatants[0].inventory[0].quantity, 1);
+
+    let healthy = one_round(
+        adventurer(20, &[("potion_of_healing", 1)]),
+        enemy(""),
+        Tactics::default(),
+    );
+    assert!(!healthy.log.iter().any(|l| l.starts_with("[ITEM]")));
+}
+
+#[test]
+fn buff_potions_answer_the_enemy_and_end_on_rest() {
+    let out = one_round(
+        adventurer(20, &[("potion_of_fire_resistance", 1), ("antitoxin", 1)]),
+        enemy(""),
+        Tactics::default(),
+    );
+    let hero = &out.combatants[0];
+    assert!(hero.item_resistances.contains(&DamageType::Fire));
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.contains("drinks Potion of Fire Resistance")));
+
+    // One action per turn: the antitoxin waits for the next round.
+    let out = run_combat(
+        out.combatants,
+        &CombatOptions {
+            max_rounds: 1,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(3),
+    );
+    let mut hero = out.combatants[0].clone();
+    assert!(hero.save_advantage.contains(&ConditionKind::Poisoned));
+
+    hero.short_rest(&ShortRestPolicy::default(), &mut Dice::from_seed(1), |_| {});
+    assert!(hero.item_resistances.is_empty() && hero.save_advantage.is_empty());
+
+    let off = one_round(
+        adventurer(20, &[("potion_of_fire_resistance", 1)]),
+        enemy(""),
+        Tactics {
+            buff_items: false,
+            ..Tactics::default()
+        },
+    );
+    assert!(!off.log.iter().any(|l| l.starts_with("[ITEM]")));
+}
+
+#[test]
+fn thrown_items_only_when_they_beat_the_weapon() {
+    // A vial of acid (2d6) out-damages a club (1d4+3) unless the target resists acid.
+    let out = one_round(
+        adventurer(20, &[("acid_vial", 1)]),
+        enemy(""),
+        Tactics::default(),
+    );
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l == "[ITEM][Hero] throws Acid at Foe (0 left)"));
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[ATTACK][Hero (Acid)]")));
+
+    let resisted = one_round(
+        adventurer(20, &[("acid_vial", 1)]),
+        enemy(r#""resistances": ["acid"],"#),
+        Tactics::default(),
+    );
+    assert!(!resisted.log.iter().any(|l| l.contains("throws")));
+    assert!(resisted
+        .log
+        .iter()
+        .any(|l| l.starts_with("[ATTACK][Hero (Club)]")));
+}
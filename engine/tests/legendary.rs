@@ -0,0 +1,108 @@
+mod common;
+
+use engine::combat::{run_combat, CombatOptions, Combatant, Side};
+use engine::content::parse_target;
+use engine::legendary::next_lair_action;
+use engine::life::Health;
+use engine::Dice;
+
+const DRAGON: &str = include_str!("../../content/targets/adult_red_dragon.json");
+
+/// A fighter tough enough to outlast a few rounds with an adult dragon.
+fn hero(name: &str) -> Combatant {
+    let mut c = common::fighter();
+    c.name = name.to_string();
+    c.health = Health::new(500);
+    c
+}
+
+fn dragon_fight(heroes: Vec<Combatant>, rounds: u32, seed: u64) -> Vec<String> {
+    let mut cs = heroes;
+    cs.push(Combatant::from_target(&parse_target(DRAGON).unwrap()));
+    run_combat(
+        cs,
+        &CombatOptions {
+            max_rounds: rounds,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(seed),
+    )
+    .log
+}
+
+#[test]
+fn lair_actions_take_count_20_and_never_repeat() {
+    let log = dragon_fight(vec![hero("Hero")], 4, 5);
+    let init = log.iter().find(|l| l.starts_with("[INIT] ")).unwrap();
+    let order: Vec<i32> = init["[INIT] ".len()..]
+        .split(", ")
+        .map(|e| e.rsplit(' ').next().unwrap().parse().unwrap())
+        .collect();
+    let lair = init["[INIT] ".len()..]
+        .split(", ")
+        .position(|e| e.starts_with("Lair (Adult Red Dragon) "))
+        .expect("the lair is in the order");
+    assert!(order[..lair].iter().all(|&t| t >= 20));
+    assert!(order[lair + 1..].iter().all(|&t| t < 20));
+
+    let lairs: Vec<&str> = log
+        .iter()
+        .filter_map(|l| l.strip_prefix("[LAIR][Adult Red Dragon] "))
+        .collect();
+    assert_eq!(
+        lairs,
+        [
+            "Magma Eruption",
+            "Tremor",
+            "Volcanic Gases",
+            "Magma Eruption"
+        ]
+    );
+    assert_eq!(next_lair_action(1, Some(0)), None);
+    assert_eq!(next_lair_action(1, None), Some(0));
+}
+
+#[test]
+fn legendary_actions_follow_other_turns_and_refill_on_the_dragons() {
+    let log = dragon_fight(vec![hero("Ana"), hero("Bo")], 2, 8);
+    let turn_ends: Vec<usize> = log
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.starts_with("[LEGENDARY][Adult Red Dragon] "))
+        .map(|(n, _)| n)
+        .collect();
+    let spent: Vec<&str> = turn_ends
+        .iter()
+        .map(|&n| &log[n]["[LEGENDARY][Adult Red Dragon] ".len()..])
+        .collect();
+    // Each round: Wing Attack after one hero's turn, Tail Attack after the other's.
+    assert_eq!(
+        spent,
+        [
+            "Wing Attack (cost 2, 1 left)",
+            "Tail Attack (cost 1, 0 left)",
+            "Wing Attack (cost 2, 1 left)",
+            "Tail Attack (cost 1, 0 left)",
+        ]
+    );
+    let wing = turn_ends[0];
+    assert!(log[wing + 1].contains("makes a Dex save DC 22 vs Wing Attack"));
+    assert!(log[wing + 2].contains("Wing Attack: 2d6"));
+}
+
+#[test]
+fn legendary_creatures_can_be_on_either_side() {
+    let mut ally = Combatant::from_target(&parse_target(DRAGON).unwrap());
+    ally.side = Side::Party;
+    ally.lair_actions.clear();
+    let goblin = Combatant::from_target(
+        &parse_target(include_str!("../../content/targets/goblin.json")).unwrap(),
+    );
+    let out = run_combat(
+        vec![ally, goblin],
+        &CombatOptions::default(),
+        &mut Dice::from_seed(2),
+    );
+    assert_eq!(out.winner, Some(Side::Party));
+    assert!(!out.log.iter().any(|l| l.starts_with("[LAIR]")));
+}
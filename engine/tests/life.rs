@@ -1,4 +1,11 @@
+mod common;
+
+use engine::combat::{run_combat, CombatOptions, Combatant};
+use engine::conditions::{DurationPhase, NamedTrigger, TurnTrigger};
+use engine::content::{parse_target, DownedPolicy};
+use engine::features::Tactics;
 use engine::life::*;
+use engine::{DamageDice, DamageType, Dice};
 
 fn noop_log(_: String) {}
 
@@ -9,6 +16,7 @@ fn nat20_wakes_to_one_hp() {
         max_hp: 10,
         state: LifeState::Unconscious { stable: false },
         death: Default::default(),
+        ..Health::new(10)
     };
     let outcome = process_death_save_start_of_turn("Hero", &mut h, || 20, noop_log);
     assert_eq!(h.state, LifeState::Conscious);
@@ -26,6 +34,7 @@ fn nat1_counts_two_failures_and_can_kill() {
             successes: 0,
             failures: 1,
         },
+        ..Health::new(10)
     };
     let _ = process_death_save_start_of_turn("Hero", &mut h, || 1, noop_log);
     assert!(matches!(h.state, LifeState::Dead));
@@ -41,6 +50,7 @@ fn three_successes_stabilize() {
             successes: 2,
             failures: 0,
         },
+        ..Health::new(10)
     };
     let _ = process_death_save_start_of_turn("Hero", &mut h, || 10, noop_log);
     assert!(matches!(h.state, LifeState::Unconscious { stable: true }));
@@ -56,6 +66,7 @@ fn healing_resets_death_saves_and_wakes() {
             successes: 2,
             failures: 2,
         },
+        ..Health::new(12)
     };
     heal("Hero", &mut h, 6, noop_log);
     assert_eq!(h.hp, 6);
@@ -72,12 +83,335 @@ fn apply_damage_triggers_unconscious_and_prone_once() {
         max_hp: 10,
         state: LifeState::Conscious,
         death: Default::default(),
+        ..Health::new(10)
     };
     let mut conds: Vec<ActiveCondition> = vec![];
     let mut seen = vec![];
-    let dropped = apply_damage("Hero", &mut h, &mut conds, 5, |s| seen.push(s));
+    let dropped = apply_damage("Hero", &mut h, &mut conds, 5, false, |s| seen.push(s));
     assert!(dropped);
     assert_eq!(h.hp, 0);
     assert!(matches!(h.state, LifeState::Unconscious { stable: false }));
     assert!(conds.iter().any(|c| c.kind == ConditionKind::Prone));
 }
+
+#[test]
+fn short_rest_spends_hit_dice_per_policy() {
+    let mut h = Health::new(30);
+    h.hp = 5;
+    let mut hd = HitDice {
+        sides: 10,
+        remaining: 4,
+        max: 4,
+    };
+    let policy = ShortRestPolicy {
+        heal_to_pct: 100,
+        reserve: 1,
+    };
+    let mut rolls = vec![6, 1, 10].into_iter();
+    let healed = short_rest(
+        "Hero",
+        &mut h,
+        &mut hd,
+        2,
+        &policy,
+        |_| rolls.next().unwrap(),
+        noop_log,
+    );
+    // 6+2, 1+2, 10+2 → 23, but one die is held in reserve after three
+    assert_eq!(healed, 23);
+    assert_eq!((h.hp, hd.remaining), (28, 1));
+
+    // Negative Con can't turn a die into damage.
+    let mut h = Health::new(10);
+    h.hp = 4;
+    let mut hd = HitDice {
+        sides: 6,
+        remaining: 1,
+        max: 1,
+    };
+    short_rest(
+        "Hero",
+        &mut h,
+        &mut hd,
+        -2,
+        &ShortRestPolicy::default(),
+        |_| 1,
+        noop_log,
+    );
+    assert_eq!(h.hp, 4);
+}
+
+#[test]
+fn long_rest_restores_and_needs_one_hp() {
+    let mut h = Health::new(20);
+    h.hp = 3;
+    let mut hd = HitDice {
+        sides: 8,
+        remaining: 0,
+        max: 5,
+    };
+    let mut exhaustion = 2;
+    assert!(long_rest(
+        "Hero",
+        &mut h,
+        Some(&mut hd),
+        &mut exhaustion,
+        noop_log
+    ));
+    assert_eq!((h.hp, hd.remaining, exhaustion), (20, 2, 1));
+
+    h.hp = 0;
+    h.state = LifeState::Unconscious { stable: true };
+    assert!(!long_rest("Hero", &mut h, None, &mut exhaustion, noop_log));
+    assert_eq!(h.hp, 0);
+}
+
+#[test]
+fn temp_hp_take_the_higher_and_absorb_damage_first() {
+    let mut h = Health::new(10);
+    grant_temp_hp("Hero", &mut h, 5, noop_log);
+    grant_temp_hp("Hero", &mut h, 3, noop_log);
+    assert_eq!(h.temp_hp, 5);
+
+    let mut conds = vec![];
+    let mut seen = vec![];
+    apply_damage("Hero", &mut h, &mut conds, 7, false, |s| seen.push(s));
+    assert_eq!((h.hp, h.temp_hp), (8, 0));
+    assert!(seen[0].starts_with("[DMG][Hero] 10 → 8 (−2; temp HP absorbs 5"));
+}
+
+#[test]
+fn damage_below_the_threshold_does_nothing() {
+    let mut h = Health {
+        threshold: 5,
+        ..Health::new(20)
+    };
+    let mut conds = vec![];
+    apply_damage("Door", &mut h, &mut conds, 4, false, noop_log);
+    assert_eq!(h.hp, 20);
+    apply_damage("Door", &mut h, &mut conds, 5, false, noop_log);
+    assert_eq!(h.hp, 15);
+}
+
+#[test]
+fn massive_damage_kills_outright() {
+    let mut h = Health::new(10);
+    h.hp = 4;
+    let mut conds = vec![];
+    assert!(apply_damage(
+        "Hero", &mut h, &mut conds, 13, false, noop_log
+    ));
+    assert!(matches!(h.state, LifeState::Unconscious { stable: false }));
+
+    let mut h = Health::new(10);
+    h.hp = 4;
+    assert!(apply_damage(
+        "Hero", &mut h, &mut conds, 14, false, noop_log
+    ));
+    assert_eq!(h.state, LifeState::Dead);
+}
+
+#[test]
+fn max_hp_reduction_lasts_until_a_long_rest() {
+    let mut h = Health::new(20);
+    assert!(!reduce_max_hp("Hero", &mut h, 8, noop_log));
+    assert_eq!((h.hp, h.effective_max_hp()), (12, 12));
+    h.hp = 2;
+    heal("Hero", &mut h, 50, noop_log);
+    assert_eq!(h.hp, 12);
+
+    let mut exhaustion = 0;
+    long_rest("Hero", &mut h, None, &mut exhaustion, noop_log);
+    assert_eq!((h.hp, h.max_hp_reduction), (20, 0));
+
+    assert!(reduce_max_hp("Hero", &mut h, 25, noop_log));
+    assert_eq!(h.state, LifeState::Dead);
+}
+
+#[test]
+fn damage_at_zero_fails_death_saves() {
+    let mut h = Health::new(10);
+    h.hp = 0;
+    h.state = LifeState::Unconscious { stable: true };
+    let mut conds = vec![];
+    apply_damage("Hero", &mut h, &mut conds, 3, false, noop_log);
+    assert_eq!(h.state, LifeState::Unconscious { stable: false });
+    assert_eq!(h.death.failures, 1);
+
+    apply_damage("Hero", &mut h, &mut conds, 3, true, noop_log);
+    assert_eq!(h.state, LifeState::Dead);
+
+    let mut h = Health::new(10);
+    h.hp = 0;
+    h.state = LifeState::Unconscious { stable: false };
+    apply_damage("Hero", &mut h, &mut conds, 10, false, noop_log);
+    assert_eq!(h.state, LifeState::Dead, "damage ≥ max HP at 0 HP kills");
+}
+
+#[test]
+fn stable_creatures_wake_with_one_hp() {
+    let mut h = Health::new(10);
+    h.hp = 0;
+    h.state = LifeState::Unconscious { stable: false };
+    assert!(!recover_from_stable("Hero", &mut h, 2, noop_log));
+    h.state = LifeState::Unconscious { stable: true };
+    assert!(recover_from_stable("Hero", &mut h, 2, noop_log));
+    assert_eq!((h.hp, h.state), (1, LifeState::Conscious));
+}
+
+#[test]
+fn enemies_finish_off_downed_characters_only_when_told_to() {
+    let hero = |name: &str| {
+        let mut c = common::fighter();
+        c.name = name.to_string();
+        c
+    };
+    let mut downed = hero("Downed");
+    downed.health.hp = 0;
+    downed.health.state = LifeState::Unconscious { stable: true };
+    let orc = Combatant::from_target(
+        &parse_target(include_str!("../../content/targets/orc.json")).unwrap(),
+    );
+    let fight = |downed_policy| {
+        run_combat(
+            vec![hero("Standing"), downed.clone(), orc.clone()],
+            &CombatOptions {
+                max_rounds: 3,
+                downed: downed_policy,
+                ..CombatOptions::default()
+            },
+            &mut Dice::from_seed(8),
+        )
+    };
+
+    let ignored = fight(DownedPolicy::Ignore);
+    assert!(!ignored.log.iter().any(|l| l.starts_with("[AI]")));
+    assert_eq!(ignored.combatants[1].health.death.failures, 0);
+
+    let finished = fight(DownedPolicy::Finish);
+    assert!(finished
+        .log
+        .iter()
+        .any(|l| l == "[AI][Orc] goes after the downed Downed"));
+    assert!(finished
+        .log
+        .iter()
+        .any(|l| l.starts_with("[DEATHSAVE][Downed] takes")));
+}
+
+#[test]
+fn nonlethal_hits_knock_out_instead_of_killing() {
+    let mut h = Health::new(10);
+    h.hp = 3;
+    let mut conds = vec![];
+    let mut seen = vec![];
+    assert!(apply_nonlethal_damage(
+        "Bandit",
+        &mut h,
+        &mut conds,
+        40,
+        |s| seen.push(s)
+    ));
+    assert_eq!(h.state, LifeState::Unconscious { stable: true });
+    assert!(seen[0].ends_with("nonlethal)"));
+    assert!(!apply_nonlethal_damage(
+        "Bandit", &mut h, &mut conds, 2, noop_log
+    ));
+    assert_eq!(h.death.failures, 1, "already down: an ordinary hit at 0 HP");
+}
+
+#[test]
+fn a_knocked_out_monster_dies_to_later_damage() {
+    let hero = common::fighter_with(common::sword(20, 6));
+    let mut bandit = Combatant::from_target(
+        &parse_target(
+            r#"{ "name": "Bandit", "ac": 5, "hp": 1,
+                "attacks": [{ "name": "Club", "to_hit": 0, "dice": { "count": 1, "sides": 4 } }] }"#,
+        )
+        .unwrap(),
+    );
+    bandit.triggers.push(NamedTrigger {
+        name: "Burning".into(),
+        trigger: TurnTrigger::Damage {
+            at: DurationPhase::StartOfTurn,
+            dice: DamageDice::new(1, 4),
+            bonus: 0,
+            damage_type: DamageType::Fire,
+        },
+    });
+    let ogre = Combatant::from_target(
+        &parse_target(
+            r#"{ "name": "Ogre", "ac": 30, "hp": 300,
+                "attacks": [{ "name": "Club", "to_hit": -10, "dice": { "count": 1, "sides": 4 } }] }"#,
+        )
+        .unwrap(),
+    );
+    let out = run_combat(
+        vec![hero, bandit, ogre],
+        &CombatOptions {
+            max_rounds: 3,
+            tactics: Tactics {
+                nonlethal: true,
+                ..Tactics::default()
+            },
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(1),
+    );
+    assert!(
+        out.log.iter().any(|l| l.ends_with("nonlethal)")),
+        "{:#?}",
+        out.log
+    );
+    assert!(out.combatants[1].is_dead(), "{:#?}", out.log);
+    assert!(out.log.contains(&"[ENEMY] Bandit defeated".to_string()));
+}
+
+#[test]
+fn stat_blocks_can_opt_into_death_saves() {
+    let captain = parse_target(
+        r#"{ "name": "Captain", "ac": 10, "hp": 20, "death_saves": true,
+            "attacks": [{ "name": "Club", "to_hit": 0, "dice": { "count": 1, "sides": 4 } }] }"#,
+    )
+    .unwrap();
+    let mut grunt = captain.clone();
+    grunt.death_saves = false;
+    for (block, dies) in [(captain, false), (grunt, true)] {
+        let hero = common::fighter_with(common::sword(20, 6));
+        let out = run_combat(
+            vec![hero, Combatant::from_target(&block)],
+            &CombatOptions::default(),
+            &mut Dice::from_seed(1),
+        );
+        let foe = &out.combatants[1];
+        assert_eq!(foe.is_dead(), dies, "{:#?}", out.log);
+        assert_eq!(foe.is_down(), !dies);
+    }
+}
+
+#[test]
+fn a_solo_character_at_zero_hp_keeps_rolling_death_saves() {
+    let mut hero = common::fighter();
+    hero.health.hp = 0;
+    hero.health.state = LifeState::Unconscious { stable: false };
+    let orc = Combatant::from_target(
+        &parse_target(include_str!("../../content/targets/orc.json")).unwrap(),
+    );
+    let out = run_combat(
+        vec![hero, orc],
+        &CombatOptions::default(),
+        &mut Dice::from_seed(2),
+    );
+    assert!(
+        out.log
+            .iter()
+            .any(|l| l.starts_with("[DEATHSAVE][Sample Fighter] roll=")),
+        "{:#?}",
+        out.log
+    );
+    let h = &out.combatants[0].health;
+    assert!(
+        h.state != (LifeState::Unconscious { stable: false }),
+        "rolls until dead, stable or awake"
+    );
+}
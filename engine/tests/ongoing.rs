@@ -0,0 +1,148 @@
+mod common;
+
+use common::fighter;
+use engine::combat::{run_combat, CombatOptions, Combatant};
+use engine::conditions::{
+    process_turn_boundary, ActiveCondition, ConditionKind, ConditionSpec, DurationPhase,
+    TurnBoundary, TurnTrigger,
+};
+use engine::content::{builtin_effects, parse_content, parse_effects, parse_target};
+use engine::effects::resolve_effects;
+use engine::saves::SaveOutcome;
+use engine::{DamageType, Dice};
+
+fn troll() -> Combatant {
+    Combatant::from_target(&parse_target(include_str!("../../content/targets/troll.json")).unwrap())
+}
+
+fn fight(hero: Combatant, seed: u64) -> Vec<String> {
+    run_combat(
+        vec![hero, troll()],
+        &CombatOptions {
+            max_rounds: 4,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(seed),
+    )
+    .log
+}
+
+#[test]
+fn ongoing_damage_fires_before_the_save_that_ends_it() {
+    let spec: ConditionSpec = parse_content(
+        "condition",
+        r#"{ "kind": "grappled", "duration": { "save_ends_each_turn": true },
+            "save": { "ability": "str", "dc": 12 },
+            "ongoing": [{ "kind": "damage", "at": "end_of_turn",
+                "dice": { "count": 2, "sides": 6 }, "damage_type": "bludgeoning" }] }"#,
+    )
+    .unwrap();
+    let mut conds = vec![ActiveCondition::from_spec_for_application(&spec)];
+    let due = process_turn_boundary(
+        TurnBoundary::StartOfTurn,
+        "Hero",
+        &mut conds,
+        |ctx| SaveOutcome::new(20, 20, ctx.dc),
+        |_| {},
+    );
+    assert!(due.is_empty());
+    let due = process_turn_boundary(
+        TurnBoundary::EndOfTurn,
+        "Hero",
+        &mut conds,
+        |ctx| SaveOutcome::new(20, 20, ctx.dc),
+        |_| {},
+    );
+    assert!(conds.is_empty(), "the save succeeded");
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].name, "Grappled");
+    assert!(matches!(
+        due[0].trigger,
+        TurnTrigger::Damage {
+            at: DurationPhase::EndOfTurn,
+            damage_type: DamageType::Bludgeoning,
+            ..
+        }
+    ));
+    assert_eq!(spec.kind, ConditionKind::Grappled);
+}
+
+#[test]
+fn burning_deals_fire_damage_through_resistances() {
+    let catalog = parse_effects(builtin_effects()["basic"]).unwrap();
+    let mut hero = fighter();
+    hero.effects = resolve_effects(
+        &["burning".to_string(), "protection_from_fire".to_string()],
+        &catalog,
+        "Troll",
+    )
+    .unwrap();
+    let log = fight(hero, 5);
+    let burn = log
+        .iter()
+        .find(|l| l.starts_with("[DMG][Sample Fighter] Burning: 1d4 "))
+        .expect("the hero burned at the start of its turn");
+    assert!(burn.contains("Fire resistance"), "{burn}");
+    assert!(burn.ends_with("[Fire]"));
+}
+
+#[test]
+fn regeneration_heals_unless_fire_or_acid_hit_since_its_last_turn() {
+    let log = fight(fighter(), 2);
+    assert!(log.iter().any(|l| l == "[HEAL][Troll] Regeneration: 10"));
+    assert!(!log.iter().any(|l| l.contains("suppressed")));
+
+    let mut torch = fighter();
+    torch.attacks[0].damage_type = DamageType::Fire;
+    let log = fight(torch, 2);
+    let hit = log
+        .iter()
+        .position(|l| l.starts_with("[HP][Troll] 84 → "))
+        .expect("the troll was burned");
+    let suppressed = log
+        .iter()
+        .position(|l| l == "[HEAL][Troll] Regeneration suppressed (Fire damage)")
+        .expect("fire stopped the regeneration");
+    assert!(hit < suppressed);
+}
+
+#[test]
+fn a_troll_at_zero_hp_dies_only_if_it_cannot_regenerate() {
+    let drop_troll = |damage_type| {
+        let mut hero = common::fighter_with(common::sword(20, 8));
+        hero.attacks[0].damage_type = damage_type;
+        let mut troll = troll();
+        troll.health.hp = 1;
+        let out = run_combat(
+            vec![hero, troll],
+            &CombatOptions {
+                max_rounds: 3,
+                ..CombatOptions::default()
+            },
+            &mut Dice::from_seed(1),
+        );
+        assert!(
+            out.log
+                .contains(&"[ENEMY] Troll drops, but may regenerate".to_string()),
+            "{:#?}",
+            out.log
+        );
+        out
+    };
+
+    let out = drop_troll(DamageType::Slashing);
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[HEAL][Troll] +10 HP (0 → 10) and regains consciousness")));
+    assert!(!out.combatants[1].is_dead());
+
+    let out = drop_troll(DamageType::Fire);
+    let suppressed = out
+        .log
+        .iter()
+        .position(|l| l == "[HEAL][Troll] Regeneration suppressed (Fire damage)")
+        .expect("fire stopped the regeneration");
+    assert_eq!(out.log[suppressed + 1], "[ENEMY] Troll defeated");
+    assert!(out.combatants[1].is_dead());
+}
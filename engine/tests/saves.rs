@@ -0,0 +1,174 @@
+mod common;
+
+use engine::character::{sample_fighter, Class};
+use engine::combat::{run_combat, CombatOptions, Combatant};
+use engine::conditions::{ActiveCondition, ConditionKind, Vantage};
+use engine::content::parse_target;
+use engine::explain::{DiceTerm, Term};
+use engine::features::aura_of_protection;
+use engine::saves::{SaveContext, SaveProfile, SaveScope};
+use engine::{Ability, AdMode, DamageDice, Dice};
+
+fn hero(class: &str, species: &str, level: u8) -> Combatant {
+    common::hero(class, species, level, "longsword")
+}
+
+fn poisoner(dc: i32) -> Combatant {
+    let t = parse_target(&format!(
+        r#"{{ "name": "Spider", "ac": 10, "hp": 200,
+            "attacks": [{{ "name": "Bite", "to_hit": 20, "dice": {{ "count": 1, "sides": 1 }},
+              "apply_condition": {{ "kind": "poisoned", "save": {{ "ability": "con", "dc": {} }} }} }}] }}"#,
+        dc
+    ))
+    .unwrap();
+    Combatant::from_target(&t)
+}
+
+#[test]
+fn profiles_grant_advantage_for_the_right_saves() {
+    let profile = hero("fighter", "dwarf", 1).save_profile();
+    let poison = SaveContext {
+        against: Some(ConditionKind::Poisoned),
+        ..SaveContext::new(Ability::Con, 12)
+    };
+    assert_eq!(profile.mode(&poison), AdMode::Advantage);
+    assert_eq!(
+        profile.mode(&SaveContext::new(Ability::Con, 12)),
+        AdMode::Normal
+    );
+
+    let mut resistant = SaveProfile::default();
+    resistant.grant(SaveScope::Magical, "Magic Resistance", Vantage::Advantage);
+    resistant.grant(SaveScope::All, "exhaustion 3", Vantage::Disadvantage);
+    let spell = SaveContext {
+        magical: true,
+        ..SaveContext::new(Ability::Wis, 15)
+    };
+    assert_eq!(resistant.mode(&spell), AdMode::Normal, "they cancel");
+    assert_eq!(
+        resistant.mode(&SaveContext::new(Ability::Wis, 15)),
+        AdMode::Disadvantage
+    );
+}
+
+#[test]
+fn bonuses_auto_fail_and_legendary_resistance() {
+    let actor = sample_fighter().actor;
+    let con = actor.save_mod(Ability::Con);
+    let mut blessed = SaveProfile {
+        bonuses: vec![Term::new("Aura of Protection", 2)],
+        dice: vec![DiceTerm::new("Bless", DamageDice { count: 1, sides: 4 })],
+        ..SaveProfile::default()
+    };
+    let out = blessed.roll(
+        &mut Dice::from_scripted(vec![10, 3]),
+        &actor,
+        &SaveContext::new(Ability::Con, 10),
+    );
+    assert_eq!(out.total, 10 + con + 2 + 3);
+
+    let mut stunned = SaveProfile {
+        auto_fail: vec![
+            (Ability::Str, "Stunned".to_string()),
+            (Ability::Dex, "Stunned".to_string()),
+        ],
+        legendary_resistance: 1,
+        ..SaveProfile::default()
+    };
+    let ctx = SaveContext::new(Ability::Dex, 1);
+    let first = stunned.roll(&mut Dice::from_scripted(vec![20]), &actor, &ctx);
+    assert!(first.success);
+    assert_eq!(first.note.as_deref(), Some("Legendary Resistance, 0 left"));
+    let second = stunned.roll(&mut Dice::from_scripted(vec![20]), &actor, &ctx);
+    assert!(!second.success);
+    assert_eq!(second.note.as_deref(), Some("automatic failure"));
+}
+
+#[test]
+fn legendary_resistance_is_spent_in_combat() {
+    let mut boss = hero("fighter", "human", 1);
+    boss.legendary_resistance = 1;
+    let opts = CombatOptions {
+        max_rounds: 3,
+        ..CombatOptions::default()
+    };
+    let out = run_combat(vec![boss, poisoner(40)], &opts, &mut Dice::from_seed(5));
+    let resisted: Vec<_> = out
+        .log
+        .iter()
+        .filter(|l| l.starts_with("[SAVE][Hero] resists Poisoned?"))
+        .collect();
+    assert!(resisted[0].ends_with("RESISTED (Legendary Resistance, 0 left)"));
+    assert!(resisted[1].ends_with("FAILED"));
+}
+
+#[test]
+fn stunned_creatures_lose_their_turns() {
+    let t = parse_target(
+        r#"{ "name": "Dazed", "ac": 10, "hp": 30, "conditions": ["stunned"],
+            "attacks": [{ "name": "Poke", "to_hit": 0, "dice": { "count": 1, "sides": 4 }}] }"#,
+    )
+    .unwrap();
+    let opts = CombatOptions {
+        max_rounds: 1,
+        ..CombatOptions::default()
+    };
+    let out = run_combat(
+        vec![hero("fighter", "human", 1), Combatant::from_target(&t)],
+        &opts,
+        &mut Dice::from_seed(1),
+    );
+    assert!(out
+        .log
+        .contains(&"[TURN][Dazed] is stunned; skipping actions".to_string()));
+    assert!(!out.log.iter().any(|l| l.starts_with("[ATTACK][Dazed")));
+}
+
+#[test]
+fn aura_of_protection_needs_sixth_level() {
+    assert_eq!(aura_of_protection(Class::Paladin, 6, 3), 3);
+    assert_eq!(aura_of_protection(Class::Paladin, 6, -1), 1);
+    assert_eq!(aura_of_protection(Class::Paladin, 5, 3), 0);
+    assert_eq!(aura_of_protection(Class::Fighter, 9, 3), 0);
+}
+
+#[test]
+fn restrained_creatures_have_disadvantage_on_dex_saves() {
+    let restrained = [ActiveCondition::indefinite(ConditionKind::Restrained)];
+    let profile = SaveProfile::from_conditions(&restrained);
+    assert_eq!(
+        profile.mode(&SaveContext::new(Ability::Dex, 10)),
+        AdMode::Disadvantage
+    );
+    assert_eq!(
+        profile.mode(&SaveContext::new(Ability::Con, 10)),
+        AdMode::Normal
+    );
+    assert!(hero("fighter", "human", 1)
+        .save_profile()
+        .vantage
+        .is_empty());
+
+    let actor = sample_fighter().actor;
+    let mut dice = Dice::from_scripted(vec![15, 3]);
+    let out = actor.saving_throw_with_conditions(
+        &mut dice,
+        Ability::Dex,
+        &restrained,
+        AdMode::Normal,
+        10,
+    );
+    assert_eq!(out.roll, 3);
+    assert_eq!(out.vantage.sources[0].source, "Restrained");
+
+    let stunned = [ActiveCondition::indefinite(ConditionKind::Stunned)];
+    let out = actor.saving_throw_with_conditions(
+        &mut Dice::from_seed(0),
+        Ability::Str,
+        &stunned,
+        AdMode::Advantage,
+        1,
+    );
+    assert!(!out.success);
+    assert_eq!(out.note.as_deref(), Some("automatic failure"));
+}
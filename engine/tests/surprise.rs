@@ -0,0 +1,78 @@
+mod common;
+
+use engine::combat::{run_combat, CombatOptions, Combatant, Side};
+use engine::content::{parse_encounter, parse_target};
+use engine::Dice;
+
+fn hero(class: &str, level: u8) -> Combatant {
+    common::hero(class, "human", level, "longsword")
+}
+
+fn lurker(stealth: i32) -> Combatant {
+    let t = parse_target(&format!(
+        r#"{{ "name": "Lurker", "ac": 10, "hp": 200, "skills": {{ "stealth": {} }},
+            "attacks": [{{ "name": "Poke", "to_hit": 0, "dice": {{ "count": 1, "sides": 4 }} }}] }}"#,
+        stealth
+    ))
+    .unwrap();
+    Combatant::from_target(&t)
+}
+
+fn ambushed_by(stealth: i32) -> Vec<String> {
+    let opts = CombatOptions {
+        max_rounds: 2,
+        ambush: Some(Side::Enemies),
+        ..CombatOptions::default()
+    };
+    run_combat(
+        vec![hero("fighter", 1), lurker(stealth)],
+        &opts,
+        &mut Dice::from_seed(3),
+    )
+    .log
+}
+
+#[test]
+fn surprised_combatants_lose_their_first_turn() {
+    let log = ambushed_by(30);
+    assert!(log
+        .iter()
+        .any(|l| l.starts_with("[SURPRISE][Lurker] Stealth")));
+    assert!(log
+        .iter()
+        .any(|l| l.starts_with("[SURPRISE][Hero] passive Perception") && l.ends_with("surprised")));
+    let skips = log
+        .iter()
+        .filter(|l| *l == "[TURN][Hero] is surprised; skipping actions")
+        .count();
+    assert_eq!(skips, 1);
+    assert!(
+        log.iter().any(|l| l.starts_with("[ATTACK][Hero")),
+        "acts normally in round 2"
+    );
+
+    let log = ambushed_by(-30);
+    assert!(log.iter().any(|l| l.ends_with("notices the ambush")));
+    assert!(!log.iter().any(|l| l.contains("is surprised")));
+}
+
+#[test]
+fn encounters_mark_the_ambush_side() {
+    let encounter = parse_encounter(include_str!("../../content/encounters/goblin_ambush.json"))
+        .expect("encounter parses");
+    assert_eq!(encounter.ambush, Some(Side::Enemies));
+    let goblin = Combatant::from_target(&encounter.enemies[0]);
+    assert_eq!(goblin.skill_mod(engine::Skill::Stealth), 6);
+    assert_eq!(goblin.passive_perception(), 10);
+
+    let mut surprised = lurker(0);
+    surprised.surprised = true;
+    assert!(!surprised.can_react());
+}
+
+#[test]
+fn feral_instinct_grants_initiative_advantage() {
+    assert!(hero("barbarian", 7).initiative_advantage);
+    assert!(!hero("barbarian", 6).initiative_advantage);
+    assert!(!hero("fighter", 7).initiative_advantage);
+}
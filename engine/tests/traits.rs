@@ -0,0 +1,106 @@
+mod common;
+
+use common::fighter;
+use engine::combat::{run_combat, CombatOptions, CombatOutcome, Combatant};
+use engine::conditions::Vantage;
+use engine::content::parse_target;
+use engine::explain::RollKind;
+use engine::traits::Trait;
+use engine::{Cover, Dice};
+
+fn monster(json: &str) -> Combatant {
+    Combatant::from_target(&parse_target(json).unwrap())
+}
+
+fn fight(mut cs: Vec<Combatant>, sunlight: bool, seed: u64) -> CombatOutcome {
+    cs.insert(0, fighter());
+    run_combat(
+        cs,
+        &CombatOptions {
+            max_rounds: 5,
+            sunlight,
+            ..CombatOptions::default()
+        },
+        &mut Dice::from_seed(seed),
+    )
+}
+
+const KOBOLD: &str = include_str!("../../content/targets/kobold.json");
+
+#[test]
+fn pack_tactics_and_sunlight_sensitivity_show_in_the_attack_vantage() {
+    let out = fight(vec![monster(KOBOLD), monster(KOBOLD)], false, 3);
+    let attack = out
+        .rolls
+        .iter()
+        .find(|r| r.kind == RollKind::Attack && r.roller == "Kobold")
+        .expect("a kobold attacked");
+    assert_eq!(attack.vantage.sources[0].source, "Pack Tactics");
+    assert_eq!(attack.vantage.net(), Vantage::Advantage);
+
+    let out = fight(vec![monster(KOBOLD)], true, 3);
+    let attack = out
+        .rolls
+        .iter()
+        .find(|r| r.kind == RollKind::Attack && r.roller == "Kobold")
+        .expect("the kobold attacked");
+    assert_eq!(attack.vantage.sources[0].source, "Sunlight Sensitivity");
+    assert_eq!(attack.vantage.net(), Vantage::Disadvantage);
+}
+
+#[test]
+fn keen_senses_and_sunlight_move_passive_perception() {
+    let wolf = monster(include_str!("../../content/targets/wolf.json"));
+    assert!(wolf.has_trait(Trait::KeenSenses));
+    assert_eq!(wolf.passive_perception(), 18, "10 + 3 + 5 for advantage");
+
+    let kobold = monster(KOBOLD);
+    assert_eq!(kobold.passive_perception(), 8);
+    assert_eq!(kobold.passive_perception_in(true), 3);
+}
+
+#[test]
+fn goblins_hide_with_nimble_escape_only_behind_cover() {
+    let goblin = || monster(include_str!("../../content/targets/goblin.json"));
+    let in_the_open = fight(vec![goblin()], false, 1);
+    assert!(!in_the_open.log.iter().any(|l| l.contains("Nimble Escape")));
+
+    let mut covered = goblin();
+    covered.cover = Cover::Half;
+    let out = fight(vec![covered], false, 1);
+    assert!(out
+        .log
+        .iter()
+        .any(|l| l.starts_with("[FEATURE][Goblin] Nimble Escape: Hide (Stealth ")));
+}
+
+#[test]
+fn undead_fortitude_keeps_a_zombie_at_one_hp() {
+    let zombie = include_str!("../../content/targets/zombie.json");
+    let saved = (0..20)
+        .map(|seed| fight(vec![monster(zombie)], false, seed).log)
+        .find(|log| {
+            log.iter()
+                .any(|l| l == "[FEATURE][Zombie] Undead Fortitude: drops to 1 HP instead")
+        })
+        .expect("a zombie shrugged off a killing blow");
+    let save = saved
+        .iter()
+        .position(|l| l.starts_with("[SAVE][Zombie] makes a Con save DC "))
+        .unwrap();
+    assert!(saved[save].ends_with("SUCCESS"));
+    assert!(saved[save + 2].starts_with("[HP][Zombie] ") && saved[save + 2].ends_with(" → 1"));
+}
+
+#[test]
+fn brute_adds_a_weapon_die_to_melee_hits() {
+    let bugbear = monster(include_str!("../../content/targets/bugbear.json"));
+    let out = fight(vec![bugbear], false, 2);
+    let hit = out
+        .log
+        .iter()
+        .position(|l| l.starts_with("[FEATURE][Bugbear] Brute 1d8 = "))
+        .expect("the bugbear hit");
+    assert!(out.log[hit - 1].starts_with("[DMG][Bugbear] rolled 1d8 "));
+    assert!(out.log[hit + 1].starts_with("[DMG][Sample Fighter] "));
+}
@@ -0,0 +1,231 @@
+use engine::validate::{validate_content_dir, validate_str, ContentKind};
+
+#[test]
+fn repo_content_is_valid() {
+    let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    for root in [
+        manifest.parent().unwrap().join("content"),
+        manifest.join("content"),
+    ] {
+        let report = validate_content_dir(&root).expect("content dir exists");
+        assert!(report.files_checked > 0);
+        let msgs: Vec<_> = report.issues.iter().map(|i| i.to_string()).collect();
+        assert!(report.is_ok(), "unexpected issues: {:#?}", msgs);
+    }
+}
+
+#[test]
+fn unknown_damage_type_is_reported_with_path_and_hint() {
+    let json = r#"{ "name": "Zombie", "ac": 8, "hp": 22, "resistances": ["poison", "slashng"] }"#;
+    let issues = validate_str(ContentKind::Target, "zombie.json", json);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].file, "zombie.json");
+    assert_eq!(issues[0].path, "/resistances/1");
+    assert!(issues[0].message.contains("did you mean `slashing`"));
+}
+
+#[test]
+fn starting_conditions_must_not_be_immune() {
+    let json = r#"{ "name": "Ghoul", "ac": 12, "hp": 22,
+        "condition_immunities": ["poisoned"], "conditions": ["prone", "poisoned"] }"#;
+    let issues = validate_str(ContentKind::Target, "ghoul.json", json);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "/conditions/1");
+    assert!(issues[0].message.contains("immune"));
+}
+
+#[test]
+fn qualified_defense_cannot_exempt_the_material_it_needs() {
+    let json = r#"{ "name": "Werewolf", "ac": 12, "hp": 58,
+        "qualified_defenses": [{ "defense": "immunity", "types": ["slashing"],
+            "except": "silvered", "material": "silvered" }] }"#;
+    let issues = validate_str(ContentKind::Target, "werewolf.json", json);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "/qualified_defenses/0");
+    assert!(issues[0].message.contains("never applies"));
+}
+
+#[test]
+fn durations_take_rounds_or_minutes() {
+    let json = r#"{ "name": "Ghoul", "ac": 12, "hp": 22,
+        "attacks": [{ "name": "Claws", "to_hit": 4, "dice": { "count": 2, "sides": 4 },
+            "apply_condition": { "kind": "paralyzed", "escape_dc": 12,
+                "duration": { "rounds": 3, "minutes": 1, "until_rest": "long" } } }] }"#;
+    let issues = validate_str(ContentKind::Target, "ghoul.json", json);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(
+        issues[0].path,
+        "/attacks/0/apply_condition/duration/minutes"
+    );
+}
+
+#[test]
+fn schema_errors_point_at_the_field() {
+    let json = r#"{ "name": "Orc", "ac": "15", "hp": 15, "speed": 30 }"#;
+    let issues = validate_str(ContentKind::Target, "orc.json", json);
+    let paths: Vec<_> = issues.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(paths, vec!["/ac", "/speed"]);
+    assert!(issues[0].message.contains("expected integer"));
+    assert!(issues[1].message.contains("unknown property"));
+}
+
+#[test]
+fn impossible_dice_and_negative_hp_in_encounter() {
+    let json = r#"{
+        "name": "Bad",
+        "enemies": [
+            { "name": "A", "ac": 12, "hp": 5,
+              "attacks": [{ "name": "Claw", "to_hit": 3, "dice": { "count": 1, "sides": 7 } }] },
+            { "name": "a", "ac": 12, "hp": 5 }
+        ]
+    }"#;
+    let issues = validate_str(ContentKind::Encounter, "bad.json", json);
+    let paths: Vec<_> = issues.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec!["/enemies/0/attacks/0/dice/sides", "/enemies/1/name"]
+    );
+
+    let negative = r#"{ "enemies": [{ "name": "Ghost", "ac": 11, "hp": -4 }] }"#;
+    let issues = validate_str(ContentKind::Encounter, "neg.json", negative);
+    assert!(issues.iter().any(|i| i.path == "/enemies/0/hp"));
+}
+
+#[test]
+fn duplicate_weapons_and_endless_conditions() {
+    let weapons = r#"[
+        { "name": "dagger", "dice": { "count": 1, "sides": 4 } },
+        { "name": "Dagger", "dice": { "count": 1, "sides": 4 } }
+    ]"#;
+    let issues = validate_str(ContentKind::Weapons, "w.json", weapons);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "/1/name");
+
+    let target = r#"{ "name": "Spider", "ac": 13, "hp": 9, "attacks": [{
+        "name": "Bite", "to_hit": 4, "dice": { "count": 1, "sides": 4 },
+        "apply_condition": { "kind": "poisoned", "duration": { "save_ends_each_turn": true } }
+    }] }"#;
+    let issues = validate_str(ContentKind::Target, "spider.json", target);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(
+        issues[0].path,
+        "/attacks/0/apply_condition/duration/save_ends_each_turn"
+    );
+}
+
+#[test]
+fn item_effects_need_their_own_fields() {
+    let items = r#"[
+        { "id": "tonic", "name": "Tonic", "effect": { "kind": "heal", "damage_type": "fire" } },
+        { "id": "Tonic", "name": "Other tonic" }
+    ]"#;
+    let issues = validate_str(ContentKind::Items, "items.json", items);
+    let msgs: Vec<_> = issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(issues.len(), 3, "{:#?}", msgs);
+    assert!(msgs[0].contains("/0/effect: a `heal` effect needs `dice`"));
+    assert_eq!(issues[1].path, "/0/effect/damage_type");
+    assert_eq!(issues[2].path, "/1/id");
+}
+
+#[test]
+fn campaign_links_and_node_fields() {
+    let campaign = r#"{ "start": "gate", "nodes": [
+        { "kind": "check", "id": "gate", "skill": "stealth", "dc": 12, "success": "den", "failure": "hal" },
+        { "kind": "rest", "id": "hall", "rest": "short", "encounter": "goblin_ambush" },
+        { "kind": "encounter", "id": "den" }
+    ] }"#;
+    let issues = validate_str(ContentKind::Campaign, "c.json", campaign);
+    let msgs: Vec<_> = issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(issues.len(), 3, "{:#?}", msgs);
+    assert_eq!(issues[0].path, "/nodes/0/failure");
+    assert!(issues[0].message.contains("did you mean `hall`"));
+    assert_eq!(issues[1].path, "/nodes/1/encounter");
+    assert!(msgs[2].contains("/nodes/2: a `encounter` node needs `encounter`"));
+}
+
+#[test]
+fn expertise_needs_proficiency_and_skills_must_exist() {
+    let json = r#"{
+        "abilities": { "str": 10, "dex": 16, "con": 12, "int": 10, "wis": 12, "cha": 10 },
+        "proficiency_bonus": 2,
+        "save_proficiencies": ["dex"],
+        "skill_proficiencies": ["stealth"],
+        "expertise": ["stealth", "perception"]
+    }"#;
+    let issues = validate_str(ContentKind::Actor, "rogue.json", json);
+    assert_eq!(issues.len(), 1, "{:?}", issues);
+    assert_eq!(issues[0].path, "/expertise/1");
+
+    let json = r#"{ "name": "Scout", "ac": 13, "hp": 16, "skills": { "stelth": 6 } }"#;
+    let issues = validate_str(ContentKind::Target, "scout.json", json);
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].message.contains("did you mean `stealth`"));
+}
+
+#[test]
+fn effect_modifiers_must_fit_their_stat() {
+    let json = r#"[
+        { "id": "odd", "name": "Odd", "modifiers": [
+            { "kind": "dice", "to": "ac", "dice": { "count": 1, "sides": 4 } },
+            { "kind": "advantage", "on": "damage" },
+            { "kind": "resistance", "damage_type": "fire", "value": 2 }
+        ], "duration": { "save_ends_each_turn": true } },
+        { "id": "ODD", "name": "Again", "modifiers": [{ "kind": "bonus", "to": "save" }] }
+    ]"#;
+    let issues = validate_str(ContentKind::Effects, "effects.json", json);
+    let paths: Vec<_> = issues.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/0/duration/save_ends_each_turn",
+            "/0/modifiers/0/to",
+            "/0/modifiers/1/on",
+            "/0/modifiers/2/value",
+            "/1/id",
+            "/1/modifiers/0",
+        ]
+    );
+}
+
+#[test]
+fn triggers_need_the_fields_of_their_kind() {
+    let json = r#"[
+        { "id": "empty", "name": "Empty" },
+        { "id": "burn", "name": "Burn", "triggers": [
+            { "kind": "damage", "at": "start_of_turn", "dice": { "count": 1, "sides": 4 }, "amount": 3 },
+            { "kind": "heal", "at": "end_of_turn", "amount": 5, "suppressed_by": ["fire"] }
+        ] }
+    ]"#;
+    let issues = validate_str(ContentKind::Effects, "effects.json", json);
+    let paths: Vec<_> = issues.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(paths, vec!["/0", "/1/triggers/0", "/1/triggers/0/amount"]);
+    assert!(issues[1].message.contains("needs `damage_type`"));
+}
+
+#[test]
+fn legendary_and_lair_actions_are_checked_against_the_stat_block() {
+    let json = r#"{ "name": "Wyrm", "ac": 18, "hp": 200,
+        "attacks": [{ "name": "Tail", "to_hit": 10, "dice": { "count": 2, "sides": 8 } }],
+        "legendary_actions": { "per_round": 2, "actions": [
+            { "name": "Tail Attack", "effect": { "kind": "attack", "attack": "Tails" } },
+            { "name": "Wing Attack", "cost": 3, "effect": { "kind": "save",
+                "save": { "ability": "dex", "dc": 19 }, "dice": { "count": 2, "sides": 6 } } }
+        ] },
+        "lair_actions": [{ "name": "Tremor", "effect": { "kind": "save",
+            "save": { "ability": "dex", "dc": 15 }, "attack": "Tail" } }] }"#;
+    let issues = validate_str(ContentKind::Target, "wyrm.json", json);
+    let paths: Vec<_> = issues.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/legendary_actions/actions/0/effect/attack",
+            "/legendary_actions/actions/1/cost",
+            "/legendary_actions/actions/1/effect",
+            "/lair_actions/0/effect/attack",
+            "/lair_actions/0/effect",
+        ]
+    );
+    assert!(issues[0].message.contains("did you mean `Tail`"));
+    assert!(issues[2].message.contains("needs `damage_type`"));
+    assert!(issues[4].message.contains("needs `dice` or `condition`"));
+}
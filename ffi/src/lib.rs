@@ -1,6 +1,9 @@
 use engine::api::{
-    simulate_duel, simulate_duel_many, simulate_encounter, DuelConfig, EncounterConfig,
+    run_campaign, simulate_duel, simulate_duel_many, simulate_encounter, step_campaign,
+    CampaignConfig, DuelConfig, EncounterConfig, MAX_SAMPLES,
 };
+use engine::content::{parse_content, parse_content_value};
+use engine::error::EngineError;
 use jni::objects::{JClass, JString};
 use jni::sys::{jint, jlong, jstring};
 use jni::JNIEnv;
@@ -13,12 +16,31 @@ fn ok(env: &JNIEnv, value: serde_json::Value) -> jstring {
         .into_raw()
 }
 
-fn err(env: &JNIEnv, e: impl std::fmt::Display) -> jstring {
-    env.new_string(format!(r#"{{"ok":false,"error":"{}"}}"#, e))
+/// Error envelope: `{"ok":false,"code":..,"error":..}` plus `field` when the failure
+/// points into the submitted JSON, and `line`/`column` when they are known.
+fn error_envelope(e: &EngineError) -> serde_json::Value {
+    let mut payload = json!({ "ok": false, "code": e.code(), "error": e.to_string() });
+    if let EngineError::Parse(p) = e {
+        payload["field"] = json!(p.field);
+        if p.line > 0 {
+            payload["line"] = json!(p.line);
+            payload["column"] = json!(p.column);
+        }
+    }
+    payload
+}
+
+fn err(env: &JNIEnv, e: impl Into<EngineError>) -> jstring {
+    let payload = error_envelope(&e.into());
+    env.new_string(serde_json::to_string(&payload).unwrap())
         .unwrap()
         .into_raw()
 }
 
+fn bad_input(e: impl std::fmt::Display) -> EngineError {
+    EngineError::InvalidConfig(format!("could not read input string: {}", e))
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_solo5e_Ffi_version<'local>(
     env: JNIEnv<'local>,
@@ -71,11 +93,11 @@ pub extern "system" fn Java_com_solo5e_Ffi_simulateDuelJson(
 ) -> jstring {
     let input: String = match env.get_string(&json) {
         Ok(s) => s.into(),
-        Err(e) => return err(&env, e),
+        Err(e) => return err(&env, bad_input(e)),
     };
-    let cfg: DuelConfig = match serde_json::from_str(&input) {
+    let cfg: DuelConfig = match parse_content("duel config", &input) {
         Ok(c) => c,
-        Err(e) => return err(&env, format!("invalid_config: {}", e)),
+        Err(e) => return err(&env, e),
     };
     match simulate_duel(cfg) {
         Ok(result) => ok(&env, serde_json::to_value(result).unwrap()),
@@ -83,6 +105,24 @@ pub extern "system" fn Java_com_solo5e_Ffi_simulateDuelJson(
     }
 }
 
+/// Remove `samples` from a duel-many request: 100 when absent, otherwise a whole
+/// number no larger than `MAX_SAMPLES`.
+fn take_samples(root: &mut serde_json::Value) -> Result<u32, EngineError> {
+    let Some(value) = root.as_object_mut().and_then(|obj| obj.remove("samples")) else {
+        return Ok(100);
+    };
+    value
+        .as_u64()
+        .and_then(|n| u32::try_from(n).ok())
+        .filter(|&n| n <= MAX_SAMPLES)
+        .ok_or_else(|| {
+            EngineError::InvalidConfig(format!(
+                "samples must be a whole number from 0 to {}, got {}",
+                MAX_SAMPLES, value
+            ))
+        })
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_solo5e_Ffi_simulateDuelManyJson(
     mut env: JNIEnv,
@@ -91,19 +131,20 @@ pub extern "system" fn Java_com_solo5e_Ffi_simulateDuelManyJson(
 ) -> jstring {
     let input: String = match env.get_string(&json) {
         Ok(s) => s.into(),
-        Err(e) => return err(&env, e),
+        Err(e) => return err(&env, bad_input(e)),
     };
-    let mut root: serde_json::Value = match serde_json::from_str(&input) {
+    let mut root: serde_json::Value = match parse_content("duel config", &input) {
         Ok(v) => v,
-        Err(e) => return err(&env, format!("invalid_config: {}", e)),
+        Err(e) => return err(&env, e),
     };
-    let samples = root.get("samples").and_then(|v| v.as_u64()).unwrap_or(100) as u32;
-    if let Some(obj) = root.as_object_mut() {
-        obj.remove("samples");
-    }
-    let cfg: DuelConfig = match serde_json::from_value(root) {
+    let samples = match take_samples(&mut root) {
+        Ok(n) => n,
+        Err(e) => return err(&env, e),
+    };
+    // The value no longer matches the caller's text, so errors carry the field only.
+    let cfg: DuelConfig = match parse_content_value("duel config", root) {
         Ok(c) => c,
-        Err(e) => return err(&env, format!("invalid_config: {}", e)),
+        Err(e) => return err(&env, e),
     };
     match simulate_duel_many(cfg, samples) {
         Ok(stats) => ok(&env, serde_json::to_value(stats).unwrap()),
@@ -119,11 +160,11 @@ pub extern "system" fn Java_com_solo5e_Ffi_simulateEncounterJson(
 ) -> jstring {
     let input: String = match env.get_string(&json) {
         Ok(s) => s.into(),
-        Err(e) => return err(&env, e),
+        Err(e) => return err(&env, bad_input(e)),
     };
-    let cfg: EncounterConfig = match serde_json::from_str(&input) {
+    let cfg: EncounterConfig = match parse_content("encounter config", &input) {
         Ok(c) => c,
-        Err(e) => return err(&env, format!("invalid_config: {}", e)),
+        Err(e) => return err(&env, e),
     };
     match simulate_encounter(cfg) {
         Ok(result) => ok(&env, serde_json::to_value(result).unwrap()),
@@ -131,6 +172,47 @@ pub extern "system" fn Java_com_solo5e_Ffi_simulateEncounterJson(
     }
 }
 
+/// One campaign node per call: pass back the returned `state` in the next config.
+#[no_mangle]
+pub extern "system" fn Java_com_solo5e_Ffi_campaignStepJson(
+    mut env: JNIEnv,
+    _class: JClass,
+    json: JString,
+) -> jstring {
+    let input: String = match env.get_string(&json) {
+        Ok(s) => s.into(),
+        Err(e) => return err(&env, bad_input(e)),
+    };
+    let cfg: CampaignConfig = match parse_content("campaign config", &input) {
+        Ok(c) => c,
+        Err(e) => return err(&env, e),
+    };
+    match step_campaign(cfg) {
+        Ok(step) => ok(&env, serde_json::to_value(step).unwrap()),
+        Err(e) => err(&env, e),
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_solo5e_Ffi_campaignRunJson(
+    mut env: JNIEnv,
+    _class: JClass,
+    json: JString,
+) -> jstring {
+    let input: String = match env.get_string(&json) {
+        Ok(s) => s.into(),
+        Err(e) => return err(&env, bad_input(e)),
+    };
+    let cfg: CampaignConfig = match parse_content("campaign config", &input) {
+        Ok(c) => c,
+        Err(e) => return err(&env, e),
+    };
+    match run_campaign(cfg) {
+        Ok(result) => ok(&env, serde_json::to_value(result).unwrap()),
+        Err(e) => err(&env, e),
+    }
+}
+
 // Internal functions for testing without JNI overhead
 pub fn roll_internal(seed: i64, n: i32, sides: i32) -> i32 {
     let mut state = seed as u64;
@@ -166,4 +248,62 @@ mod tests {
         assert_eq!(roll_internal(42, 0, 6), 0); // No rolls
         assert_eq!(roll_internal(42, 1, 1), 1); // Single-sided die
     }
+
+    #[test]
+    fn test_error_envelope_is_valid_json() {
+        let e = EngineError::InvalidConfig("bad \"quote\"\nand newline".to_string());
+        let text = serde_json::to_string(&error_envelope(&e)).unwrap();
+        let back: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(back["ok"], false);
+        assert_eq!(back["code"], "invalid_config");
+        assert!(back["error"].as_str().unwrap().contains("\"quote\"\n"));
+    }
+
+    #[test]
+    fn test_samples_must_be_within_the_limit() {
+        let mut absent = json!({ "weapon": "x" });
+        assert_eq!(take_samples(&mut absent).unwrap(), 100);
+        let mut given = json!({ "weapon": "x", "samples": 250 });
+        assert_eq!(take_samples(&mut given).unwrap(), 250);
+        assert!(given.get("samples").is_none());
+        let mut most = json!({ "samples": MAX_SAMPLES });
+        assert_eq!(take_samples(&mut most).unwrap(), MAX_SAMPLES);
+        for bad in [
+            json!(-1),
+            json!(2.5),
+            json!("100"),
+            json!(MAX_SAMPLES + 1),
+            json!(u64::from(u32::MAX) + 1),
+        ] {
+            let mut root = json!({ "samples": bad });
+            let e = take_samples(&mut root).unwrap_err();
+            assert_eq!(e.code(), "invalid_config");
+        }
+    }
+
+    #[test]
+    fn test_duel_many_errors_name_the_field_without_a_position() {
+        let mut root: serde_json::Value = parse_content(
+            "duel config",
+            "{\n  \"samples\": 10,\n  \"weapon\": \"x\",\n  \"seed\": \"one\"\n}",
+        )
+        .unwrap();
+        take_samples(&mut root).unwrap();
+        let e = parse_content_value::<DuelConfig>("duel config", root).unwrap_err();
+        let env = error_envelope(&e.into());
+        assert_eq!(env["code"], "parse_error");
+        assert_eq!(env["field"], "seed");
+        assert!(env.get("line").is_none());
+        assert!(env.get("column").is_none());
+    }
+
+    #[test]
+    fn test_error_envelope_parse_location() {
+        let e = parse_content::<DuelConfig>("duel config", r#"{ "weapon": "x", "seed": "one" }"#)
+            .unwrap_err();
+        let env = error_envelope(&e.into());
+        assert_eq!(env["code"], "parse_error");
+        assert_eq!(env["field"], "seed");
+        assert_eq!(env["line"], 1);
+    }
 }